@@ -0,0 +1,129 @@
+//! 染色体の評価速度 (evaluations/sec) を計測する
+//!
+//! $ cargo run --release --example evaluation_benchmark -- data/input/la40.txt
+//!
+//! インスタンスを指定しない場合は、la40 と同じ規模 (15 job x 15 actor) の
+//! ランダムなインスタンスで計測する
+//! (job-shop の染色体を semi-active デコードするため、FJSP・オープンショップは扱わない)
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use sandbox::optimization::decoder::flat::{FlatInstance, SwapEvaluator};
+use sandbox::optimization::decoder::score::{
+    Decoder, ScoreCalculator, ScoreEvaluator, SemiActiveDecoder,
+};
+use sandbox::optimization::preprocess::instance_format;
+use sandbox::optimization::preprocess::runner::JobMaster;
+use sandbox::optimization::types::{Chromosome, Time};
+
+const MEASURE_DURATION: Duration = Duration::from_secs(2);
+
+/// la40 と同じ規模のランダムなインスタンス
+fn random_job_master(rng: &mut ChaCha8Rng) -> JobMaster {
+    let (job_size, machine_series_size): (usize, usize) = (15, 15);
+    let exec_times: Vec<Vec<Time>> = (0..job_size)
+        .map(|_| {
+            (0..machine_series_size)
+                .map(|_| rng.random_range(1..100))
+                .collect()
+        })
+        .collect();
+    let actor_sequences: Vec<Vec<u16>> = (0..job_size)
+        .map(|_| {
+            let mut actors: Vec<u16> =
+                (0..machine_series_size as u16).collect();
+            actors.shuffle(rng);
+            actors
+        })
+        .collect();
+    JobMaster::try_new(
+        job_size,
+        machine_series_size,
+        exec_times,
+        actor_sequences,
+    )
+    .unwrap()
+}
+
+/// MEASURE_DURATION の間 evaluate を繰り返し、1秒あたりの評価回数を出力する
+fn measure(name: &str, mut evaluate: impl FnMut() -> Time) {
+    let start: Instant = Instant::now();
+    let mut count: u64 = 0;
+    while start.elapsed() < MEASURE_DURATION {
+        for _ in 0..1_000 {
+            black_box(evaluate());
+        }
+        count += 1_000;
+    }
+    let per_sec: f64 = count as f64 / start.elapsed().as_secs_f64();
+    println!("{:<36} {:>14.0} evaluations/sec", name, per_sec);
+}
+
+fn main() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(40);
+    let job_master: JobMaster = match std::env::args_os().nth(1) {
+        Some(path) => {
+            instance_format::load_instance(&path).unwrap_or_else(|err| {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            })
+        }
+        None => random_job_master(&mut rng),
+    };
+    if job_master.is_flexible() || job_master.open_shop {
+        eprintln!(
+            "Error: the benchmark only supports job-shop instances (got a flexible or open-shop instance)"
+        );
+        std::process::exit(1);
+    }
+    println!(
+        "instance: {} jobs x {} machines",
+        job_master.job_size, job_master.machine_series_size
+    );
+
+    // 各 job を operation 数だけ並べた染色体
+    let mut chromosome: Chromosome = (0..job_master.job_size)
+        .flat_map(|job| {
+            std::iter::repeat_n(job as u16, job_master.operation_count(job))
+        })
+        .collect();
+    chromosome.shuffle(&mut rng);
+    let length: usize = chromosome.len();
+
+    measure("decode (full schedule)", || {
+        SemiActiveDecoder.decode(&job_master, &chromosome).makespan
+    });
+
+    measure("SemiActiveDecoder::makespan", || {
+        SemiActiveDecoder.makespan(&job_master, &chromosome)
+    });
+
+    let calculator: ScoreCalculator = ScoreCalculator::new(job_master.clone());
+    let mut scorer: ScoreEvaluator = calculator.evaluator();
+    measure("ScoreEvaluator::calc_makespan", || {
+        scorer.calc_makespan(&chromosome)
+    });
+
+    let Some(instance) = FlatInstance::new(&job_master) else {
+        println!("FlatInstance: skipped (the instance cannot be flattened)");
+        return;
+    };
+    let mut state = instance.state();
+    measure("FlatInstance::makespan", || {
+        instance.makespan(&chromosome, &mut state)
+    });
+
+    let interval: usize = (length as f64).sqrt().ceil() as usize;
+    let mut evaluator: SwapEvaluator =
+        SwapEvaluator::new(&instance, &chromosome, interval);
+    measure("SwapEvaluator::swap_makespan", || {
+        let first: usize = rng.random_range(0..length);
+        let second: usize = rng.random_range(0..length);
+        evaluator.swap_makespan(first, second)
+    });
+}
@@ -3,7 +3,6 @@
 // #[macro_use]
 // extern crate ndarray_linalg;
 
-// pub mod genetic;
 pub mod modules;
 pub mod norm_module;
 pub mod optimization;
@@ -1,27 +1,29 @@
-mod norm_module;
-use norm_module::norm::sampling_norm;
+use std::error::Error;
+use std::fs;
+use std::process;
 
 // mod modules;
 // use modules::trait_impl_trial::{iterate_turn, Agent};
 
-mod optimization;
-use optimization::preprocess;
-use optimization::preprocess::runner::JobMaster;
-use optimization::runner::la40;
+use sandbox::optimization::decoder::score::Evaluation;
+use sandbox::optimization::preprocess::best_known;
+use sandbox::optimization::preprocess::calendar::Interruption;
+use sandbox::optimization::preprocess::instance_format::{
+    self, InstanceFormat,
+};
+use sandbox::optimization::preprocess::runner::JobMaster;
+use sandbox::optimization::runner::la40::{self, Solution, SolveOptions};
+use sandbox::optimization::runner::{evaluation, report};
+use sandbox::optimization::types::Time;
 // use optimization::initializer;
 
-mod utilities;
-use utilities::file_readers;
+use sandbox::utilities::cli::{self, Command, InstanceArgs, OutputArgs};
+use sandbox::utilities::file_readers;
+use sandbox::utilities::gantt;
+use sandbox::utilities::solution_file::{self, ImportedSolution, SolutionFile};
 
 fn main() {
-    // 対数と指数関数の計算
-    let x: f64 = 2.716;
-    let y: f64 = x.ln();
-    println!("ln(x): {}", y);
-    println!("exp(y): {}", y.exp());
-
-    let vec_rand: Vec<f64> = sampling_norm(10.0, 5.0, 5);
-    println!("vec_rand: {vec_rand:?}");
+    let command: Command = cli::parse();
 
     // initializer::init();
 
@@ -34,10 +36,185 @@ fn main() {
     /* --------------------------------
     最適化処理のサンプル実装
     -------------------------------- */
-    let (file_path, delimiter, delimiter_str) = file_readers::parse_args();
-    let (header, row_list) =
-        file_readers::read_csv(file_path, delimiter, delimiter_str);
-    let job_master: JobMaster = preprocess::runner::run(header, row_list);
+    let result: Result<(), Box<dyn Error>> = match command {
+        Command::Solve {
+            instance,
+            options,
+            output,
+        } => load_job_master(&instance).and_then(|job_master| {
+            let solution: Solution = la40::run(job_master.clone(), &options)?;
+            // 変換して解いた問題の解は、元の問題の既知の最良値と比べられない
+            let best_known: Option<Time> =
+                if solution.problem == la40::native_problem(&job_master) {
+                    best_known(&instance)?
+                } else {
+                    None
+                };
+            // 下界は変換後のインスタンス (実際に解いた問題) で求める
+            report::report_gap(
+                &solution.job_master,
+                solution.evaluation.makespan,
+                best_known,
+            );
+            write_outputs(&instance, &options, &solution, &output)
+        }),
+        Command::Evaluate {
+            instance,
+            solution,
+            objective,
+            decoder,
+        } => load_job_master(&instance).and_then(|job_master| {
+            let imported: ImportedSolution =
+                solution_file::read_solution(&solution)?;
+            // 指定がなければ解ファイルに記録された設定、それもなければ既定値を使う
+            let defaults: SolveOptions = SolveOptions::default();
+            let objective: String = objective
+                .or(imported.objective)
+                .unwrap_or(defaults.objective);
+            let decoder: String =
+                decoder.or(imported.decoder).unwrap_or(defaults.decoder);
+            let evaluation: Evaluation = evaluation::evaluate(
+                job_master.clone(),
+                &imported.chromosome,
+                imported.operations,
+                &objective,
+                &decoder,
+            )?;
+            report::report_gap(
+                &job_master,
+                evaluation.makespan,
+                best_known(&instance)?,
+            );
+            Ok(())
+        }),
+        Command::Info { instance } => {
+            load_job_master(&instance).and_then(|job_master| {
+                report::info(&job_master, best_known(&instance)?);
+                Ok(())
+            })
+        }
+    };
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+/// インスタンスファイルと、job 属性 (納期など)・段取り時間・稼働カレンダーを読み込む
+fn load_job_master(
+    instance: &InstanceArgs,
+) -> Result<JobMaster, Box<dyn Error>> {
+    let job_master: JobMaster =
+        match file_readers::delimiter_from_name(&instance.format) {
+            Some(delimiter) => {
+                let (header, row_list) = file_readers::read_csv(
+                    instance.path.clone(),
+                    delimiter,
+                    instance.format.clone(),
+                )?;
+                JobMaster::try_from_records(header, &row_list)?
+            }
+            None => {
+                // OR-Library / Taillard 形式のインスタンス
+                let format: Option<InstanceFormat> =
+                    InstanceFormat::from_name(&instance.format);
+                let text: String = file_readers::read_text(&instance.path)?;
+                instance_format::parse_instance(&text, format)?
+            }
+        };
+
+    let job_master: JobMaster = match &instance.job_attributes {
+        Some(path) => {
+            let (header, row_list) = file_readers::read_csv(
+                path.clone(),
+                b',',
+                "comma".to_string(),
+            )?;
+            job_master.try_with_job_attributes(header, &row_list)?
+        }
+        None => job_master,
+    };
+
+    let job_master: JobMaster = match &instance.setup_times {
+        Some(path) => {
+            let (header, row_list) = file_readers::read_csv(
+                path.clone(),
+                b',',
+                "comma".to_string(),
+            )?;
+            job_master.try_with_setup_times(header, &row_list)?
+        }
+        None => job_master,
+    };
+
+    match &instance.calendar {
+        Some(path) => {
+            let (header, row_list) = file_readers::read_csv(
+                path.clone(),
+                b',',
+                "comma".to_string(),
+            )?;
+            // NOTE: 名前は clap の possible_values で検証済み
+            let interruption: Interruption =
+                Interruption::from_name(&instance.interruption)
+                    .unwrap_or_default();
+            Ok(
+                job_master.try_with_calendar(
+                    header,
+                    &row_list,
+                    interruption,
+                )?,
+            )
+        }
+        None => Ok(job_master),
+    }
+}
+
+/// インスタンスの既知の最良値 (--best-known の表、なければ組み込みの表から探す)
+fn best_known(instance: &InstanceArgs) -> Result<Option<Time>, Box<dyn Error>> {
+    let extra: Vec<(String, Time)> = match &instance.best_known {
+        Some(path) => {
+            let text: String = fs::read_to_string(path)
+                .map_err(|err| format!("cannot read {:?}: {}", path, err))?;
+            best_known::parse_best_known_table(&text)
+        }
+        None => Vec::new(),
+    };
+    Ok(best_known::best_known_makespan(
+        &best_known::instance_name(&instance.path),
+        &extra,
+    ))
+}
 
-    la40::run(job_master);
+/// 最良解を output で指定された先に書き出す
+fn write_outputs(
+    instance: &InstanceArgs,
+    options: &SolveOptions,
+    solution: &Solution,
+    output: &OutputArgs,
+) -> Result<(), Box<dyn Error>> {
+    if output.gantt {
+        print!(
+            "{}",
+            gantt::render_ascii(&solution.schedule, gantt::terminal_width())
+        );
+    }
+    if let Some(path) = &output.svg {
+        fs::write(
+            path,
+            gantt::render_svg(&solution.job_master, &solution.schedule),
+        )
+        .map_err(|err| format!("cannot write {:?}: {}", path, err))?;
+        println!("[INFO] wrote the Gantt chart to {:?}", path);
+    }
+    if output.solutions.is_empty() {
+        return Ok(());
+    }
+    let solution_file: SolutionFile =
+        SolutionFile::new(&instance.path.to_string_lossy(), options, solution)?;
+    for path in output.solutions.iter() {
+        solution_file::write_solution(path, &solution_file)?;
+        println!("[INFO] wrote the best solution to {:?}", path);
+    }
+    Ok(())
 }
@@ -0,0 +1,365 @@
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::optimization::decoder::score::{
+    Decoder, ScoreCalculator, SemiActiveDecoder,
+};
+use crate::optimization::preprocess::lower_bound::{
+    jackson_preemptive, LowerBounds, Relaxed,
+};
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types;
+
+static DEFAULT_TIME_LIMIT: f32 = 600.0; // seconds
+/// 何ノードごとに経過時間を確認するか
+static TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// 分枝限定法の設定
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BranchAndBoundConfig {
+    /// 実行時間の上限 (秒)。超えた場合はそれまでの最良解を返す
+    pub time_limit: f32,
+}
+
+impl Default for BranchAndBoundConfig {
+    fn default() -> Self {
+        BranchAndBoundConfig {
+            time_limit: DEFAULT_TIME_LIMIT,
+        }
+    }
+}
+
+/// 分枝限定法の結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchAndBoundResult {
+    /// 最良解 (semi-active デコードすると makespan になる)
+    pub chromosome: types::Chromosome,
+    pub makespan: types::Time,
+    /// 証明できた makespan の下界 (optimal の場合は makespan と一致する)
+    pub lower_bound: types::Time,
+    /// 探索を打ち切らずに終えた (= makespan が最適値である) か
+    pub optimal: bool,
+    /// 調べたノード数
+    pub nodes: u64,
+}
+
+/// 探索の途中状態 (Giffler-Thompson 法で operation を1つずつ割り当てる)
+///
+/// actor ごとの処理順を先頭から確定していくため、各ノードは
+/// 選言グラフの disjunctive arc の一部を固定した部分問題に対応する
+struct Search<'a> {
+    job_master: &'a JobMaster,
+    /// tails[job][operation]: operation の後に job 内で残る実行時間の合計
+    tails: Vec<Vec<types::Time>>,
+    next_operations: Vec<usize>,
+    job_ready_times: Vec<types::Time>,
+    actor_ready_times: Vec<types::Time>,
+    sequence: types::Chromosome,
+    best_solution: types::Chromosome,
+    upper_bound: types::Time,
+    /// 根ノードの下界 (上界がこれに達したら探索を終える)
+    root_bound: types::Time,
+    nodes: u64,
+    start: Instant,
+    time_limit: f32,
+    timed_out: bool,
+    /// 下界計算用の作業領域 (actor ごとの 1機械緩和)
+    relaxed: Vec<Vec<Relaxed>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(job_master: &'a JobMaster, time_limit: f32) -> Self {
+        let tails: Vec<Vec<types::Time>> = job_master
+            .exec_times
+            .iter()
+            .map(|times| {
+                let mut tail: types::Time = times.iter().sum();
+                times
+                    .iter()
+                    .map(|time| {
+                        tail -= time;
+                        tail
+                    })
+                    .collect()
+            })
+            .collect();
+        Search {
+            job_master,
+            tails,
+            next_operations: vec![0; job_master.job_size],
+            job_ready_times: (0..job_master.job_size)
+                .map(|job| job_master.release_date(job))
+                .collect(),
+            actor_ready_times: vec![0; job_master.machine_series_size],
+            sequence: Vec::new(),
+            best_solution: Vec::new(),
+            upper_bound: types::Time::MAX,
+            root_bound: 0,
+            nodes: 0,
+            start: Instant::now(),
+            time_limit,
+            timed_out: false,
+            relaxed: vec![Vec::new(); job_master.machine_series_size],
+        }
+    }
+
+    /// job の次の operation の (actor, 最早開始時刻, 実行時間)
+    fn next_operation(&self, job: usize) -> (usize, types::Time, types::Time) {
+        let operation: usize = self.next_operations[job];
+        let actor: usize =
+            self.job_master.actor_sequences[job][operation] as usize;
+        let start: types::Time =
+            self.job_ready_times[job].max(self.actor_ready_times[actor]);
+        (actor, start, self.job_master.exec_times[job][operation])
+    }
+
+    /// 現在のノードの下界
+    /// 未割り当ての operation について、actor ごとに head / tail 付きの
+    /// 1機械緩和を Jackson の preemptive schedule で解いた値の最大値
+    fn lower_bound(&mut self) -> types::Time {
+        for operations in self.relaxed.iter_mut() {
+            operations.clear();
+        }
+        for job in 0..self.job_master.job_size {
+            let mut head: types::Time = self.job_ready_times[job];
+            for operation in
+                self.next_operations[job]..self.job_master.machine_series_size
+            {
+                let actor: usize =
+                    self.job_master.actor_sequences[job][operation] as usize;
+                let time: types::Time =
+                    self.job_master.exec_times[job][operation];
+                head = head.max(self.actor_ready_times[actor]);
+                self.relaxed[actor].push(Relaxed {
+                    head,
+                    time,
+                    tail: self.tails[job][operation],
+                });
+                head += time;
+            }
+        }
+        self.relaxed
+            .iter_mut()
+            .zip(self.actor_ready_times.iter())
+            .map(|(operations, ready)| {
+                (*ready).max(jackson_preemptive(operations))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// job の次の operation を割り当てる (戻り値は元に戻すための値)
+    fn assign(&mut self, job: usize) -> (types::Time, types::Time) {
+        let (actor, start, time) = self.next_operation(job);
+        let saved: (types::Time, types::Time) =
+            (self.job_ready_times[job], self.actor_ready_times[actor]);
+        self.job_ready_times[job] = start + time;
+        self.actor_ready_times[actor] = start + time;
+        self.next_operations[job] += 1;
+        self.sequence.push(job as u16);
+        saved
+    }
+
+    fn unassign(&mut self, job: usize, saved: (types::Time, types::Time)) {
+        self.sequence.pop();
+        self.next_operations[job] -= 1;
+        let (actor, _, _) = self.next_operation(job);
+        self.job_ready_times[job] = saved.0;
+        self.actor_ready_times[actor] = saved.1;
+    }
+
+    /// 深さ優先で探索する (下界の小さい子ノードから調べる)
+    fn branch(&mut self) {
+        // 根ノードでも確認する (時間制限 0 の場合は探索しない)
+        if self.nodes.is_multiple_of(TIME_CHECK_INTERVAL)
+            && self.time_limit < self.start.elapsed().as_secs_f32()
+        {
+            self.timed_out = true;
+        }
+        self.nodes += 1;
+        if self.timed_out || self.upper_bound <= self.root_bound {
+            return;
+        }
+
+        // 最早終了時刻が最小の operation と同じ actor で、
+        // その終了時刻より前に開始できる operation に分岐する (active schedule)
+        let candidates: Vec<(usize, usize, types::Time, types::Time)> = (0
+            ..self.job_master.job_size)
+            .filter(|job| {
+                self.next_operations[*job] < self.job_master.machine_series_size
+            })
+            .map(|job| {
+                let (actor, start, time) = self.next_operation(job);
+                (job, actor, start, start + time)
+            })
+            .collect();
+        let (_, conflict_actor, _, earliest_end) = match candidates
+            .iter()
+            .min_by_key(|(job, _, _, end)| (*end, *job))
+        {
+            Some(candidate) => *candidate,
+            None => {
+                // 全 operation を割り当てた
+                let makespan: types::Time =
+                    self.actor_ready_times.iter().copied().max().unwrap_or(0);
+                if makespan < self.upper_bound {
+                    self.upper_bound = makespan;
+                    self.best_solution.clone_from(&self.sequence);
+                }
+                return;
+            }
+        };
+
+        let mut children: Vec<(types::Time, usize)> = Vec::new();
+        for (job, actor, start, _) in candidates {
+            if actor != conflict_actor || earliest_end <= start {
+                continue;
+            }
+            let saved: (types::Time, types::Time) = self.assign(job);
+            let bound: types::Time = self.lower_bound();
+            self.unassign(job, saved);
+            if bound < self.upper_bound {
+                children.push((bound, job));
+            }
+        }
+        children.sort();
+        for (bound, job) in children {
+            // 兄弟ノードの探索中に上界が更新されている場合がある
+            if self.upper_bound <= bound {
+                continue;
+            }
+            let saved: (types::Time, types::Time) = self.assign(job);
+            self.branch();
+            self.unassign(job, saved);
+            if self.timed_out || self.upper_bound <= self.root_bound {
+                return;
+            }
+        }
+    }
+}
+
+/// 分枝限定法で makespan 最小のスケジュールを求める
+///
+/// initial (任意) を semi-active デコードした makespan を最初の上界とする。
+/// 時間内に探索を終えた場合は optimal = true で、makespan が最適値である
+pub fn solve(
+    job_master: &JobMaster,
+    config: &BranchAndBoundConfig,
+    initial: Option<&types::Chromosome>,
+) -> BranchAndBoundResult {
+    // initial がない場合は job 番号を順に並べた解を初期解とする
+    let initial: types::Chromosome = initial.cloned().unwrap_or_else(|| {
+        (0..job_master.machine_series_size)
+            .flat_map(|_| 0..job_master.job_size as u16)
+            .collect()
+    });
+    let mut search: Search = Search::new(job_master, config.time_limit);
+    // 上界 + 1 とし、同じ makespan の解も探索で見つけられるようにする
+    search.upper_bound = SemiActiveDecoder.makespan(job_master, &initial) + 1;
+    search.root_bound = search
+        .lower_bound()
+        .max(LowerBounds::new(job_master).best());
+    search.branch();
+
+    let (chromosome, makespan) = if search.best_solution.is_empty() {
+        // initial より良い解が見つからなかった
+        (initial, search.upper_bound - 1)
+    } else {
+        (search.best_solution, search.upper_bound)
+    };
+    let optimal: bool = !search.timed_out;
+    BranchAndBoundResult {
+        chromosome,
+        makespan,
+        lower_bound: if optimal { makespan } else { search.root_bound },
+        optimal,
+        nodes: search.nodes,
+    }
+}
+
+/// 分枝限定法を実行する (目的関数は makespan のみ)
+///
+/// 戻り値は simulated_annealing::run と同じく
+/// (最良解, その makespan, そのスコア, 調べたノード数)
+pub fn run(
+    chromosome: &types::Chromosome,
+    calculator: ScoreCalculator,
+    config: &BranchAndBoundConfig,
+) -> (types::Chromosome, types::Time, f64, u32) {
+    let result: BranchAndBoundResult =
+        solve(calculator.job_master(), config, Some(chromosome));
+    println!(
+        "[INFO] optimal: {:?}, lower_bound: {:?}, nodes: {:?}",
+        result.optimal, result.lower_bound, result.nodes
+    );
+    let score: f64 = calculator.makespan_to_score(result.makespan);
+    (
+        result.chromosome,
+        result.makespan,
+        score,
+        result.nodes.min(u32::MAX as u64) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::preprocess::instance_format::parse_or_library;
+    use crate::optimization::test_fixtures::{sample_job_master, FT06};
+
+    #[test]
+    fn test_solve_small_instance() {
+        // 3 job x 2 actor: 全列挙で最適値は 12
+        let job_master = sample_job_master();
+        let result = solve(&job_master, &BranchAndBoundConfig::default(), None);
+        assert!(result.optimal);
+        assert_eq!(result.makespan, 12);
+        assert_eq!(result.lower_bound, 12);
+        assert_eq!(
+            SemiActiveDecoder.makespan(&job_master, &result.chromosome),
+            12
+        );
+    }
+
+    #[test]
+    fn test_solve_proves_ft06_optimum() {
+        let job_master = parse_or_library(FT06).unwrap();
+        let result = solve(&job_master, &BranchAndBoundConfig::default(), None);
+        assert!(result.optimal);
+        assert_eq!(result.makespan, 55);
+        assert_eq!(
+            SemiActiveDecoder.makespan(&job_master, &result.chromosome),
+            55
+        );
+    }
+
+    #[test]
+    fn test_solve_with_optimal_initial_solution() {
+        let job_master = parse_or_library(FT06).unwrap();
+        let optimal =
+            solve(&job_master, &BranchAndBoundConfig::default(), None);
+        // 初期解が最適な場合も、その解を最適解として返す
+        let result = solve(
+            &job_master,
+            &BranchAndBoundConfig::default(),
+            Some(&optimal.chromosome),
+        );
+        assert!(result.optimal);
+        assert_eq!(result.makespan, 55);
+    }
+
+    #[test]
+    fn test_solve_stops_at_time_limit() {
+        let job_master = parse_or_library(FT06).unwrap();
+        let result =
+            solve(&job_master, &BranchAndBoundConfig { time_limit: 0.0 }, None);
+        assert!(!result.optimal);
+        assert!(result.lower_bound <= 55);
+        // 探索しなくても初期解を返す
+        assert_eq!(
+            SemiActiveDecoder.makespan(&job_master, &result.chromosome),
+            result.makespan
+        );
+    }
+}
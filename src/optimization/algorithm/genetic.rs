@@ -0,0 +1,539 @@
+use crate::optimization::algorithm::neighborhood::Neighborhood;
+use crate::optimization::decoder::score::{
+    Evaluation, ScoreCalculator, ScoreEvaluator,
+};
+use crate::optimization::types;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use serde::Serialize;
+use std::time::Instant;
+
+/// 親の選択方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Selection {
+    /// size 個体をランダムに選び、最もスコアの高い個体を選ぶ
+    Tournament { size: usize },
+    /// スコアに比例した確率で選ぶ
+    Roulette,
+}
+
+/// 重複順列 (operation-based 表現) に対する交叉
+///
+/// いずれの交叉も、子の各 job の出現回数は親と同じになる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Crossover {
+    /// Job-based Order Crossover:
+    /// 各 job を確率 1/2 で選び、選んだ job の遺伝子は親1の位置のまま、
+    /// 残りの位置は親2に現れる順で埋める
+    Jox,
+    /// Precedence Operation Crossover:
+    /// job 集合を空でない2つの集合に分け、一方の job の遺伝子は親1の位置のまま、
+    /// 残りの位置は親2に現れる順で埋める
+    Pox,
+    /// Generalized Order Crossover:
+    /// 親2の部分列を親1に埋め込み、部分列と同じ operation を親1から取り除く
+    Gox,
+    /// Precedence Preservative Crossover:
+    /// ランダムなマスクに従い、親1・親2の先頭から順に operation を取り出す
+    Ppx,
+}
+
+static DEFAULT_POPULATION_SIZE: usize = 100;
+static DEFAULT_GENERATIONS: u32 = 1_000;
+static DEFAULT_CROSSOVER_RATE: f64 = 0.9;
+static DEFAULT_MUTATION_RATE: f64 = 0.1;
+static DEFAULT_TOURNAMENT_SIZE: usize = 3;
+static DEFAULT_ELITE_SIZE: usize = 2;
+static DEFAULT_TIME_LIMIT: f32 = 600.0; // seconds
+
+/// 遺伝的アルゴリズムの設定
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GeneticConfig {
+    pub population_size: usize,
+    /// 最大世代数
+    pub generations: u32,
+    pub selection: Selection,
+    pub crossover: Crossover,
+    /// 選んだ親の組に交叉を適用する確率
+    pub crossover_rate: f64,
+    /// 子に突然変異 (近傍の移動) を適用する確率
+    pub mutation_rate: f64,
+    /// そのまま次の世代に残す上位個体の数
+    pub elite_size: usize,
+    /// 実行時間の上限 (秒)
+    pub time_limit: f32,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        GeneticConfig {
+            population_size: DEFAULT_POPULATION_SIZE,
+            generations: DEFAULT_GENERATIONS,
+            selection: Selection::Tournament {
+                size: DEFAULT_TOURNAMENT_SIZE,
+            },
+            crossover: Crossover::Pox,
+            crossover_rate: DEFAULT_CROSSOVER_RATE,
+            mutation_rate: DEFAULT_MUTATION_RATE,
+            elite_size: DEFAULT_ELITE_SIZE,
+            time_limit: DEFAULT_TIME_LIMIT,
+        }
+    }
+}
+
+/// 指定できる交叉名
+pub const CROSSOVER_NAMES: [&str; 4] = ["jox", "pox", "gox", "ppx"];
+
+impl Crossover {
+    /// 名前から交叉を取得 (未知の名前の場合は None)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "jox" => Some(Crossover::Jox),
+            "pox" => Some(Crossover::Pox),
+            "gox" => Some(Crossover::Gox),
+            "ppx" => Some(Crossover::Ppx),
+            _ => None,
+        }
+    }
+
+    /// 2つの親から子を1つ生成する
+    pub fn apply(
+        &self,
+        parent1: &types::Chromosome,
+        parent2: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        let job_size: usize = parent1
+            .iter()
+            .map(|job| *job as usize + 1)
+            .max()
+            .unwrap_or(0);
+        match self {
+            Crossover::Jox => {
+                let kept: Vec<bool> =
+                    (0..job_size).map(|_| rng.random_bool(0.5)).collect();
+                order_crossover(parent1, parent2, &kept)
+            }
+            Crossover::Pox => {
+                let mut kept: Vec<bool> = vec![false; job_size];
+                if 1 < job_size {
+                    // 空でない真部分集合を選ぶ
+                    let mut jobs: Vec<usize> = (0..job_size).collect();
+                    jobs.shuffle(rng);
+                    let kept_size: usize = rng.random_range(1..job_size);
+                    for job in &jobs[..kept_size] {
+                        kept[*job] = true;
+                    }
+                }
+                order_crossover(parent1, parent2, &kept)
+            }
+            Crossover::Gox => {
+                if parent2.is_empty() {
+                    return parent1.clone();
+                }
+                let start: usize = rng.random_range(0..parent2.len());
+                let end: usize = rng.random_range(start..parent2.len()) + 1;
+                generalized_order_crossover(parent1, parent2, start, end)
+            }
+            Crossover::Ppx => {
+                let mask: Vec<bool> =
+                    (0..parent1.len()).map(|_| rng.random_bool(0.5)).collect();
+                precedence_preservative_crossover(parent1, parent2, &mask)
+            }
+        }
+    }
+}
+
+impl Selection {
+    /// "tournament", "tournament:5", "roulette" の形式の指定を解釈する
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, parameter) = match spec.split_once(':') {
+            Some((name, parameter)) => (name, Some(parameter)),
+            None => (spec, None),
+        };
+        match (name.trim().to_lowercase().as_str(), parameter) {
+            ("tournament", None) => Ok(Selection::Tournament {
+                size: DEFAULT_TOURNAMENT_SIZE,
+            }),
+            ("tournament", Some(size)) => size
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|size| 0 < *size)
+                .map(|size| Selection::Tournament { size })
+                .ok_or_else(|| format!("invalid tournament size {:?}", size)),
+            ("roulette", None) => Ok(Selection::Roulette),
+            _ => Err(format!(
+                "unknown selection {:?} (expected tournament[:size] or roulette)",
+                spec
+            )),
+        }
+    }
+
+    /// スコアに基づいて親の index を1つ選ぶ
+    fn select(&self, scores: &[f64], rng: &mut dyn RngCore) -> usize {
+        match self {
+            Selection::Tournament { size } => (0..*size)
+                .map(|_| rng.random_range(0..scores.len()))
+                .max_by(|a, b| scores[*a].total_cmp(&scores[*b]))
+                .unwrap_or(0),
+            Selection::Roulette => {
+                let total: f64 = scores.iter().sum();
+                if total <= 0.0 {
+                    return rng.random_range(0..scores.len());
+                }
+                let mut threshold: f64 = rng.random::<f64>() * total;
+                for (index, score) in scores.iter().enumerate() {
+                    threshold -= score;
+                    if threshold < 0.0 {
+                        return index;
+                    }
+                }
+                scores.len() - 1
+            }
+        }
+    }
+}
+
+/// kept[job] が true の job の遺伝子は parent1 の位置のまま残し、
+/// 残りの位置を parent2 に現れる順 (kept でない job の遺伝子) で埋める
+fn order_crossover(
+    parent1: &types::Chromosome,
+    parent2: &types::Chromosome,
+    kept: &[bool],
+) -> types::Chromosome {
+    let mut filler = parent2.iter().filter(|job| !kept[**job as usize]);
+    parent1
+        .iter()
+        .map(|job| {
+            if kept[*job as usize] {
+                *job
+            } else {
+                *filler.next().expect("parents must have the same genes")
+            }
+        })
+        .collect()
+}
+
+/// 各遺伝子が job の何番目の operation か (出現回数) を求める
+fn occurrences(chromosome: &types::Chromosome) -> Vec<usize> {
+    let job_size: usize = chromosome
+        .iter()
+        .map(|job| *job as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let mut counts: Vec<usize> = vec![0; job_size];
+    chromosome
+        .iter()
+        .map(|job| {
+            counts[*job as usize] += 1;
+            counts[*job as usize] - 1
+        })
+        .collect()
+}
+
+/// parent2[start..end] を parent1 に埋め込む
+///
+/// 部分列に含まれる operation (job, 出現回数) を parent1 から取り除き、
+/// 部分列の先頭の operation が parent1 にあった位置に部分列を挿入する
+fn generalized_order_crossover(
+    parent1: &types::Chromosome,
+    parent2: &types::Chromosome,
+    start: usize,
+    end: usize,
+) -> types::Chromosome {
+    let occurrences1: Vec<usize> = occurrences(parent1);
+    let occurrences2: Vec<usize> = occurrences(parent2);
+    let implanted: Vec<(u16, usize)> = (start..end)
+        .map(|index| (parent2[index], occurrences2[index]))
+        .collect();
+
+    let mut rest: types::Chromosome = Vec::with_capacity(parent1.len());
+    let mut insert_at: usize = 0;
+    for (index, job) in parent1.iter().enumerate() {
+        let operation: (u16, usize) = (*job, occurrences1[index]);
+        if operation == implanted[0] {
+            insert_at = rest.len();
+        }
+        if !implanted.contains(&operation) {
+            rest.push(*job);
+        }
+    }
+
+    let mut child: types::Chromosome = rest[..insert_at].to_vec();
+    child.extend(implanted.iter().map(|(job, _)| *job));
+    child.extend_from_slice(&rest[insert_at..]);
+    child
+}
+
+/// mask[i] が true なら parent1、false なら parent2 の先頭に残っている
+/// operation を取り出して子に追加し、もう一方の親からも同じ operation を取り除く
+fn precedence_preservative_crossover(
+    parent1: &types::Chromosome,
+    parent2: &types::Chromosome,
+    mask: &[bool],
+) -> types::Chromosome {
+    let job_size: usize = parent1
+        .iter()
+        .map(|job| *job as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let occurrences1: Vec<usize> = occurrences(parent1);
+    let occurrences2: Vec<usize> = occurrences(parent2);
+    // job ごとに子へ取り出した operation の数
+    let mut taken: Vec<usize> = vec![0; job_size];
+    let mut cursors: [usize; 2] = [0, 0];
+    let parents: [(&types::Chromosome, &Vec<usize>); 2] =
+        [(parent1, &occurrences1), (parent2, &occurrences2)];
+
+    let mut child: types::Chromosome = Vec::with_capacity(parent1.len());
+    for use_first in mask.iter().take(parent1.len()) {
+        let side: usize = if *use_first { 0 } else { 1 };
+        let (parent, occurrence) = parents[side];
+        // 取り出し済みの operation を読み飛ばす
+        while occurrence[cursors[side]] < taken[parent[cursors[side]] as usize]
+        {
+            cursors[side] += 1;
+        }
+        let job: u16 = parent[cursors[side]];
+        taken[job as usize] += 1;
+        child.push(job);
+    }
+    child
+}
+
+/// 遺伝的アルゴリズムで解を探索する
+///
+/// chromosome と、それをシャッフルした個体で初期集団を作る
+/// mutation は突然変異に使う近傍 (swap, insertion など)
+/// 戻り値は simulated_annealing::run と同じく
+/// (最良解, その makespan, そのスコア, 世代数)
+pub fn run(
+    chromosome: &types::Chromosome,
+    calculator: ScoreCalculator,
+    mutation: &dyn Neighborhood,
+    config: &GeneticConfig,
+    rng: &mut dyn RngCore,
+) -> (types::Chromosome, types::Time, f64, u32) {
+    let start: Instant = Instant::now();
+    let population_size: usize = config.population_size.max(2);
+    let elite_size: usize = config.elite_size.min(population_size);
+
+    // 初期集団
+    let mut population: Vec<types::Chromosome> = vec![chromosome.clone()];
+    while population.len() < population_size {
+        let mut individual: types::Chromosome = chromosome.clone();
+        individual.shuffle(rng);
+        population.push(individual);
+    }
+    let mut scorer: ScoreEvaluator = calculator.evaluator();
+    let mut evaluations: Vec<Evaluation> = population
+        .iter()
+        .map(|individual| scorer.evaluate(individual))
+        .collect();
+
+    let mut best_solution: types::Chromosome = chromosome.clone();
+    let mut best_evaluation: Evaluation = scorer.evaluate(chromosome);
+
+    let mut generation_num: u32 = 0;
+    for _generation in 0..config.generations {
+        // 最良個体の更新
+        for (individual, evaluation) in population.iter().zip(&evaluations) {
+            if best_evaluation.score < evaluation.score {
+                best_evaluation = *evaluation;
+                best_solution = individual.clone();
+                println!(
+                    "generation: {:?}, score: {:?}, makespan: {:?}, objective: {:?}",
+                    _generation,
+                    best_evaluation.score,
+                    best_evaluation.makespan,
+                    best_evaluation.objective_value
+                );
+            }
+        }
+
+        if config.time_limit < start.elapsed().as_secs_f32() {
+            break;
+        }
+        generation_num += 1;
+
+        let scores: Vec<f64> = evaluations
+            .iter()
+            .map(|evaluation| evaluation.score)
+            .collect();
+
+        // エリート保存
+        let mut ranking: Vec<usize> = (0..population_size).collect();
+        ranking.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+        let mut next_population: Vec<types::Chromosome> = ranking[..elite_size]
+            .iter()
+            .map(|index| population[*index].clone())
+            .collect();
+        let mut next_evaluations: Vec<Evaluation> = ranking[..elite_size]
+            .iter()
+            .map(|index| evaluations[*index])
+            .collect();
+
+        while next_population.len() < population_size {
+            let parent1: &types::Chromosome =
+                &population[config.selection.select(&scores, rng)];
+            let parent2: &types::Chromosome =
+                &population[config.selection.select(&scores, rng)];
+            let mut children: Vec<types::Chromosome> =
+                if rng.random::<f64>() < config.crossover_rate {
+                    vec![
+                        config.crossover.apply(parent1, parent2, rng),
+                        config.crossover.apply(parent2, parent1, rng),
+                    ]
+                } else {
+                    vec![parent1.clone(), parent2.clone()]
+                };
+            for child in children.iter_mut() {
+                if rng.random::<f64>() < config.mutation_rate {
+                    *child = mutation.neighbor(child, rng);
+                }
+            }
+            for child in children {
+                if next_population.len() < population_size {
+                    next_evaluations.push(scorer.evaluate(&child));
+                    next_population.push(child);
+                }
+            }
+        }
+        population = next_population;
+        evaluations = next_evaluations;
+    }
+
+    // 最終世代の最良個体
+    for (individual, evaluation) in population.iter().zip(&evaluations) {
+        if best_evaluation.score < evaluation.score {
+            best_evaluation = *evaluation;
+            best_solution = individual.clone();
+        }
+    }
+
+    (
+        best_solution,
+        best_evaluation.makespan,
+        best_evaluation.score,
+        generation_num,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::algorithm::neighborhood::SwapNeighborhood;
+    use crate::optimization::test_fixtures::sample_job_master;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn sorted(chromosome: &types::Chromosome) -> types::Chromosome {
+        let mut sorted: types::Chromosome = chromosome.clone();
+        sorted.sort();
+        sorted
+    }
+
+    #[test]
+    fn test_crossovers_preserve_genes() {
+        let parent1: types::Chromosome = vec![0, 1, 2, 3, 0, 1, 2, 3, 0, 1];
+        let parent2: types::Chromosome = vec![3, 3, 1, 1, 1, 0, 0, 0, 2, 2];
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for name in CROSSOVER_NAMES {
+            let crossover = Crossover::from_name(name).unwrap();
+            for _ in 0..100 {
+                let child = crossover.apply(&parent1, &parent2, &mut rng);
+                assert_eq!(sorted(&child), sorted(&parent1));
+            }
+        }
+        assert_eq!(Crossover::from_name("ox"), None);
+    }
+
+    #[test]
+    fn test_order_crossover() {
+        // job 0 の位置は親1のまま、job 1, 2 は親2の順で埋める
+        let parent1: types::Chromosome = vec![0, 1, 2, 0, 2, 1];
+        let parent2: types::Chromosome = vec![2, 2, 1, 0, 1, 0];
+        let child = order_crossover(&parent1, &parent2, &[true, false, false]);
+        assert_eq!(child, vec![0, 2, 2, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_generalized_order_crossover() {
+        // 親2の部分列 [1(1回目), 0(1回目)] を、親1で 1(1回目) があった位置に埋め込む
+        let parent1: types::Chromosome = vec![0, 1, 2, 0, 2, 1];
+        let parent2: types::Chromosome = vec![2, 1, 0, 2, 1, 0];
+        let child = generalized_order_crossover(&parent1, &parent2, 1, 3);
+        assert_eq!(child, vec![1, 0, 2, 0, 2, 1]);
+    }
+
+    #[test]
+    fn test_precedence_preservative_crossover() {
+        let parent1: types::Chromosome = vec![0, 1, 2, 0, 2, 1];
+        let parent2: types::Chromosome = vec![2, 2, 1, 0, 1, 0];
+        assert_eq!(
+            precedence_preservative_crossover(&parent1, &parent2, &[true; 6]),
+            parent1
+        );
+        assert_eq!(
+            precedence_preservative_crossover(&parent1, &parent2, &[false; 6]),
+            parent2
+        );
+        let mask: [bool; 6] = [true, false, false, true, true, false];
+        assert_eq!(
+            precedence_preservative_crossover(&parent1, &parent2, &mask),
+            vec![0, 2, 2, 1, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_selection() {
+        let scores: Vec<f64> = vec![0.1, 0.0, 0.9];
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        // 集団全体を大きく上回るトーナメントでは、ほぼ必ず最良個体が選ばれる
+        let tournament = Selection::Tournament { size: 64 };
+        assert_eq!(tournament.select(&scores, &mut rng), 2);
+        // スコア 0 の個体はルーレットで選ばれない
+        for _ in 0..100 {
+            assert_ne!(Selection::Roulette.select(&scores, &mut rng), 1);
+        }
+
+        assert_eq!(
+            Selection::parse("tournament:5"),
+            Ok(Selection::Tournament { size: 5 })
+        );
+        assert_eq!(Selection::parse("roulette"), Ok(Selection::Roulette));
+        assert!(Selection::parse("tournament:0").is_err());
+        assert!(Selection::parse("rank").is_err());
+    }
+
+    #[test]
+    fn test_run_improves_and_is_reproducible() {
+        let job_master = sample_job_master();
+        let config = GeneticConfig {
+            population_size: 20,
+            generations: 30,
+            ..GeneticConfig::default()
+        };
+        let run_with_seed = |seed: u64| {
+            let chromosome: types::Chromosome = vec![0, 2, 2, 0, 1, 1];
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            run(
+                &chromosome,
+                ScoreCalculator::new(job_master.clone()),
+                &SwapNeighborhood,
+                &config,
+                &mut rng,
+            )
+        };
+        let (solution, makespan, score, generation_num) = run_with_seed(5);
+        // 初期解 (makespan 17) より良い解が見つかる
+        assert!(makespan < 17);
+        assert_eq!(generation_num, 30);
+        assert_eq!(
+            ScoreCalculator::new(job_master.clone()).calc_makespan(&solution),
+            makespan
+        );
+        assert_eq!(run_with_seed(5), (solution, makespan, score, 30));
+    }
+}
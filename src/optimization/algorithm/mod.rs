@@ -1 +1,7 @@
+pub mod branch_and_bound;
+pub mod genetic;
+pub mod neh;
+pub mod neighborhood;
+pub mod parallel;
 pub mod simulated_annealing;
+pub mod tabu_search;
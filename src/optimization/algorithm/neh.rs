@@ -0,0 +1,52 @@
+use std::cmp::Reverse;
+
+use crate::optimization::decoder::flow_shop::insertion_makespans;
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types;
+
+/// NEH 法 (Nawaz-Enscore-Ham) で順列フローショップの解を構築する
+///
+/// 実行時間の合計が大きい job から順に、それまでの部分順列のうち
+/// makespan が最小になる位置 (同じ場合は前の位置) に挿入する
+pub fn run(job_master: &JobMaster) -> types::Chromosome {
+    let mut jobs: Vec<u16> = (0..job_master.job_size as u16).collect();
+    // 合計の降順 (同じ場合は job 番号順)
+    jobs.sort_by_key(|job| {
+        let total: types::Time =
+            job_master.exec_times[*job as usize].iter().sum();
+        (Reverse(total), *job)
+    });
+
+    let mut sequence: types::Chromosome = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let makespans: Vec<types::Time> =
+            insertion_makespans(job_master, &sequence, job);
+        let position: usize = makespans
+            .iter()
+            .enumerate()
+            .min_by_key(|(position, makespan)| (**makespan, *position))
+            .map_or(0, |(position, _)| position);
+        sequence.insert(position, job);
+    }
+    sequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::flow_shop::PermutationDecoder;
+    use crate::optimization::decoder::score::Decoder;
+    use crate::optimization::test_fixtures::flow_shop_job_master;
+
+    #[test]
+    fn test_run() {
+        let job_master = flow_shop_job_master();
+        let sequence: types::Chromosome = run(&job_master);
+        let mut sorted: types::Chromosome = sequence.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3]);
+        // この例では総当たりの最適値 43 に一致する
+        assert_eq!(sequence, vec![3, 1, 0, 2]);
+        assert_eq!(PermutationDecoder.makespan(&job_master, &sequence), 43);
+    }
+}
@@ -0,0 +1,744 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use serde::Serialize;
+
+use crate::optimization::decoder::disjunctive_graph::{
+    DisjunctiveGraph, GraphSchedule, MachineMove, OperationId,
+};
+use crate::optimization::decoder::schedule::Schedule;
+use crate::optimization::decoder::score::{Decoder, SemiActiveDecoder};
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types;
+
+/// 染色体から近傍解を1つ生成する移動操作
+pub trait Neighborhood: Send + Sync {
+    /// 近傍の名前 (コマンドライン引数などで指定する名前)
+    fn name(&self) -> &'static str;
+
+    /// 近傍解を1つ生成する (元の染色体は変更しない)
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome;
+
+    /// 近傍解への移動を1つ選ぶ
+    ///
+    /// 2つの位置の交換で表せる移動は NeighborMove::Swap で返すと、
+    /// 染色体を複製せずに差分評価できる (flat::SwapEvaluator)
+    /// neighbor と同じ乱数の消費で、同じ近傍解を選ぶこと
+    fn propose(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> NeighborMove {
+        NeighborMove::Chromosome(self.neighbor(chromosome, rng))
+    }
+}
+
+/// Neighborhood::propose で選んだ移動
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NeighborMove {
+    /// 2つの位置の遺伝子を交換する
+    Swap(usize, usize),
+    /// 1つ目の位置の遺伝子を取り除き、取り除いた後の2つ目の位置に挿入する
+    Insert(usize, usize),
+    /// 生成した近傍解
+    Chromosome(types::Chromosome),
+}
+
+impl NeighborMove {
+    /// chromosome に移動を適用する
+    pub fn apply(&self, chromosome: &mut types::Chromosome) {
+        match self {
+            NeighborMove::Swap(first, second) => {
+                chromosome.swap(*first, *second)
+            }
+            NeighborMove::Insert(from, to) => {
+                let gene: u16 = chromosome.remove(*from);
+                chromosome.insert(*to, gene);
+            }
+            NeighborMove::Chromosome(new_chromo) => {
+                chromosome.clone_from(new_chromo)
+            }
+        }
+    }
+
+    /// apply で適用した移動を取り消す (Chromosome は元に戻せないため対象外)
+    pub fn revert(&self, chromosome: &mut types::Chromosome) {
+        match self {
+            NeighborMove::Swap(first, second) => {
+                chromosome.swap(*first, *second)
+            }
+            NeighborMove::Insert(from, to) => {
+                let gene: u16 = chromosome.remove(*to);
+                chromosome.insert(*from, gene);
+            }
+            NeighborMove::Chromosome(_) => {
+                unreachable!("a generated chromosome cannot be reverted")
+            }
+        }
+    }
+
+    /// 移動後の染色体を返す
+    pub fn into_chromosome(
+        self,
+        chromosome: &types::Chromosome,
+    ) -> types::Chromosome {
+        match self {
+            NeighborMove::Chromosome(new_chromo) => new_chromo,
+            proposal => {
+                let mut new_chromo: types::Chromosome = chromosome.clone();
+                proposal.apply(&mut new_chromo);
+                new_chromo
+            }
+        }
+    }
+}
+
+/// ランダムに選んだ2つの遺伝子を交換する
+pub struct SwapNeighborhood;
+
+/// ランダムに選んだ遺伝子を取り出し、別の位置に挿入する
+pub struct InsertionNeighborhood;
+
+/// ランダムに選んだ隣り合う2つの遺伝子を交換する
+pub struct AdjacentSwapNeighborhood;
+
+/// ランダムに選んだ区間の遺伝子の並びを反転する
+pub struct ReversalNeighborhood;
+
+/// クリティカルブロックに基づく近傍の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CriticalBlockMoves {
+    /// Nowicki–Smutnicki の N5 (ブロック境界の隣接 operation の入れ替え)
+    N5,
+    /// Zhang らの N7 (ブロックの先頭・末尾への移動と、先頭・末尾からの移動)
+    N7,
+}
+
+/// semi-active デコードしたスケジュールのクリティカルパスを求め、
+/// クリティカルブロック上の移動で近傍解を生成する
+///
+/// ランダムな入れ替えと異なり、makespan を改善しうる移動だけを選ぶ
+/// 移動後の処理順は、トポロジカル順に並べ直した染色体として返す
+/// (semi-active 以外のデコーダで評価する場合は使えない。la40::check_graph_support を参照)
+pub struct CriticalBlockNeighborhood {
+    job_master: JobMaster,
+    moves: CriticalBlockMoves,
+}
+
+/// 複数の近傍を重み (選択確率) に従ってランダムに使い分ける
+pub struct MixedNeighborhood {
+    neighborhoods: Vec<(Box<dyn Neighborhood>, f64)>,
+}
+
+/// FJSP の染色体 (前半: operation 順, 後半: actor の選択) の近傍
+///
+/// assignment_rate の確率で operation を1つ選んで actor の選択を変え、
+/// それ以外は sequence の近傍で前半 (operation 順) を変える
+/// 前半の近傍はクリティカルパスを使わないもの (swap など) に限る
+pub struct FlexibleNeighborhood {
+    sequence: Box<dyn Neighborhood>,
+    /// operation ごとの選択肢の数 (染色体の後半と同じ順)
+    choice_counts: Vec<u16>,
+    /// 選択肢が2つ以上ある operation の番号
+    flexible_operations: Vec<usize>,
+    assignment_rate: f64,
+}
+
+/// FlexibleNeighborhood で actor の選択を変える確率の既定値
+pub const DEFAULT_ASSIGNMENT_RATE: f64 = 0.5;
+
+/// 指定できる近傍名
+pub const NEIGHBORHOOD_NAMES: [&str; 6] =
+    ["swap", "insertion", "adjacent-swap", "reversal", "n5", "n7"];
+
+/// 名前から近傍を取得 (未知の名前の場合は None)
+/// N5/N7 はクリティカルパスの計算に job_master を使う
+pub fn neighborhood_from_name(
+    name: &str,
+    job_master: &JobMaster,
+) -> Option<Box<dyn Neighborhood>> {
+    match name.trim().to_lowercase().as_str() {
+        "swap" => Some(Box::new(SwapNeighborhood)),
+        "insertion" => Some(Box::new(InsertionNeighborhood)),
+        "adjacent-swap" => Some(Box::new(AdjacentSwapNeighborhood)),
+        "reversal" => Some(Box::new(ReversalNeighborhood)),
+        "n5" => Some(Box::new(CriticalBlockNeighborhood::new(
+            job_master.clone(),
+            CriticalBlockMoves::N5,
+        ))),
+        "n7" => Some(Box::new(CriticalBlockNeighborhood::new(
+            job_master.clone(),
+            CriticalBlockMoves::N7,
+        ))),
+        _ => None,
+    }
+}
+
+/// 近傍の指定を解釈する
+///
+/// - "swap" のように名前1つの場合はその近傍
+/// - "swap:0.5,insertion:0.3,reversal:0.2" のように "名前:重み" を
+///   カンマ区切りで並べた場合は MixedNeighborhood
+pub fn parse_neighborhood(
+    spec: &str,
+    job_master: &JobMaster,
+) -> Result<Box<dyn Neighborhood>, String> {
+    if !spec.contains(',') && !spec.contains(':') {
+        return neighborhood_from_name(spec, job_master).ok_or_else(|| {
+            format!(
+                "unknown neighborhood {:?} (expected one of {:?})",
+                spec, NEIGHBORHOOD_NAMES
+            )
+        });
+    }
+
+    let mut mixed: MixedNeighborhood = MixedNeighborhood::new();
+    for entry in spec.split(',') {
+        let (name, weight) = entry.split_once(':').unwrap_or((entry, "1"));
+        let weight: f64 = weight
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|weight| weight.is_finite() && 0.0 < *weight)
+            .ok_or_else(|| {
+                format!("invalid weight {:?} for {:?}", weight, name)
+            })?;
+        let neighborhood: Box<dyn Neighborhood> =
+            neighborhood_from_name(name, job_master).ok_or_else(|| {
+                format!(
+                    "unknown neighborhood {:?} (expected one of {:?})",
+                    name, NEIGHBORHOOD_NAMES
+                )
+            })?;
+        mixed = mixed.add(neighborhood, weight);
+    }
+    Ok(Box::new(mixed))
+}
+
+/// swap 近傍で交換する2つの位置をランダムに選ぶ (同じ位置の場合もある)
+pub fn swap_positions(
+    num_genes: usize,
+    rng: &mut dyn RngCore,
+) -> (usize, usize) {
+    (
+        rng.random_range(0..num_genes),
+        rng.random_range(0..num_genes),
+    )
+}
+
+/// 染色体（解）の中からランダムに2つの遺伝子を選択して、その2つの位置を交換
+pub fn swap_2_genes(
+    chromosome: &types::Chromosome,
+    rng: &mut dyn RngCore,
+) -> types::Chromosome {
+    let (first_idx, second_idx): (usize, usize) =
+        swap_positions(chromosome.len(), rng);
+    let mut new_chromo: types::Chromosome = chromosome.clone();
+    new_chromo.swap(first_idx, second_idx);
+    new_chromo.to_owned()
+}
+
+impl Neighborhood for SwapNeighborhood {
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        swap_2_genes(chromosome, rng)
+    }
+
+    fn propose(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> NeighborMove {
+        let (first_idx, second_idx): (usize, usize) =
+            swap_positions(chromosome.len(), rng);
+        NeighborMove::Swap(first_idx, second_idx)
+    }
+}
+
+impl Neighborhood for InsertionNeighborhood {
+    fn name(&self) -> &'static str {
+        "insertion"
+    }
+
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        let mut new_chromo: types::Chromosome = chromosome.clone();
+        if chromosome.len() < 2 {
+            return new_chromo;
+        }
+        let from_idx: usize = rng.random_range(0..chromosome.len());
+        let to_idx: usize = rng.random_range(0..chromosome.len());
+        let gene: u16 = new_chromo.remove(from_idx);
+        new_chromo.insert(to_idx, gene);
+        new_chromo
+    }
+
+    fn propose(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> NeighborMove {
+        if chromosome.len() < 2 {
+            return NeighborMove::Chromosome(chromosome.clone());
+        }
+        let from_idx: usize = rng.random_range(0..chromosome.len());
+        let to_idx: usize = rng.random_range(0..chromosome.len());
+        NeighborMove::Insert(from_idx, to_idx)
+    }
+}
+
+impl Neighborhood for AdjacentSwapNeighborhood {
+    fn name(&self) -> &'static str {
+        "adjacent-swap"
+    }
+
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        let mut new_chromo: types::Chromosome = chromosome.clone();
+        if chromosome.len() < 2 {
+            return new_chromo;
+        }
+        let idx: usize = rng.random_range(0..chromosome.len() - 1);
+        new_chromo.swap(idx, idx + 1);
+        new_chromo
+    }
+
+    fn propose(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> NeighborMove {
+        if chromosome.len() < 2 {
+            return NeighborMove::Chromosome(chromosome.clone());
+        }
+        let idx: usize = rng.random_range(0..chromosome.len() - 1);
+        NeighborMove::Swap(idx, idx + 1)
+    }
+}
+
+impl Neighborhood for ReversalNeighborhood {
+    fn name(&self) -> &'static str {
+        "reversal"
+    }
+
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        let mut new_chromo: types::Chromosome = chromosome.clone();
+        if chromosome.len() < 2 {
+            return new_chromo;
+        }
+        let first_idx: usize = rng.random_range(0..chromosome.len());
+        let second_idx: usize = rng.random_range(0..chromosome.len());
+        let (start, end) = if first_idx <= second_idx {
+            (first_idx, second_idx)
+        } else {
+            (second_idx, first_idx)
+        };
+        new_chromo[start..=end].reverse();
+        new_chromo
+    }
+}
+
+impl CriticalBlockNeighborhood {
+    pub fn new(job_master: JobMaster, moves: CriticalBlockMoves) -> Self {
+        CriticalBlockNeighborhood { job_master, moves }
+    }
+}
+
+impl Neighborhood for CriticalBlockNeighborhood {
+    fn name(&self) -> &'static str {
+        match self.moves {
+            CriticalBlockMoves::N5 => "n5",
+            CriticalBlockMoves::N7 => "n7",
+        }
+    }
+
+    /// 移動候補がない (N5 の場合は最適解が確定している) か、
+    /// すべての候補が実行不可能な場合は元の染色体を返す
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        let schedule: Schedule =
+            SemiActiveDecoder.decode(&self.job_master, chromosome);
+        let graph: DisjunctiveGraph =
+            DisjunctiveGraph::from_schedule(&self.job_master, &schedule);
+        let evaluated: GraphSchedule = match graph.evaluate() {
+            Some(evaluated) => evaluated,
+            None => return chromosome.clone(),
+        };
+        let path: Vec<OperationId> = graph.critical_path(&evaluated);
+        let mut moves: Vec<MachineMove> = match self.moves {
+            CriticalBlockMoves::N5 => graph.n5_moves(&path),
+            CriticalBlockMoves::N7 => graph.n7_moves(&path),
+        };
+
+        // N7 の移動は閉路を作ることがあるため、実行可能な移動が見つかるまで試す
+        moves.shuffle(rng);
+        for machine_move in moves.iter() {
+            let moved: DisjunctiveGraph = graph.apply(machine_move);
+            if let Some(moved_evaluated) = moved.evaluate() {
+                return moved.to_chromosome(&moved_evaluated);
+            }
+        }
+        chromosome.clone()
+    }
+}
+
+impl MixedNeighborhood {
+    pub fn new() -> Self {
+        MixedNeighborhood {
+            neighborhoods: Vec::new(),
+        }
+    }
+
+    /// 近傍を重み付きで追加する (重みは合計が 1 でなくてもよい)
+    pub fn add(
+        mut self,
+        neighborhood: Box<dyn Neighborhood>,
+        weight: f64,
+    ) -> Self {
+        self.neighborhoods.push((neighborhood, weight));
+        self
+    }
+
+    /// 重みに従って近傍を1つ選ぶ
+    fn choose(&self, rng: &mut dyn RngCore) -> &dyn Neighborhood {
+        let total: f64 = self.neighborhoods.iter().map(|(_, w)| w).sum();
+        let mut threshold: f64 = rng.random::<f64>() * total;
+        for (neighborhood, weight) in self.neighborhoods.iter() {
+            if threshold < *weight {
+                return neighborhood.as_ref();
+            }
+            threshold -= weight;
+        }
+        // 浮動小数点の誤差で選ばれなかった場合は最後の近傍
+        self.neighborhoods
+            .last()
+            .expect("MixedNeighborhood has no neighborhood")
+            .0
+            .as_ref()
+    }
+}
+
+impl Default for MixedNeighborhood {
+    fn default() -> Self {
+        MixedNeighborhood::new()
+    }
+}
+
+impl Neighborhood for MixedNeighborhood {
+    fn name(&self) -> &'static str {
+        "mixed"
+    }
+
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        self.choose(rng).neighbor(chromosome, rng)
+    }
+
+    fn propose(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> NeighborMove {
+        self.choose(rng).propose(chromosome, rng)
+    }
+}
+
+impl FlexibleNeighborhood {
+    pub fn new(
+        job_master: &JobMaster,
+        sequence: Box<dyn Neighborhood>,
+        assignment_rate: f64,
+    ) -> Self {
+        let choice_counts: Vec<u16> = (0..job_master.job_size)
+            .flat_map(|job| {
+                (0..job_master.operation_count(job)).map(move |operation| {
+                    job_master.alternative_count(job, operation) as u16
+                })
+            })
+            .collect();
+        let flexible_operations: Vec<usize> = (0..choice_counts.len())
+            .filter(|index| 1 < choice_counts[*index])
+            .collect();
+        FlexibleNeighborhood {
+            sequence,
+            choice_counts,
+            flexible_operations,
+            assignment_rate,
+        }
+    }
+}
+
+impl Neighborhood for FlexibleNeighborhood {
+    fn name(&self) -> &'static str {
+        "flexible"
+    }
+
+    fn neighbor(
+        &self,
+        chromosome: &types::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> types::Chromosome {
+        let operation_count: usize = self.choice_counts.len();
+        if !self.flexible_operations.is_empty()
+            && rng.random::<f64>() < self.assignment_rate
+        {
+            // 別の選択肢に変える
+            let mut new_chromo: types::Chromosome = chromosome.clone();
+            let index: usize = self.flexible_operations
+                [rng.random_range(0..self.flexible_operations.len())];
+            let count: u16 = self.choice_counts[index];
+            let gene: &mut u16 = &mut new_chromo[operation_count + index];
+            *gene = (*gene % count + rng.random_range(1..count)) % count;
+            return new_chromo;
+        }
+
+        let sequence: types::Chromosome =
+            chromosome[..operation_count].to_vec();
+        let mut new_chromo: types::Chromosome =
+            self.sequence.neighbor(&sequence, rng);
+        new_chromo.extend_from_slice(&chromosome[operation_count..]);
+        new_chromo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::preprocess::runner::Alternative;
+    use crate::optimization::test_fixtures::square_job_master;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn sorted(chromosome: &types::Chromosome) -> types::Chromosome {
+        let mut sorted = chromosome.clone();
+        sorted.sort();
+        sorted
+    }
+
+    #[test]
+    fn test_swap_2_genes() {
+        // シード値を固定して乱数を再現可能に
+        let chromosome = types::Chromosome::from(vec![1, 2, 3, 4, 5]);
+        let original = chromosome.clone();
+        let result =
+            swap_2_genes(&chromosome, &mut ChaCha8Rng::seed_from_u64(1));
+
+        // 長さは同じ
+        assert_eq!(result.len(), original.len());
+
+        // 同じ seed なら同じ位置が交換される
+        assert_eq!(
+            swap_2_genes(&chromosome, &mut ChaCha8Rng::seed_from_u64(1)),
+            result
+        );
+        // 交換されていれば、異なるのはちょうど2つの位置
+        let changed: usize = original
+            .iter()
+            .zip(result.iter())
+            .filter(|(before, after)| before != after)
+            .count();
+        assert!(changed == 0 || changed == 2);
+    }
+
+    #[test]
+    fn test_swap_2_genes_identity() {
+        // 長さ1の配列では交換してもそのまま
+        let chromosome = types::Chromosome::from(vec![1]);
+        let original = chromosome.clone();
+        let result =
+            swap_2_genes(&chromosome, &mut ChaCha8Rng::seed_from_u64(2));
+        assert_eq!(result, original);
+    }
+
+    #[test]
+    fn test_swap_2_genes_preserves_elements() {
+        // 要素の総和は変わらないことを確認
+        let chromosome = types::Chromosome::from(vec![1, 2, 3, 4, 5]);
+        let original_sum: u16 = chromosome.iter().sum();
+        let result =
+            swap_2_genes(&chromosome, &mut ChaCha8Rng::seed_from_u64(3));
+        let result_sum: u16 = result.iter().sum();
+        assert_eq!(original_sum, result_sum);
+    }
+
+    #[test]
+    fn test_neighborhoods_preserve_genes() {
+        // どの近傍でも遺伝子の多重集合は変わらない (job ごとの operation 数が保たれる)
+        let job_master = square_job_master();
+        let chromosome: types::Chromosome = vec![0, 1, 2, 0, 1, 2, 0, 1, 2];
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        for name in NEIGHBORHOOD_NAMES {
+            let neighborhood =
+                neighborhood_from_name(name, &job_master).unwrap();
+            assert_eq!(neighborhood.name(), name);
+            for _ in 0..100 {
+                let result = neighborhood.neighbor(&chromosome, &mut rng);
+                assert_eq!(sorted(&result), sorted(&chromosome));
+            }
+        }
+    }
+
+    #[test]
+    fn test_adjacent_swap_changes_neighbors_only() {
+        let chromosome: types::Chromosome = vec![0, 1, 2, 3, 4];
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        for _ in 0..100 {
+            let result =
+                AdjacentSwapNeighborhood.neighbor(&chromosome, &mut rng);
+            let diff: Vec<usize> =
+                (0..5).filter(|i| result[*i] != chromosome[*i]).collect();
+            assert_eq!(diff.len(), 2);
+            assert_eq!(diff[0] + 1, diff[1]);
+        }
+    }
+
+    #[test]
+    fn test_propose_matches_neighbor() {
+        // 同じ seed なら propose と neighbor は同じ近傍解を選ぶ
+        let job_master = square_job_master();
+        let chromosome: types::Chromosome = vec![0, 1, 2, 0, 1, 2, 0, 1, 2];
+        for spec in ["swap", "adjacent-swap", "insertion", "swap:1,reversal:1"]
+        {
+            let neighborhood = parse_neighborhood(spec, &job_master).unwrap();
+            let mut proposing = ChaCha8Rng::seed_from_u64(7);
+            let mut generating = ChaCha8Rng::seed_from_u64(7);
+            for _ in 0..100 {
+                assert_eq!(
+                    neighborhood
+                        .propose(&chromosome, &mut proposing)
+                        .into_chromosome(&chromosome),
+                    neighborhood.neighbor(&chromosome, &mut generating)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_neighborhood() {
+        let job_master = square_job_master();
+        assert_eq!(
+            parse_neighborhood("swap", &job_master).unwrap().name(),
+            "swap"
+        );
+        assert_eq!(
+            parse_neighborhood("swap:0.5,insertion:0.3,n7:0.2", &job_master)
+                .unwrap()
+                .name(),
+            "mixed"
+        );
+        assert!(parse_neighborhood("unknown", &job_master).is_err());
+        assert!(parse_neighborhood("swap:0,insertion:1", &job_master).is_err());
+        assert!(parse_neighborhood("swap:a", &job_master).is_err());
+    }
+
+    #[test]
+    fn test_critical_block_neighborhood_changes_critical_path() {
+        // N5/N7 の移動は必ずクリティカルブロックの処理順を変える
+        let job_master = square_job_master();
+        let mut rng = ChaCha8Rng::seed_from_u64(6);
+        let chromosome: types::Chromosome = vec![0, 0, 0, 1, 1, 1, 2, 2, 2];
+        let before = SemiActiveDecoder.decode(&job_master, &chromosome);
+        for moves in [CriticalBlockMoves::N5, CriticalBlockMoves::N7] {
+            let neighborhood =
+                CriticalBlockNeighborhood::new(job_master.clone(), moves);
+            for _ in 0..20 {
+                let result = neighborhood.neighbor(&chromosome, &mut rng);
+                let after = SemiActiveDecoder.decode(&job_master, &result);
+                let before_orders: Vec<Vec<(u16, usize)>> = orders(&before);
+                assert_ne!(orders(&after), before_orders);
+            }
+        }
+    }
+
+    fn orders(schedule: &Schedule) -> Vec<Vec<(u16, usize)>> {
+        schedule
+            .machine_sequences
+            .iter()
+            .map(|sequence| {
+                sequence
+                    .iter()
+                    .map(|index| {
+                        let op = schedule.operations[*index];
+                        (op.job, op.operation)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mixed_neighborhood_uses_only_positive_weights() {
+        // 重みの大きい近傍だけが選ばれる
+        let mixed = MixedNeighborhood::new()
+            .add(Box::new(AdjacentSwapNeighborhood), 1.0)
+            .add(Box::new(ReversalNeighborhood), 1e-300);
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..100 {
+            assert_eq!(mixed.choose(&mut rng).name(), "adjacent-swap");
+        }
+    }
+
+    #[test]
+    fn test_flexible_neighborhood_mutates_both_parts() {
+        let alternative =
+            |actor_id: u16, time: types::Time| Alternative { actor_id, time };
+        // 選択肢が2つあるのは job 0 の operation 0 (後半の 0 番目) のみ
+        let job_master = JobMaster::try_new_flexible(
+            2,
+            2,
+            vec![
+                vec![vec![alternative(0, 3), alternative(1, 5)]],
+                vec![vec![alternative(0, 4)], vec![alternative(1, 2)]],
+            ],
+        )
+        .unwrap();
+        let neighborhood = FlexibleNeighborhood::new(
+            &job_master,
+            Box::new(SwapNeighborhood),
+            0.5,
+        );
+        let chromosome: types::Chromosome = vec![1, 0, 1, 0, 0, 0];
+        let mut rng = ChaCha8Rng::seed_from_u64(8);
+        let (mut sequence_moves, mut assignment_moves) = (0, 0);
+        for _ in 0..200 {
+            let next = neighborhood.neighbor(&chromosome, &mut rng);
+            assert_eq!(next.len(), chromosome.len());
+            assert_eq!(sorted(&next[..3].to_vec()), vec![0, 1, 1]);
+            if next[3..] != chromosome[3..] {
+                // 選択肢の範囲内で別の actor に変える
+                assert_eq!(next[3..], [1, 0, 0]);
+                assert_eq!(next[..3], chromosome[..3]);
+                assignment_moves += 1;
+            } else if next != chromosome {
+                sequence_moves += 1;
+            }
+        }
+        assert!(0 < sequence_moves && 0 < assignment_moves);
+    }
+}
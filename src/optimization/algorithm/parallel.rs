@@ -0,0 +1,469 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+use crate::optimization::algorithm::neighborhood::Neighborhood;
+use crate::optimization::algorithm::simulated_annealing::{
+    self, AnnealingConfig, Migration,
+};
+use crate::optimization::decoder::score::{split_flexible, ScoreCalculator};
+use crate::optimization::types;
+
+static DEFAULT_MIGRATION_INTERVAL: u32 = 10_000;
+
+/// 複数の焼きなましの進め方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ParallelMode {
+    /// 独立に探索し、最も良い結果を返す
+    MultiStart,
+    /// migration_interval step ごとに、環状に並べた隣の島の最良解を受け取る
+    Island { migration_interval: u32 },
+}
+
+/// 並列探索の設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ParallelConfig {
+    /// スレッド数 (= 焼きなましの本数)
+    pub threads: usize,
+    pub mode: ParallelMode,
+}
+
+impl Default for ParallelConfig {
+    fn default() -> Self {
+        ParallelConfig {
+            threads: 1,
+            mode: ParallelMode::MultiStart,
+        }
+    }
+}
+
+impl ParallelMode {
+    /// 既定の交換間隔の島モデル
+    pub fn island() -> Self {
+        ParallelMode::Island {
+            migration_interval: DEFAULT_MIGRATION_INTERVAL,
+        }
+    }
+}
+
+/// seed と焼きなましの番号から、その焼きなまし専用の乱数生成器を作る
+/// (同じ seed でも焼きなましごとに異なる stream になる)
+pub fn chain_rng(seed: u64, chain: usize) -> ChaCha8Rng {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(chain as u64);
+    rng
+}
+
+/// 途中で抜ける島があっても、残りの島だけで足並みを揃えられる Barrier
+///
+/// time_limit に達した島は交換の途中の step で探索を終えるため、
+/// 抜けた島を待ち続けないよう leave で待つ島の数を減らす
+struct IslandBarrier {
+    /// (参加している島の数, 待っている島の数, 世代)
+    state: Mutex<(usize, usize, u64)>,
+    condvar: Condvar,
+}
+
+impl IslandBarrier {
+    fn new(participants: usize) -> Self {
+        IslandBarrier {
+            state: Mutex::new((participants, 0, 0)),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// 参加しているすべての島が wait を呼ぶまで待つ
+    fn wait(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.1 += 1;
+        if state.0 <= state.1 {
+            self.release(&mut state);
+            return;
+        }
+        let generation: u64 = state.2;
+        while state.2 == generation {
+            state = self.condvar.wait(state).unwrap();
+        }
+    }
+
+    /// 以降の wait に参加しない
+    fn leave(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.0 -= 1;
+        if 0 < state.1 && state.0 <= state.1 {
+            self.release(&mut state);
+        }
+    }
+
+    fn release(&self, state: &mut (usize, usize, u64)) {
+        state.1 = 0;
+        state.2 += 1;
+        self.condvar.notify_all();
+    }
+}
+
+/// 島どうしで共有する最良解の置き場
+///
+/// 全島が同じ step で exchange を呼ぶため、IslandBarrier で足並みを揃えて
+/// 書き込みと読み出しを分ける (交換の結果はスレッドの実行順によらない)
+struct Archipelago {
+    slots: Mutex<Vec<types::Chromosome>>,
+    barrier: IslandBarrier,
+    stop: AtomicBool,
+    start: Instant,
+    time_limit: f32,
+}
+
+struct Island<'a> {
+    index: usize,
+    migration_interval: u32,
+    archipelago: &'a Archipelago,
+}
+
+impl Migration for Island<'_> {
+    fn interval(&self) -> u32 {
+        self.migration_interval
+    }
+
+    fn exchange(
+        &mut self,
+        best_solution: &types::Chromosome,
+        _best_score: f64,
+    ) -> Option<types::Chromosome> {
+        let archipelago: &Archipelago = self.archipelago;
+        if archipelago.time_limit < archipelago.start.elapsed().as_secs_f32() {
+            archipelago.stop.store(true, Ordering::SeqCst);
+        }
+        archipelago.slots.lock().unwrap()[self.index] = best_solution.clone();
+        archipelago.barrier.wait();
+
+        // 全島の書き込みが終わってから読み出す
+        let stop: bool = archipelago.stop.load(Ordering::SeqCst);
+        let migrant: types::Chromosome = {
+            let slots = archipelago.slots.lock().unwrap();
+            slots[(self.index + slots.len() - 1) % slots.len()].clone()
+        };
+        archipelago.barrier.wait();
+
+        (!stop).then_some(migrant)
+    }
+}
+
+/// 探索を終えた (panic した場合も含む) 島は交換から抜ける
+impl Drop for Island<'_> {
+    fn drop(&mut self) {
+        self.archipelago.barrier.leave();
+    }
+}
+
+/// config.threads 本の焼きなましをスレッドで並列に実行し、最も良い結果を返す
+///
+/// 0 番の焼きなましは chromosome そのもの (NEH などの初期解) から開始し、
+/// それ以外は chain_rng(seed, 番号) の乱数で chromosome の operation 順を
+/// シャッフルした解から開始する (FJSP の actor の選択は chromosome のまま)。
+/// 戻り値は simulated_annealing::run と同じく
+/// (最良解, その makespan, そのスコア, 全スレッドの合計 step 数)
+pub fn run(
+    chromosome: &types::Chromosome,
+    calculator: &ScoreCalculator,
+    neighborhood: &dyn Neighborhood,
+    annealing_config: &AnnealingConfig,
+    config: &ParallelConfig,
+    seed: u64,
+) -> (types::Chromosome, types::Time, f64, u32) {
+    let threads: usize = config.threads.max(1);
+    // FJSP の染色体の後半 (actor の選択) は位置ごとに意味があるため混ぜない
+    let sequence_len: usize =
+        split_flexible(calculator.job_master(), chromosome).0.len();
+    let archipelago: Archipelago = Archipelago {
+        slots: Mutex::new(vec![chromosome.clone(); threads]),
+        barrier: IslandBarrier::new(threads),
+        stop: AtomicBool::new(false),
+        start: Instant::now(),
+        time_limit: annealing_config.time_limit,
+    };
+
+    let results: Vec<(types::Chromosome, types::Time, f64, u32)> =
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|index| {
+                    let archipelago: &Archipelago = &archipelago;
+                    scope.spawn(move || {
+                        let mut rng: ChaCha8Rng = chain_rng(seed, index);
+                        let mut initial: types::Chromosome = chromosome.clone();
+                        if 0 < index {
+                            initial[..sequence_len].shuffle(&mut rng);
+                        }
+                        let mut island: Option<Island> = match config.mode {
+                            ParallelMode::MultiStart => None,
+                            ParallelMode::Island { migration_interval } => {
+                                Some(Island {
+                                    index,
+                                    migration_interval,
+                                    archipelago,
+                                })
+                            }
+                        };
+                        simulated_annealing::anneal(
+                            &initial,
+                            calculator,
+                            neighborhood,
+                            annealing_config,
+                            &mut rng,
+                            island
+                                .as_mut()
+                                .map(|island| island as &mut dyn Migration),
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+    let iterated_num: u32 = results.iter().map(|result| result.3).sum();
+    for (index, result) in results.iter().enumerate() {
+        println!(
+            "[INFO] chain: {:?}, best_score: {:?}, best_makespan: {:?}",
+            index, result.2, result.1
+        );
+    }
+    // スコアが同じ場合は番号の小さい焼きなましの結果を選ぶ
+    let (best_solution, best_makespan, best_score, _) = results
+        .into_iter()
+        .reduce(|best, result| if best.2 < result.2 { result } else { best })
+        .unwrap();
+
+    (best_solution, best_makespan, best_score, iterated_num)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::algorithm::neighborhood::{
+        FlexibleNeighborhood, SwapNeighborhood, DEFAULT_ASSIGNMENT_RATE,
+    };
+    use crate::optimization::decoder::score::FlexibleDecoder;
+    use crate::optimization::decoder::validator::validate;
+    use crate::optimization::preprocess::runner::{Alternative, JobMaster};
+    use crate::optimization::test_fixtures::medium_job_master;
+
+    fn sample_calculator() -> ScoreCalculator {
+        ScoreCalculator::new(medium_job_master())
+    }
+
+    #[test]
+    fn test_chain_rng_differs_by_chain() {
+        let mut chromosome: types::Chromosome = (0..20).collect();
+        let mut other: types::Chromosome = chromosome.clone();
+        chromosome.shuffle(&mut chain_rng(1, 0));
+        other.shuffle(&mut chain_rng(1, 1));
+        assert_ne!(chromosome, other);
+
+        let mut again: types::Chromosome = (0..20).collect();
+        again.shuffle(&mut chain_rng(1, 0));
+        assert_eq!(chromosome, again);
+    }
+
+    #[test]
+    fn test_run_is_reproducible() {
+        let calculator = sample_calculator();
+        let chromosome: types::Chromosome =
+            vec![0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3];
+        let annealing_config = AnnealingConfig {
+            steps: 300,
+            steps_per_temperature: 10,
+            ..AnnealingConfig::default()
+        };
+        for mode in [
+            ParallelMode::MultiStart,
+            ParallelMode::Island {
+                migration_interval: 50,
+            },
+        ] {
+            let config = ParallelConfig { threads: 3, mode };
+            let run_once = || {
+                run(
+                    &chromosome,
+                    &calculator,
+                    &SwapNeighborhood,
+                    &annealing_config,
+                    &config,
+                    11,
+                )
+            };
+            let result = run_once();
+            assert_eq!(result.3, 900);
+            assert_eq!(calculator.calc_makespan(&result.0), result.1);
+            assert_eq!(run_once(), result);
+        }
+    }
+
+    #[test]
+    fn test_first_chain_starts_from_chromosome() {
+        // 0 番の焼きなましは与えた初期解をシャッフルしない
+        let calculator = sample_calculator();
+        let chromosome: types::Chromosome =
+            vec![3, 2, 1, 0, 3, 2, 1, 0, 3, 2, 1, 0];
+        let annealing_config = AnnealingConfig {
+            steps: 0,
+            ..AnnealingConfig::default()
+        };
+        let config = ParallelConfig {
+            threads: 1,
+            mode: ParallelMode::MultiStart,
+        };
+        let result = run(
+            &chromosome,
+            &calculator,
+            &SwapNeighborhood,
+            &annealing_config,
+            &config,
+            7,
+        );
+        assert_eq!(result.0, chromosome);
+        assert_eq!(result.1, calculator.calc_makespan(&chromosome));
+    }
+
+    #[test]
+    fn test_island_stops_at_time_limit() {
+        // 交換の間隔が長くても、time_limit で各島が打ち切られ、
+        // 先に抜けた島を待って止まることもない
+        let calculator = sample_calculator();
+        let chromosome: types::Chromosome =
+            vec![0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3];
+        let annealing_config = AnnealingConfig {
+            steps: 1_000_000,
+            time_limit: 0.2,
+            ..AnnealingConfig::default()
+        };
+        let config = ParallelConfig {
+            threads: 3,
+            mode: ParallelMode::Island {
+                migration_interval: u32::MAX,
+            },
+        };
+        let result = run(
+            &chromosome,
+            &calculator,
+            &SwapNeighborhood,
+            &annealing_config,
+            &config,
+            3,
+        );
+        assert!(0 < result.3 && result.3 < 3 * annealing_config.steps);
+        assert_eq!(calculator.calc_makespan(&result.0), result.1);
+    }
+
+    #[test]
+    fn test_island_barrier_releases_when_island_leaves() {
+        let barrier: IslandBarrier = IslandBarrier::new(3);
+        thread::scope(|scope| {
+            let waiters: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        barrier.wait();
+                    })
+                })
+                .collect();
+            // 3 島目が一度も wait せずに抜けても、残りの 2 島は進める
+            barrier.leave();
+            for waiter in waiters {
+                waiter.join().unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn test_island_keeps_flexible_assignment_valid() {
+        let alternative =
+            |actor_id: u16, time: types::Time| Alternative { actor_id, time };
+        let job_master: JobMaster = JobMaster::try_new_flexible(
+            3,
+            3,
+            vec![
+                vec![
+                    vec![alternative(0, 3), alternative(1, 5)],
+                    vec![alternative(1, 2), alternative(2, 4)],
+                ],
+                vec![
+                    vec![alternative(2, 4)],
+                    vec![alternative(0, 2), alternative(1, 1)],
+                    vec![alternative(1, 3), alternative(2, 2)],
+                ],
+                vec![
+                    vec![
+                        alternative(0, 2),
+                        alternative(1, 2),
+                        alternative(2, 2),
+                    ],
+                    vec![alternative(0, 5)],
+                ],
+            ],
+        )
+        .unwrap();
+        let calculator: ScoreCalculator = ScoreCalculator::with_decoder(
+            job_master.clone(),
+            Box::new(FlexibleDecoder),
+        );
+        let neighborhood: FlexibleNeighborhood = FlexibleNeighborhood::new(
+            &job_master,
+            Box::new(SwapNeighborhood),
+            DEFAULT_ASSIGNMENT_RATE,
+        );
+        // 前半が operation 順、後半が (job, operation) 順の actor の選択
+        let chromosome: types::Chromosome =
+            vec![0, 0, 1, 1, 1, 2, 2, 0, 0, 0, 0, 0, 0, 0];
+        let annealing_config = AnnealingConfig {
+            steps: 300,
+            steps_per_temperature: 10,
+            ..AnnealingConfig::default()
+        };
+        let config = ParallelConfig {
+            threads: 3,
+            mode: ParallelMode::Island {
+                migration_interval: 20,
+            },
+        };
+        let (best_solution, best_makespan, _, _) = run(
+            &chromosome,
+            &calculator,
+            &neighborhood,
+            &annealing_config,
+            &config,
+            5,
+        );
+
+        // 交換を経ても operation 順と actor の選択が混ざらない
+        let (sequence, assignment) =
+            split_flexible(&job_master, &best_solution);
+        let mut sorted: types::Chromosome = sequence.to_vec();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 0, 1, 1, 1, 2, 2]);
+        let choices: Vec<(usize, usize)> = (0..job_master.job_size)
+            .flat_map(|job| {
+                (0..job_master.operation_count(job))
+                    .map(move |operation| (job, operation))
+            })
+            .collect();
+        assert_eq!(assignment.len(), choices.len());
+        for ((job, operation), choice) in choices.into_iter().zip(assignment) {
+            assert!(
+                (*choice as usize)
+                    < job_master.alternative_count(job, operation)
+            );
+        }
+        let schedule = calculator.decode_schedule(&best_solution);
+        assert_eq!(validate(&job_master, &schedule), vec![]);
+        assert_eq!(schedule.makespan, best_makespan);
+    }
+}
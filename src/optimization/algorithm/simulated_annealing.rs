@@ -1,19 +1,158 @@
-use crate::optimization::decoder::score::ScoreCalculator;
+use crate::optimization::algorithm::neighborhood::{
+    NeighborMove, Neighborhood,
+};
+use crate::optimization::decoder::flat::SwapEvaluator;
+use crate::optimization::decoder::flow_shop::InsertionEvaluator;
+use crate::optimization::decoder::score::{
+    Evaluation, ScoreCalculator, ScoreEvaluator,
+};
 use crate::optimization::types;
-use rand::Rng;
+use rand::{Rng, RngCore};
+use serde::Serialize;
 use std::ops::Sub;
 use std::time::Instant;
 
-static COOLING_RATE: f64 = 0.9;
-static STEPS: u32 = 2_000_000;
-static TIME_LIMIT: f32 = 600.0; // seconds
+static DEFAULT_COOLING_RATE: f64 = 0.99;
+static DEFAULT_STEPS_PER_TEMPERATURE: u32 = 1_000;
+static DEFAULT_STEPS: u32 = 2_000_000;
+static DEFAULT_TIME_LIMIT: f32 = 600.0; // seconds
+static DEFAULT_INITIAL_ACCEPTANCE: f64 = 0.8;
+static TEMPERATURE_SAMPLES: u32 = 100;
+
+/// 温度の下げ方 (level は温度を下げた回数)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum CoolingSchedule {
+    /// T_{k+1} = alpha * T_k
+    Geometric { alpha: f64 },
+    /// T_{k+1} = max(T_k - delta, 0)
+    Linear { delta: f64 },
+    /// T_k = T_0 / ln(e + k)
+    Logarithmic,
+    /// Lundy–Mees: T_{k+1} = T_k / (1 + beta * T_k)
+    LundyMees { beta: f64 },
+}
+
+impl CoolingSchedule {
+    /// 次の温度を求める
+    pub fn cool(
+        &self,
+        temperature: f64,
+        initial_temperature: f64,
+        level: u32,
+    ) -> f64 {
+        match self {
+            CoolingSchedule::Geometric { alpha } => alpha * temperature,
+            CoolingSchedule::Linear { delta } => (temperature - delta).max(0.0),
+            CoolingSchedule::Logarithmic => {
+                initial_temperature / (std::f64::consts::E + level as f64).ln()
+            }
+            CoolingSchedule::LundyMees { beta } => {
+                temperature / (1.0 + beta * temperature)
+            }
+        }
+    }
+
+    /// "geometric:0.99", "linear:0.001", "logarithmic", "lundy-mees:0.01"
+    /// の形式の指定を解釈する
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, parameter) = match spec.split_once(':') {
+            Some((name, parameter)) => (name, Some(parameter)),
+            None => (spec, None),
+        };
+        let parameter = |default: f64| -> Result<f64, String> {
+            match parameter {
+                None => Ok(default),
+                Some(value) => value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite() && 0.0 < *value)
+                    .ok_or_else(|| {
+                        format!("invalid cooling parameter {:?}", value)
+                    }),
+            }
+        };
+
+        match name.trim().to_lowercase().as_str() {
+            "geometric" => {
+                let alpha: f64 = parameter(DEFAULT_COOLING_RATE)?;
+                if 1.0 <= alpha {
+                    return Err(format!(
+                        "cooling rate must be less than 1, but got {}",
+                        alpha
+                    ));
+                }
+                Ok(CoolingSchedule::Geometric { alpha })
+            }
+            "linear" => Ok(CoolingSchedule::Linear {
+                delta: parameter(1e-6)?,
+            }),
+            "logarithmic" => Ok(CoolingSchedule::Logarithmic),
+            "lundy-mees" => Ok(CoolingSchedule::LundyMees {
+                beta: parameter(1e-3)?,
+            }),
+            _ => Err(format!(
+                "unknown cooling schedule {:?} (expected geometric, linear, logarithmic or lundy-mees)",
+                spec
+            )),
+        }
+    }
+}
+
+/// 最良解が更新されない状態が続いたときに温度を上げ直す設定
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Reheat {
+    /// 最良解が更新されない step 数がこの値に達したら再加熱する
+    pub after_stagnant_steps: u32,
+    /// 再加熱後の温度 (初期温度に対する比率)
+    pub ratio: f64,
+}
+
+/// 焼きなまし法の設定
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AnnealingConfig {
+    /// 初期温度 (None の場合は近傍の移動によるスコアの悪化幅から推定する)
+    pub initial_temperature: Option<f64>,
+    /// 初期温度を推定する際に目標とする、悪化する移動の受理確率
+    pub initial_acceptance: f64,
+    pub cooling: CoolingSchedule,
+    /// 同じ温度で探索する step 数
+    pub steps_per_temperature: u32,
+    pub reheat: Option<Reheat>,
+    /// 最大 step 数
+    pub steps: u32,
+    /// 実行時間の上限 (秒)
+    pub time_limit: f32,
+}
+
+impl Default for AnnealingConfig {
+    fn default() -> Self {
+        AnnealingConfig {
+            initial_temperature: None,
+            initial_acceptance: DEFAULT_INITIAL_ACCEPTANCE,
+            cooling: CoolingSchedule::Geometric {
+                alpha: DEFAULT_COOLING_RATE,
+            },
+            steps_per_temperature: DEFAULT_STEPS_PER_TEMPERATURE,
+            reheat: None,
+            steps: DEFAULT_STEPS,
+            time_limit: DEFAULT_TIME_LIMIT,
+        }
+    }
+}
 
 /// 現在のスコアと次のスコアを比較して、確率的に次の値を選択する
 /// current: 現在のスコア
 /// next: 次のスコア
 /// temperature: 温度パラメータ
 ///     低いほど、次のスコアを選択しやすくなる
-fn is_replaceable<T>(current: T, next: T, temperature: f64) -> bool
+/// rng: 受理判定に使う乱数生成器
+fn is_replaceable<T>(
+    current: T,
+    next: T,
+    temperature: f64,
+    rng: &mut dyn RngCore,
+) -> bool
 where
     T: PartialOrd + Sub<Output = T> + Into<f64> + Copy,
 {
@@ -22,82 +161,272 @@ where
     }
     let diff: f64 = (next - current).into();
     let prob: f64 = (diff / temperature).exp();
-    if rand::random::<f64>() < prob {
+    if rng.random::<f64>() < prob {
         return true;
     }
     false
 }
 
-/// 染色体（解）の中からランダムに2つの遺伝子を選択して、その2つの位置を交換
-fn swap_2_genes(chromosome: &types::Chromosome) -> types::Chromosome {
-    let num_genes: usize = chromosome.len();
-
-    // TODO: 乱数生成を統一 seed に従わせる
-    let mut rng = rand::rng();
-    let first_idx: usize = rng.random_range(0..num_genes) as usize;
-    let second_idx: usize = rng.random_range(0..num_genes) as usize;
-    let mut new_chromo: types::Chromosome = chromosome.clone();
-    new_chromo.swap(first_idx, second_idx);
-    new_chromo.to_owned()
+/// 初期解の近傍をランダムにたどり、スコアが悪化する移動の平均悪化幅から
+/// 悪化する移動を acceptance の確率で受理する初期温度を推定する
+/// T_0 = -mean(delta) / ln(acceptance)
+pub fn estimate_initial_temperature(
+    chromosome: &types::Chromosome,
+    calculator: &ScoreCalculator,
+    neighborhood: &dyn Neighborhood,
+    acceptance: f64,
+    rng: &mut dyn RngCore,
+) -> f64 {
+    let mut scorer: ScoreEvaluator = calculator.evaluator();
+    let mut current: types::Chromosome = chromosome.clone();
+    let mut current_score: f64 = scorer.evaluate(&current).score;
+    let mut deltas: Vec<f64> = Vec::new();
+    for _ in 0..TEMPERATURE_SAMPLES {
+        let next: types::Chromosome = neighborhood.neighbor(&current, rng);
+        let next_score: f64 = scorer.evaluate(&next).score;
+        if next_score < current_score {
+            deltas.push(current_score - next_score);
+        }
+        current = next;
+        current_score = next_score;
+    }
+
+    if deltas.is_empty() {
+        // 悪化する移動が見つからない場合は、スコアの大きさを目安にする
+        return current_score.abs().max(f64::MIN_POSITIVE);
+    }
+    let mean: f64 = deltas.iter().sum::<f64>() / deltas.len() as f64;
+    -mean / acceptance.ln()
 }
 
-fn cool(temperature: f64) -> f64 {
-    COOLING_RATE * temperature
+/// 島モデルで、他の探索 (島) との間で解を交換する窓口
+///
+/// time_limit に達した探索は交換の間の step で終わるため、
+/// 他の島と足並みを揃える実装は、終わった島を待たないようにする
+pub trait Migration {
+    /// 解を交換する間隔 (step 数)
+    fn interval(&self) -> u32;
+
+    /// 自身の最良解を送り、他の島から届いた解を受け取る
+    /// 探索を終了する場合は None を返す
+    fn exchange(
+        &mut self,
+        best_solution: &types::Chromosome,
+        best_score: f64,
+    ) -> Option<types::Chromosome>;
 }
 
+/// neighborhood で近傍解を生成しながら、焼きなまし法で解を探索する
+/// chromosome は初期解として読むだけで、最良解は戻り値で返す
+/// 乱数はすべて rng から生成するため、同じ seed の rng を渡せば
+/// (time_limit に達しない限り) 同じ結果が再現される
 pub fn run(
-    chromosome: &mut types::Chromosome,
+    chromosome: &types::Chromosome,
     calculator: ScoreCalculator,
-) -> (types::Chromosome, u16, f64, u32) {
+    neighborhood: &dyn Neighborhood,
+    config: &AnnealingConfig,
+    rng: &mut dyn RngCore,
+) -> (types::Chromosome, types::Time, f64, u32) {
+    anneal(chromosome, &calculator, neighborhood, config, rng, None)
+}
+
+/// 焼きなまし法の本体
+///
+/// migration を指定した場合は interval step ごとに他の島と解を交換し、
+/// 届いた解が現在の解より良ければ乗り換える
+/// (time_limit は migration の有無によらず毎 step 確認する)
+pub fn anneal(
+    chromosome: &types::Chromosome,
+    calculator: &ScoreCalculator,
+    neighborhood: &dyn Neighborhood,
+    config: &AnnealingConfig,
+    rng: &mut dyn RngCore,
+    mut migration: Option<&mut dyn Migration>,
+) -> (types::Chromosome, types::Time, f64, u32) {
     let start: Instant = Instant::now();
-    let mut temperature: f64 = 1.0;
+    let initial_temperature: f64 = match config.initial_temperature {
+        Some(temperature) => temperature,
+        None => estimate_initial_temperature(
+            chromosome,
+            calculator,
+            neighborhood,
+            config.initial_acceptance,
+            rng,
+        ),
+    };
+    println!("[INFO] initial_temperature: {:?}", initial_temperature);
+    let mut temperature: f64 = initial_temperature;
+    let mut level: u32 = 0;
+    let mut stagnant_steps: u32 = 0;
 
+    // 評価用のスクラッチバッファと swap 移動の差分評価器
+    // (探索ごとに作るため、並列実行時もスレッド間で共有しない)
+    let mut scorer: ScoreEvaluator = calculator.evaluator();
     let mut current_solution: types::Chromosome = chromosome.clone();
-    let current_makespan: u16 =
-        calculator.calc_makespan(current_solution.clone());
-    let mut current_score: f64 = calculator.makespan_to_score(current_makespan);
+    let current_evaluation: Evaluation = scorer.evaluate(&current_solution);
+    let mut current_score: f64 = current_evaluation.score;
 
     let mut best_solution: types::Chromosome = chromosome.clone();
-    let mut best_makespan: u16 = current_makespan;
+    let mut best_makespan: types::Time = current_evaluation.makespan;
     let mut best_score: f64 = current_score;
 
+    // swap 移動は染色体を複製せず、交換する位置だけで差分評価する
+    // swap 以外の移動を受理した後は、次の swap 移動の前に作り直す
+    let mut swap_evaluator: Option<SwapEvaluator> =
+        calculator.swap_evaluator(&current_solution);
+    let mut swap_evaluator_stale: bool = false;
+    // 順列フローショップの挿入移動は、Taillard の高速化で差分評価する
+    // (挿入以外の移動を受理した後は、次の挿入移動の前に作り直す)
+    let mut insertion_evaluator: Option<InsertionEvaluator> =
+        calculator.insertion_evaluator(&current_solution);
+    let mut insertion_evaluator_stale: bool = false;
+
     let mut iterated_num: u32 = 0;
-    for _step in 0..STEPS {
+    for _step in 0..config.steps {
         iterated_num += 1;
-        let tmp_chromosome: types::Chromosome = swap_2_genes(&current_solution);
-        let tmp_makespan: u16 =
-            calculator.calc_makespan(tmp_chromosome.clone());
-        let tmp_score: f64 = calculator.makespan_to_score(tmp_makespan);
+        let proposal: NeighborMove =
+            neighborhood.propose(&current_solution, rng);
+        let tmp_evaluation: Evaluation = match (
+            &proposal,
+            swap_evaluator.as_mut(),
+            insertion_evaluator.as_mut(),
+        ) {
+            (NeighborMove::Swap(first, second), Some(evaluator), _) => {
+                if swap_evaluator_stale {
+                    evaluator.reset(&current_solution);
+                    swap_evaluator_stale = false;
+                }
+                calculator.makespan_evaluation(
+                    evaluator.swap_makespan(*first, *second),
+                )
+            }
+            (NeighborMove::Insert(from, to), _, Some(evaluator)) => {
+                if insertion_evaluator_stale {
+                    evaluator.reset(&current_solution);
+                    insertion_evaluator_stale = false;
+                }
+                calculator
+                    .makespan_evaluation(evaluator.insert_makespan(*from, *to))
+            }
+            (NeighborMove::Swap(..) | NeighborMove::Insert(..), _, _) => {
+                // 複製せずに移動して評価し、元に戻す
+                proposal.apply(&mut current_solution);
+                let evaluation: Evaluation = scorer.evaluate(&current_solution);
+                proposal.revert(&mut current_solution);
+                evaluation
+            }
+            (NeighborMove::Chromosome(tmp_chromosome), _, _) => {
+                scorer.evaluate(tmp_chromosome)
+            }
+        };
+        let tmp_score: f64 = tmp_evaluation.score;
 
         // annealing アルゴリズムに基づき、解を更新できるか判定
         let replacable: bool =
-            is_replaceable(current_score, tmp_score, temperature);
-
-        if replacable {
-            current_score = tmp_score;
-            current_solution = tmp_chromosome.clone();
-        }
+            is_replaceable(current_score, tmp_score, temperature, rng);
 
+        stagnant_steps += 1;
         if best_score < tmp_score {
             best_score = tmp_score;
-            best_makespan = tmp_makespan;
-            best_solution = tmp_chromosome;
+            best_makespan = tmp_evaluation.makespan;
+            match &proposal {
+                NeighborMove::Chromosome(tmp_chromosome) => {
+                    best_solution.clone_from(tmp_chromosome);
+                }
+                _ => {
+                    best_solution.clone_from(&current_solution);
+                    proposal.apply(&mut best_solution);
+                }
+            }
+            stagnant_steps = 0;
             println!(
-                "step: {:?}, score: {:?}, makespan: {:?}",
-                _step, best_score, best_makespan
+                "step: {:?}, score: {:?}, makespan: {:?}, objective: {:?}",
+                _step,
+                best_score,
+                best_makespan,
+                tmp_evaluation.objective_value
             );
         }
 
+        // 最良解を更新した場合 (必ず受理される) 以外は複製せずに引き継ぐ
+        if replacable {
+            current_score = tmp_score;
+            match proposal {
+                NeighborMove::Swap(first, second) => {
+                    current_solution.swap(first, second);
+                    // swap 移動の評価前に作り直しているため、ここでは最新
+                    if let Some(evaluator) = swap_evaluator.as_mut() {
+                        evaluator.apply_swap(first, second);
+                    }
+                    insertion_evaluator_stale = true;
+                }
+                NeighborMove::Insert(from, to) => {
+                    proposal.apply(&mut current_solution);
+                    // 挿入移動の評価前に作り直しているため、ここでは最新
+                    if let Some(evaluator) = insertion_evaluator.as_mut() {
+                        evaluator.apply_insert(from, to);
+                    }
+                    swap_evaluator_stale = true;
+                }
+                NeighborMove::Chromosome(tmp_chromosome) => {
+                    current_solution = tmp_chromosome;
+                    swap_evaluator_stale = true;
+                    insertion_evaluator_stale = true;
+                }
+            }
+        }
+
+        if let Some(migration) = migration.as_deref_mut() {
+            if (_step + 1) % migration.interval().max(1) == 0 {
+                let migrant: types::Chromosome =
+                    match migration.exchange(&best_solution, best_score) {
+                        Some(migrant) => migrant,
+                        None => break,
+                    };
+                let migrant_evaluation: Evaluation = scorer.evaluate(&migrant);
+                if current_score < migrant_evaluation.score {
+                    current_score = migrant_evaluation.score;
+                    current_solution = migrant.clone();
+                    swap_evaluator_stale = true;
+                    insertion_evaluator_stale = true;
+                }
+                if best_score < migrant_evaluation.score {
+                    best_score = migrant_evaluation.score;
+                    best_makespan = migrant_evaluation.makespan;
+                    best_solution = migrant;
+                }
+            }
+        }
+
+        // 島モデルでも交換を待たずに打ち切る (抜けた島は交換の相手から外れる)
         let exec_duration = start.elapsed().as_secs_f32();
         // if exec_duration as u16 % 20 == 0 {
         //     println!("経過時間: {:?}", exec_duration);
         // }
-        if TIME_LIMIT < exec_duration {
+        if config.time_limit < exec_duration {
             break;
         }
 
-        // アニーリングの温度を 1 step ごとに低下させる
-        temperature = cool(temperature);
+        // 最良解が更新されない状態が続いたら温度を上げ直す
+        if let Some(reheat) = config.reheat {
+            if reheat.after_stagnant_steps <= stagnant_steps {
+                temperature = initial_temperature * reheat.ratio;
+                level = 0;
+                stagnant_steps = 0;
+                println!(
+                    "[INFO] reheat at step: {:?}, temperature: {:?}",
+                    _step, temperature
+                );
+                continue;
+            }
+        }
+
+        // アニーリングの温度を steps_per_temperature step ごとに低下させる
+        if (_step + 1) % config.steps_per_temperature.max(1) == 0 {
+            level += 1;
+            temperature =
+                config.cooling.cool(temperature, initial_temperature, level);
+        }
     }
 
     (best_solution, best_makespan, best_score, iterated_num)
@@ -106,19 +435,30 @@ pub fn run(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::optimization::algorithm::neighborhood::{
+        InsertionNeighborhood, SwapNeighborhood,
+    };
+    use crate::optimization::decoder::flow_shop::PermutationDecoder;
+    use crate::optimization::preprocess::runner::JobMaster;
+    use crate::optimization::test_fixtures::{
+        sample_job_master, ta001_job_master,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn test_is_replaceable() {
         // 確率的な関数のため、複数のケースをテスト
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
         // ケース1: current < next のとき、必ず true を返す
-        assert!(is_replaceable(1.0, 2.0, 1.0));
-        assert!(is_replaceable(100, 200, 1.0));
+        assert!(is_replaceable(1.0, 2.0, 1.0, &mut rng));
+        assert!(is_replaceable(100, 200, 1.0, &mut rng));
 
         // ケース2: current > next のとき、温度が低いと false になりやすい
         // 確率的な要素があるため、単純に値を比較できないが、
         // temperature を極端に小さくすれば false になるはず
         // (注: 実際のテストでは確率的要素を考慮する必要がある)
-        let result = is_replaceable(2.0, 1.0, 0.0000001);
+        let result = is_replaceable(2.0, 1.0, 0.0000001, &mut rng);
         // 確率が極めて低いので false であることを期待
         assert!(!result);
     }
@@ -126,7 +466,12 @@ mod tests {
     #[test]
     fn test_is_replaceable_with_equal_values() {
         // 値が等しい場合も確率計算に入るため、温度によって結果が変わる
-        let result_low_temp = is_replaceable(5.0, 5.0, 0.0000001);
+        let result_low_temp = is_replaceable(
+            5.0,
+            5.0,
+            0.0000001,
+            &mut ChaCha8Rng::seed_from_u64(2),
+        );
         // 同じ値の場合、差分が0なので確率は exp(0) = 1 となる
         // よって常に true になるはず
         assert!(result_low_temp);
@@ -134,60 +479,249 @@ mod tests {
 
     #[test]
     fn test_is_replaceable_with_extreme_values() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
         // 極端な温度のテスト
         // 温度が非常に高い場合、悪化する変更も受け入れやすくなる
-        let _prob_high_temp = is_replaceable(100.0, 50.0, 1000.0);
+        let _prob_high_temp = is_replaceable(100.0, 50.0, 1000.0, &mut rng);
         // 温度が高いと確率は高くなるが、乱数要素があるため確定的ではない
 
         // 温度が0の場合、悪化する変更は絶対に受け入れない
-        let prob_zero_temp = is_replaceable(10.0, 5.0, 0.0);
+        let prob_zero_temp = is_replaceable(10.0, 5.0, 0.0, &mut rng);
         assert!(!prob_zero_temp);
     }
 
     #[test]
-    fn test_swap_2_genes() {
-        // シード値を固定して乱数を再現可能に
-        let chromosome = types::Chromosome::from(vec![1, 2, 3, 4, 5]);
-        let original = chromosome.clone();
+    fn test_cool() {
+        let cooling = CoolingSchedule::Geometric { alpha: 0.9 };
+        let initial_temp = 1.0;
+        let cooled_temp = cooling.cool(initial_temp, initial_temp, 1);
+        assert_eq!(cooled_temp, 0.9 * initial_temp);
+
+        let initial_temp = 10.0;
+        let cooled_temp = cooling.cool(initial_temp, initial_temp, 1);
+        assert_eq!(cooled_temp, 0.9 * initial_temp);
+    }
 
-        // 本来なら乱数生成器を注入できるように関数を変更すべき
-        let result = swap_2_genes(&chromosome);
+    #[test]
+    fn test_cooling_schedules() {
+        let linear = CoolingSchedule::Linear { delta: 0.4 };
+        assert_eq!(linear.cool(1.0, 1.0, 1), 0.6);
+        assert_eq!(linear.cool(0.2, 1.0, 3), 0.0);
 
-        // 長さは同じ
-        assert_eq!(result.len(), original.len());
+        // 対数スケジュールは level 0 で初期温度になり、単調に下がる
+        let logarithmic = CoolingSchedule::Logarithmic;
+        assert_eq!(logarithmic.cool(10.0, 10.0, 0), 10.0);
+        assert!(
+            logarithmic.cool(10.0, 10.0, 2) < logarithmic.cool(10.0, 10.0, 1)
+        );
 
-        // 要素の順序は変わっているはず（2つの位置が交換されている）
-        // 注: 乱数によっては同じ位置が選ばれる可能性があるため、単純比較は困難
-        // そのため実際のテストでは乱数生成器のモック化や注入が必要
+        let lundy_mees = CoolingSchedule::LundyMees { beta: 1.0 };
+        assert_eq!(lundy_mees.cool(1.0, 1.0, 1), 0.5);
     }
 
     #[test]
-    fn test_swap_2_genes_identity() {
-        // 長さ1の配列では交換してもそのまま
-        let chromosome = types::Chromosome::from(vec![1]);
-        let original = chromosome.clone();
-        let result = swap_2_genes(&chromosome);
-        assert_eq!(result, original);
+    fn test_parse_cooling_schedule() {
+        assert_eq!(
+            CoolingSchedule::parse("geometric:0.95"),
+            Ok(CoolingSchedule::Geometric { alpha: 0.95 })
+        );
+        assert_eq!(
+            CoolingSchedule::parse("geometric"),
+            Ok(CoolingSchedule::Geometric {
+                alpha: DEFAULT_COOLING_RATE
+            })
+        );
+        assert_eq!(
+            CoolingSchedule::parse("lundy-mees:0.5"),
+            Ok(CoolingSchedule::LundyMees { beta: 0.5 })
+        );
+        assert_eq!(
+            CoolingSchedule::parse("logarithmic"),
+            Ok(CoolingSchedule::Logarithmic)
+        );
+        assert!(CoolingSchedule::parse("geometric:1.5").is_err());
+        assert!(CoolingSchedule::parse("linear:-1").is_err());
+        assert!(CoolingSchedule::parse("exponential").is_err());
     }
 
     #[test]
-    fn test_swap_2_genes_preserves_elements() {
-        // 要素の総和は変わらないことを確認
-        let chromosome = types::Chromosome::from(vec![1, 2, 3, 4, 5]);
-        let original_sum: u16 = chromosome.iter().sum();
-        let result = swap_2_genes(&chromosome);
-        let result_sum: u16 = result.iter().sum();
-        assert_eq!(original_sum, result_sum);
+    fn test_estimate_initial_temperature() {
+        let calculator = ScoreCalculator::new(sample_job_master());
+        let chromosome: types::Chromosome = vec![0, 1, 2, 0, 1, 2];
+        let temperature = estimate_initial_temperature(
+            &chromosome,
+            &calculator,
+            &SwapNeighborhood,
+            0.8,
+            &mut ChaCha8Rng::seed_from_u64(4),
+        );
+        // makespan は 12 - 17 程度なので、スコアの悪化幅は 1/12 - 1/17 以下
+        assert!(0.0 < temperature);
+        assert!(temperature < (1.0 / 12.0 - 1.0 / 17.0) / -(0.8f64.ln()));
     }
 
     #[test]
-    fn test_cool() {
-        let initial_temp = 1.0;
-        let cooled_temp = cool(initial_temp);
-        assert_eq!(cooled_temp, COOLING_RATE * initial_temp);
+    fn test_run_is_reproducible_with_same_seed() {
+        let config = AnnealingConfig {
+            steps: 500,
+            steps_per_temperature: 10,
+            ..AnnealingConfig::default()
+        };
+        let run_with_seed = |seed: u64| {
+            let chromosome: types::Chromosome = vec![2, 2, 1, 0, 1, 0];
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            run(
+                &chromosome,
+                ScoreCalculator::new(sample_job_master()),
+                &InsertionNeighborhood,
+                &config,
+                &mut rng,
+            )
+        };
+        let first = run_with_seed(7);
+        let second = run_with_seed(7);
+        assert_eq!(first.0, second.0);
+        assert_eq!(first.1, second.1);
+        assert_eq!(first.2.to_bits(), second.2.to_bits());
+        assert_eq!(first.3, second.3);
+    }
 
-        let initial_temp = 10.0;
-        let cooled_temp = cool(initial_temp);
-        assert_eq!(cooled_temp, COOLING_RATE * initial_temp);
+    /// inner の近傍を使い、propose に渡された現在の解を記録する
+    /// (incremental が false の場合は移動を染色体にして、差分評価を使わせない)
+    struct Recording {
+        inner: &'static dyn Neighborhood,
+        incremental: bool,
+        trajectory: std::sync::Mutex<Vec<types::Chromosome>>,
+    }
+
+    impl Neighborhood for Recording {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn neighbor(
+            &self,
+            chromosome: &types::Chromosome,
+            rng: &mut dyn RngCore,
+        ) -> types::Chromosome {
+            self.inner.neighbor(chromosome, rng)
+        }
+
+        fn propose(
+            &self,
+            chromosome: &types::Chromosome,
+            rng: &mut dyn RngCore,
+        ) -> NeighborMove {
+            self.trajectory.lock().unwrap().push(chromosome.clone());
+            let proposal: NeighborMove = self.inner.propose(chromosome, rng);
+            if self.incremental {
+                proposal
+            } else {
+                NeighborMove::Chromosome(proposal.into_chromosome(chromosome))
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_evaluator_keeps_trajectory() {
+        // 差分評価 (SwapEvaluator) と染色体全体のデコードで、同じ seed なら
+        // 各 step の現在の解と最良解が一致する
+        let job_master = JobMaster::try_new(
+            5,
+            3,
+            vec![
+                vec![3, 2, 4],
+                vec![2, 5, 3],
+                vec![4, 1, 2],
+                vec![3, 3, 1],
+                vec![1, 4, 2],
+            ],
+            vec![
+                vec![0, 1, 2],
+                vec![1, 0, 2],
+                vec![2, 1, 0],
+                vec![0, 2, 1],
+                vec![1, 2, 0],
+            ],
+        )
+        .unwrap();
+        let config = AnnealingConfig {
+            steps: 2_000,
+            steps_per_temperature: 20,
+            ..AnnealingConfig::default()
+        };
+        let run_with = |incremental: bool| {
+            let neighborhood = Recording {
+                inner: &SwapNeighborhood,
+                incremental,
+                trajectory: std::sync::Mutex::new(Vec::new()),
+            };
+            let chromosome: types::Chromosome =
+                (0..15).map(|gene| gene % 5).collect();
+            let mut rng = ChaCha8Rng::seed_from_u64(11);
+            let result = run(
+                &chromosome,
+                ScoreCalculator::new(job_master.clone()),
+                &neighborhood,
+                &config,
+                &mut rng,
+            );
+            (result, neighborhood.trajectory.into_inner().unwrap())
+        };
+        let (incremental, incremental_trajectory) = run_with(true);
+        let (full, full_trajectory) = run_with(false);
+        assert_eq!(incremental_trajectory.len(), 2_000);
+        assert_eq!(incremental_trajectory, full_trajectory);
+        assert_eq!(incremental.0, full.0);
+        assert_eq!(incremental.1, full.1);
+        assert_eq!(incremental.2.to_bits(), full.2.to_bits());
+        assert_eq!(
+            ScoreCalculator::new(job_master).calc_makespan(&incremental.0),
+            incremental.1
+        );
+    }
+
+    #[test]
+    fn test_insertion_evaluator_keeps_trajectory() {
+        // 順列フローショップの挿入移動で、差分評価 (InsertionEvaluator) と
+        // 染色体全体のデコードの結果が、同じ seed なら step ごとに一致する
+        let job_master: JobMaster = ta001_job_master();
+        let calculator = || {
+            ScoreCalculator::with_decoder(
+                job_master.clone(),
+                Box::new(PermutationDecoder),
+            )
+        };
+        assert!(calculator().insertion_evaluator(&[0, 1]).is_some());
+        let config = AnnealingConfig {
+            steps: 2_000,
+            steps_per_temperature: 20,
+            ..AnnealingConfig::default()
+        };
+        let run_with = |incremental: bool| {
+            let neighborhood = Recording {
+                inner: &InsertionNeighborhood,
+                incremental,
+                trajectory: std::sync::Mutex::new(Vec::new()),
+            };
+            let chromosome: types::Chromosome = (0..20).collect();
+            let mut rng = ChaCha8Rng::seed_from_u64(13);
+            let result = run(
+                &chromosome,
+                calculator(),
+                &neighborhood,
+                &config,
+                &mut rng,
+            );
+            (result, neighborhood.trajectory.into_inner().unwrap())
+        };
+        let (incremental, incremental_trajectory) = run_with(true);
+        let (full, full_trajectory) = run_with(false);
+        assert_eq!(incremental_trajectory.len(), 2_000);
+        assert_eq!(incremental_trajectory, full_trajectory);
+        assert_eq!(incremental.0, full.0);
+        assert_eq!(incremental.1, full.1);
+        assert_eq!(incremental.2.to_bits(), full.2.to_bits());
+        assert_eq!(calculator().calc_makespan(&incremental.0), incremental.1);
     }
 }
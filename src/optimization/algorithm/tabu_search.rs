@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use rand::{Rng, RngCore};
+use serde::Serialize;
+
+use crate::optimization::algorithm::neighborhood::CriticalBlockMoves;
+use crate::optimization::decoder::disjunctive_graph::{
+    DisjunctiveGraph, GraphSchedule, MachineMove, OperationId,
+};
+use crate::optimization::decoder::schedule::Schedule;
+use crate::optimization::decoder::score::{
+    Decoder, Evaluation, ScoreCalculator, ScoreEvaluator, SemiActiveDecoder,
+};
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types;
+
+static DEFAULT_ITERATIONS: u32 = 10_000;
+static DEFAULT_RESTART_AFTER: u32 = 1_000;
+static DEFAULT_ELITE_SIZE: usize = 5;
+static DEFAULT_TIME_LIMIT: f32 = 600.0; // seconds
+
+/// タブー探索の設定
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TabuConfig {
+    /// クリティカルブロック上の移動の種類
+    pub moves: CriticalBlockMoves,
+    /// タブー期間の範囲 (最小, 最大)。反復ごとにこの範囲から選び直す
+    /// None の場合は instance の規模から決める (default_tenure)
+    pub tenure: Option<(u32, u32)>,
+    /// 最大反復回数
+    pub iterations: u32,
+    /// 最良解が更新されない反復回数がこの値に達したら、エリート解から再開する
+    pub restart_after: u32,
+    /// 再開に使うエリート解の数
+    pub elite_size: usize,
+    /// 実行時間の上限 (秒)
+    pub time_limit: f32,
+}
+
+impl Default for TabuConfig {
+    fn default() -> Self {
+        TabuConfig {
+            moves: CriticalBlockMoves::N7,
+            tenure: None,
+            iterations: DEFAULT_ITERATIONS,
+            restart_after: DEFAULT_RESTART_AFTER,
+            elite_size: DEFAULT_ELITE_SIZE,
+            time_limit: DEFAULT_TIME_LIMIT,
+        }
+    }
+}
+
+/// instance の規模に応じたタブー期間の範囲
+/// L = 10 + job_size / machine_series_size として [L, 1.5L]
+pub fn default_tenure(job_master: &JobMaster) -> (u32, u32) {
+    let base: u32 = 10
+        + (job_master.job_size / job_master.machine_series_size.max(1)) as u32;
+    (base, base + base / 2)
+}
+
+/// "7" (固定) または "7:12" (最小:最大) の形式のタブー期間の指定を解釈する
+pub fn parse_tenure(spec: &str) -> Result<(u32, u32), String> {
+    let parse = |value: &str| -> Result<u32, String> {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|value| 0 < *value)
+            .ok_or_else(|| format!("invalid tenure {:?}", spec))
+    };
+    let (min_tenure, max_tenure) = match spec.split_once(':') {
+        Some((min_tenure, max_tenure)) => {
+            (parse(min_tenure)?, parse(max_tenure)?)
+        }
+        None => (parse(spec)?, parse(spec)?),
+    };
+    if max_tenure < min_tenure {
+        return Err(format!("invalid tenure {:?} (min > max)", spec));
+    }
+    Ok((min_tenure, max_tenure))
+}
+
+/// 反転した disjunctive arc を記録するタブーリスト
+///
+/// (a, b) が記録されている間は、a を b より先に戻す移動を禁止する
+struct TabuList {
+    /// arc ごとのタブーが解ける反復番号
+    expires: HashMap<(OperationId, OperationId), u32>,
+}
+
+impl TabuList {
+    fn new() -> Self {
+        TabuList {
+            expires: HashMap::new(),
+        }
+    }
+
+    /// 移動で反転する arc のうち、タブーな arc を元に戻すものがあるか
+    fn is_tabu(
+        &self,
+        reversed_arcs: &[(OperationId, OperationId)],
+        iteration: u32,
+    ) -> bool {
+        reversed_arcs.iter().any(|(a, b)| {
+            self.expires
+                .get(&(*b, *a))
+                .is_some_and(|expire| iteration < *expire)
+        })
+    }
+
+    /// 移動で反転した arc を tenure 反復の間タブーにする
+    fn add(
+        &mut self,
+        reversed_arcs: &[(OperationId, OperationId)],
+        iteration: u32,
+        tenure: u32,
+    ) {
+        for arc in reversed_arcs {
+            self.expires.insert(*arc, iteration + tenure);
+        }
+        self.expires.retain(|_, expire| iteration < *expire);
+    }
+
+    fn clear(&mut self) {
+        self.expires.clear();
+    }
+}
+
+/// 移動後の解の候補
+struct Candidate {
+    chromosome: types::Chromosome,
+    evaluation: Evaluation,
+    reversed_arcs: Vec<(OperationId, OperationId)>,
+    is_tabu: bool,
+}
+
+/// 現在の解のクリティカルブロック上の移動をすべて評価する
+/// 閉路ができる (実行不可能な) 移動は除く
+fn candidates(
+    job_master: &JobMaster,
+    chromosome: &types::Chromosome,
+    scorer: &mut ScoreEvaluator,
+    moves: CriticalBlockMoves,
+) -> Vec<Candidate> {
+    let schedule: Schedule = SemiActiveDecoder.decode(job_master, chromosome);
+    let graph: DisjunctiveGraph =
+        DisjunctiveGraph::from_schedule(job_master, &schedule);
+    let evaluated: GraphSchedule = match graph.evaluate() {
+        Some(evaluated) => evaluated,
+        None => return Vec::new(),
+    };
+    let path: Vec<OperationId> = graph.critical_path(&evaluated);
+    let machine_moves: Vec<MachineMove> = match moves {
+        CriticalBlockMoves::N5 => graph.n5_moves(&path),
+        CriticalBlockMoves::N7 => graph.n7_moves(&path),
+    };
+
+    machine_moves
+        .iter()
+        .filter_map(|machine_move| {
+            let moved: DisjunctiveGraph = graph.apply(machine_move);
+            let moved_evaluated: GraphSchedule = moved.evaluate()?;
+            let chromosome: types::Chromosome =
+                moved.to_chromosome(&moved_evaluated);
+            Some(Candidate {
+                evaluation: scorer.evaluate(&chromosome),
+                chromosome,
+                reversed_arcs: graph.reversed_arcs(machine_move),
+                is_tabu: false,
+            })
+        })
+        .collect()
+}
+
+/// スコアの高い順に最大 elite_size 個の異なる解を保持する
+fn update_elites(
+    elites: &mut Vec<(types::Chromosome, Evaluation)>,
+    chromosome: &types::Chromosome,
+    evaluation: Evaluation,
+    elite_size: usize,
+) {
+    if elites.iter().any(|(elite, _)| elite == chromosome) {
+        return;
+    }
+    elites.push((chromosome.clone(), evaluation));
+    elites.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+    elites.truncate(elite_size);
+}
+
+/// クリティカルブロック上の移動によるタブー探索で解を探索する
+///
+/// - 移動で反転した disjunctive arc をタブーにし、元に戻す移動を禁止する
+/// - タブーな移動でも最良解を更新する場合は許可する (aspiration)
+/// - タブー期間は反復ごとに tenure の範囲からランダムに選ぶ
+/// - 最良解が更新されない状態が続いたら、エリート解から探索を再開する
+///
+/// 移動は semi-active スケジュールから選ぶため、calculator のデコーダも
+/// semi-active であること (la40::check_graph_support で確認する)
+/// 戻り値は simulated_annealing::run と同じく
+/// (最良解, その makespan, そのスコア, 反復回数)
+pub fn run(
+    chromosome: &types::Chromosome,
+    calculator: ScoreCalculator,
+    config: &TabuConfig,
+    rng: &mut dyn RngCore,
+) -> (types::Chromosome, types::Time, f64, u32) {
+    let start: Instant = Instant::now();
+    let job_master: JobMaster = calculator.job_master().clone();
+    let (min_tenure, max_tenure) =
+        config.tenure.unwrap_or_else(|| default_tenure(&job_master));
+    let max_tenure: u32 = max_tenure.max(min_tenure);
+    println!("[INFO] tenure: {:?}..={:?}", min_tenure, max_tenure);
+
+    let mut tabu_list: TabuList = TabuList::new();
+    let mut current_solution: types::Chromosome = chromosome.clone();
+    let mut best_solution: types::Chromosome = chromosome.clone();
+    // 評価用のバッファは探索全体で使い回す
+    let mut scorer: ScoreEvaluator = calculator.evaluator();
+    let mut best_evaluation: Evaluation = scorer.evaluate(chromosome);
+    let mut elites: Vec<(types::Chromosome, Evaluation)> = Vec::new();
+    update_elites(
+        &mut elites,
+        &best_solution,
+        best_evaluation,
+        config.elite_size.max(1),
+    );
+
+    let mut stagnant_iterations: u32 = 0;
+    let mut iterated_num: u32 = 0;
+    for iteration in 0..config.iterations {
+        iterated_num += 1;
+        let mut candidates: Vec<Candidate> = candidates(
+            &job_master,
+            &current_solution,
+            &mut scorer,
+            config.moves,
+        );
+        for candidate in candidates.iter_mut() {
+            candidate.is_tabu =
+                tabu_list.is_tabu(&candidate.reversed_arcs, iteration);
+        }
+
+        // タブーでない移動と、最良解を更新するタブーな移動 (aspiration) から選ぶ
+        // すべてタブーな場合は、タブーを無視して最も良い移動を選ぶ
+        let admissible = |candidate: &&Candidate| {
+            !candidate.is_tabu
+                || best_evaluation.score < candidate.evaluation.score
+        };
+        let chosen: Option<&Candidate> = candidates
+            .iter()
+            .filter(admissible)
+            .max_by(|a, b| a.evaluation.score.total_cmp(&b.evaluation.score))
+            .or_else(|| {
+                candidates.iter().max_by(|a, b| {
+                    a.evaluation.score.total_cmp(&b.evaluation.score)
+                })
+            });
+
+        stagnant_iterations += 1;
+        match chosen {
+            Some(candidate) => {
+                let tenure: u32 = rng.random_range(min_tenure..=max_tenure);
+                tabu_list.add(&candidate.reversed_arcs, iteration, tenure);
+                current_solution = candidate.chromosome.clone();
+                update_elites(
+                    &mut elites,
+                    &current_solution,
+                    candidate.evaluation,
+                    config.elite_size.max(1),
+                );
+                if best_evaluation.score < candidate.evaluation.score {
+                    best_evaluation = candidate.evaluation;
+                    best_solution = current_solution.clone();
+                    stagnant_iterations = 0;
+                    println!(
+                        "iteration: {:?}, score: {:?}, makespan: {:?}, objective: {:?}",
+                        iteration,
+                        best_evaluation.score,
+                        best_evaluation.makespan,
+                        best_evaluation.objective_value
+                    );
+                }
+            }
+            // 移動候補がない (N5 で最適性が確定した等) 場合は再開する
+            None => stagnant_iterations = config.restart_after,
+        }
+
+        if config.time_limit < start.elapsed().as_secs_f32() {
+            break;
+        }
+
+        // エリート解からの再開
+        if config.restart_after <= stagnant_iterations {
+            let index: usize = rng.random_range(0..elites.len());
+            current_solution = elites[index].0.clone();
+            tabu_list.clear();
+            stagnant_iterations = 0;
+        }
+    }
+
+    (
+        best_solution,
+        best_evaluation.makespan,
+        best_evaluation.score,
+        iterated_num,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::test_fixtures::medium_job_master;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_tabu_list() {
+        let mut tabu_list = TabuList::new();
+        tabu_list.add(&[(0, 3), (0, 4)], 0, 2);
+        // 3 を 0 より後ろに戻す (arc (3, 0) を反転する) 移動はタブー
+        assert!(tabu_list.is_tabu(&[(3, 0)], 1));
+        assert!(!tabu_list.is_tabu(&[(0, 3)], 1));
+        assert!(!tabu_list.is_tabu(&[(5, 0)], 1));
+        // タブー期間が過ぎると解除される
+        assert!(!tabu_list.is_tabu(&[(3, 0)], 2));
+    }
+
+    #[test]
+    fn test_tenure() {
+        assert_eq!(default_tenure(&medium_job_master()), (11, 16));
+        assert_eq!(parse_tenure("7"), Ok((7, 7)));
+        assert_eq!(parse_tenure("7:12"), Ok((7, 12)));
+        assert!(parse_tenure("12:7").is_err());
+        assert!(parse_tenure("0").is_err());
+        assert!(parse_tenure("a:3").is_err());
+    }
+
+    #[test]
+    fn test_run_improves_and_is_reproducible() {
+        let job_master = medium_job_master();
+        let initial: types::Chromosome =
+            vec![3, 3, 3, 2, 2, 2, 1, 1, 1, 0, 0, 0];
+        let initial_makespan: types::Time =
+            ScoreCalculator::new(job_master.clone()).calc_makespan(&initial);
+        for moves in [CriticalBlockMoves::N5, CriticalBlockMoves::N7] {
+            let config = TabuConfig {
+                moves,
+                iterations: 200,
+                restart_after: 20,
+                ..TabuConfig::default()
+            };
+            let run_with_seed = |seed: u64| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                run(
+                    &initial,
+                    ScoreCalculator::new(job_master.clone()),
+                    &config,
+                    &mut rng,
+                )
+            };
+            let (solution, makespan, score, iterated_num) = run_with_seed(3);
+            assert!(makespan < initial_makespan);
+            assert_eq!(iterated_num, 200);
+            assert_eq!(
+                ScoreCalculator::new(job_master.clone())
+                    .calc_makespan(&solution),
+                makespan
+            );
+            assert_eq!(run_with_seed(3), (solution, makespan, score, 200));
+        }
+    }
+}
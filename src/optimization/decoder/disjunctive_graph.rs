@@ -0,0 +1,471 @@
+use std::collections::VecDeque;
+
+use crate::optimization::decoder::schedule::Schedule;
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::{self, Time};
+
+/// 選言グラフ (disjunctive graph) 上の operation の番号
+/// job * machine_series_size + operation で表す
+pub type OperationId = usize;
+
+/// actor ごとの処理順を固定した選言グラフ
+///
+/// job 内の順序 (conjunctive arc) と actor ごとの処理順 (disjunctive arc) から
+/// 各 operation の最早開始時刻、クリティカルパスを求める
+#[derive(Debug, Clone)]
+pub struct DisjunctiveGraph<'a> {
+    job_master: &'a JobMaster,
+    /// actor ごとの operation の処理順
+    pub machine_orders: Vec<Vec<OperationId>>,
+}
+
+/// 選言グラフの最長路計算の結果 (semi-active schedule)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphSchedule {
+    /// operation ごとの開始時刻
+    pub starts: Vec<Time>,
+    /// トポロジカル順に並べた operation
+    pub order: Vec<OperationId>,
+    pub makespan: Time,
+}
+
+/// actor の処理順の中で operation を移動する操作
+/// (machine_orders[actor_id] から from の位置の operation を取り出し to の位置に挿入)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachineMove {
+    pub actor_id: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl<'a> DisjunctiveGraph<'a> {
+    /// スケジュールの actor ごとの処理順から選言グラフを生成
+    pub fn from_schedule(
+        job_master: &'a JobMaster,
+        schedule: &Schedule,
+    ) -> Self {
+        let machine_orders: Vec<Vec<OperationId>> = schedule
+            .machine_sequences
+            .iter()
+            .map(|sequence| {
+                sequence
+                    .iter()
+                    .map(|index| {
+                        let operation = &schedule.operations[*index];
+                        operation.job as usize * job_master.machine_series_size
+                            + operation.operation
+                    })
+                    .collect()
+            })
+            .collect();
+
+        DisjunctiveGraph {
+            job_master,
+            machine_orders,
+        }
+    }
+
+    pub fn job_master(&self) -> &JobMaster {
+        self.job_master
+    }
+
+    /// operation 数
+    pub fn size(&self) -> usize {
+        self.job_master.job_size * self.job_master.machine_series_size
+    }
+
+    /// operation が属する job
+    pub fn job_of(&self, id: OperationId) -> usize {
+        id / self.job_master.machine_series_size
+    }
+
+    /// job 内での operation の番号
+    pub fn operation_of(&self, id: OperationId) -> usize {
+        id % self.job_master.machine_series_size
+    }
+
+    /// operation を処理する actor
+    pub fn actor_of(&self, id: OperationId) -> usize {
+        self.job_master.actor_sequences[self.job_of(id)][self.operation_of(id)]
+            as usize
+    }
+
+    /// operation の実行時間
+    pub fn exec_time(&self, id: OperationId) -> Time {
+        self.job_master.exec_times[self.job_of(id)][self.operation_of(id)]
+    }
+
+    /// job 内で直前の operation
+    pub fn job_predecessor(&self, id: OperationId) -> Option<OperationId> {
+        (0 < self.operation_of(id)).then(|| id - 1)
+    }
+
+    /// job 内で直後の operation
+    pub fn job_successor(&self, id: OperationId) -> Option<OperationId> {
+        (self.operation_of(id) + 1 < self.job_master.machine_series_size)
+            .then(|| id + 1)
+    }
+
+    /// operation ごとの actor 内での処理順の位置
+    pub fn machine_positions(&self) -> Vec<usize> {
+        let mut positions: Vec<usize> = vec![0; self.size()];
+        for order in self.machine_orders.iter() {
+            for (position, id) in order.iter().enumerate() {
+                positions[*id] = position;
+            }
+        }
+        positions
+    }
+
+    /// actor 内で直前の operation を operation ごとに求める
+    pub fn machine_predecessors(&self) -> Vec<Option<OperationId>> {
+        let mut predecessors: Vec<Option<OperationId>> =
+            vec![None; self.size()];
+        for order in self.machine_orders.iter() {
+            for pair in order.windows(2) {
+                predecessors[pair[1]] = Some(pair[0]);
+            }
+        }
+        predecessors
+    }
+
+    /// actor 内で直後の operation を operation ごとに求める
+    pub fn machine_successors(&self) -> Vec<Option<OperationId>> {
+        let mut successors: Vec<Option<OperationId>> = vec![None; self.size()];
+        for order in self.machine_orders.iter() {
+            for pair in order.windows(2) {
+                successors[pair[0]] = Some(pair[1]);
+            }
+        }
+        successors
+    }
+
+    /// 最長路計算で各 operation の最早開始時刻を求める
+    /// 処理順に閉路がある (実行不可能な) 場合は None を返す
+    pub fn evaluate(&self) -> Option<GraphSchedule> {
+        let size: usize = self.size();
+        let machine_successors: Vec<Option<OperationId>> =
+            self.machine_successors();
+        let mut in_degrees: Vec<usize> = vec![0; size];
+        for (id, machine_successor) in machine_successors.iter().enumerate() {
+            for next in [self.job_successor(id), *machine_successor]
+                .into_iter()
+                .flatten()
+            {
+                in_degrees[next] += 1;
+            }
+        }
+
+        let mut starts: Vec<Time> = (0..size)
+            .map(|id| self.job_master.release_date(self.job_of(id)))
+            .collect();
+        let mut queue: VecDeque<OperationId> =
+            (0..size).filter(|id| in_degrees[*id] == 0).collect();
+        let mut order: Vec<OperationId> = Vec::with_capacity(size);
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            let end: Time = starts[id] + self.exec_time(id);
+            for next in [self.job_successor(id), machine_successors[id]]
+                .into_iter()
+                .flatten()
+            {
+                starts[next] = starts[next].max(end);
+                in_degrees[next] -= 1;
+                if in_degrees[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+        if order.len() < size {
+            return None;
+        }
+
+        let makespan: Time = (0..size)
+            .map(|id| starts[id] + self.exec_time(id))
+            .max()
+            .unwrap_or(0);
+        Some(GraphSchedule {
+            starts,
+            order,
+            makespan,
+        })
+    }
+
+    /// クリティカルパス (makespan を決める operation の列) を先頭から順に返す
+    /// 同じ時刻に終わる先行 operation が複数ある場合は actor 内の先行を優先する
+    pub fn critical_path(&self, evaluated: &GraphSchedule) -> Vec<OperationId> {
+        let size: usize = self.size();
+        let end_of =
+            |id: OperationId| evaluated.starts[id] + self.exec_time(id);
+        let machine_predecessors: Vec<Option<OperationId>> =
+            self.machine_predecessors();
+
+        let mut path: Vec<OperationId> = Vec::new();
+        let mut current: Option<OperationId> =
+            (0..size).find(|id| end_of(*id) == evaluated.makespan);
+        while let Some(id) = current {
+            path.push(id);
+            let start: Time = evaluated.starts[id];
+            current = [machine_predecessors[id], self.job_predecessor(id)]
+                .into_iter()
+                .flatten()
+                .find(|prev| end_of(*prev) == start);
+        }
+        path.reverse();
+        path
+    }
+
+    /// クリティカルパスを同じ actor で連続する operation ごとのブロックに分割する
+    /// (長さ 1 のブロックも含む)
+    pub fn critical_blocks(
+        &self,
+        path: &[OperationId],
+    ) -> Vec<Vec<OperationId>> {
+        let mut blocks: Vec<Vec<OperationId>> = Vec::new();
+        for id in path.iter() {
+            match blocks.last_mut() {
+                Some(block)
+                    if self.actor_of(block[block.len() - 1])
+                        == self.actor_of(*id) =>
+                {
+                    block.push(*id)
+                }
+                _ => blocks.push(vec![*id]),
+            }
+        }
+        blocks
+    }
+
+    /// Nowicki–Smutnicki の N5 近傍
+    ///
+    /// クリティカルブロックの先頭2つ、末尾2つの operation を入れ替える
+    /// (最初のブロックの先頭と最後のブロックの末尾は改善につながらないため除く)
+    pub fn n5_moves(&self, path: &[OperationId]) -> Vec<MachineMove> {
+        let positions: Vec<usize> = self.machine_positions();
+        let blocks: Vec<Vec<OperationId>> = self.critical_blocks(path);
+        let mut moves: Vec<MachineMove> = Vec::new();
+        for (i, block) in blocks.iter().enumerate() {
+            if block.len() < 2 {
+                continue;
+            }
+            let actor_id: usize = self.actor_of(block[0]);
+            let first: usize = positions[block[0]];
+            let last: usize = positions[block[block.len() - 1]];
+            if 0 < i {
+                moves.push(MachineMove {
+                    actor_id,
+                    from: first,
+                    to: first + 1,
+                });
+            }
+            if i + 1 < blocks.len() && !(0 < i && block.len() == 2) {
+                moves.push(MachineMove {
+                    actor_id,
+                    from: last - 1,
+                    to: last,
+                });
+            }
+        }
+        moves
+    }
+
+    /// Zhang らの N7 近傍
+    ///
+    /// クリティカルブロック内の operation をブロックの先頭・末尾へ移動する、
+    /// またはブロックの先頭・末尾の operation をブロックの内部へ移動する
+    pub fn n7_moves(&self, path: &[OperationId]) -> Vec<MachineMove> {
+        let positions: Vec<usize> = self.machine_positions();
+        let mut moves: Vec<MachineMove> = Vec::new();
+        for block in self.critical_blocks(path) {
+            if block.len() < 2 {
+                continue;
+            }
+            let actor_id: usize = self.actor_of(block[0]);
+            let first: usize = positions[block[0]];
+            let last: usize = positions[block[block.len() - 1]];
+            let mut push = |from: usize, to: usize| {
+                let machine_move = MachineMove { actor_id, from, to };
+                if from != to && !moves.contains(&machine_move) {
+                    moves.push(machine_move);
+                }
+            };
+            for position in first..=last {
+                // ブロック内の operation を先頭・末尾へ
+                push(position, first);
+                push(position, last);
+                // 先頭・末尾の operation をブロック内部へ
+                push(first, position);
+                push(last, position);
+            }
+        }
+        moves
+    }
+
+    /// actor の処理順を変更した選言グラフを返す
+    pub fn apply(&self, machine_move: &MachineMove) -> DisjunctiveGraph<'a> {
+        let mut machine_orders: Vec<Vec<OperationId>> =
+            self.machine_orders.clone();
+        let order: &mut Vec<OperationId> =
+            &mut machine_orders[machine_move.actor_id];
+        let id: OperationId = order.remove(machine_move.from);
+        order.insert(machine_move.to, id);
+
+        DisjunctiveGraph {
+            job_master: self.job_master,
+            machine_orders,
+        }
+    }
+
+    /// 移動によって向きが反転する disjunctive arc を返す
+    /// (a, b) は移動前に a が b より先に処理され、移動後は b が先になることを表す
+    pub fn reversed_arcs(
+        &self,
+        machine_move: &MachineMove,
+    ) -> Vec<(OperationId, OperationId)> {
+        let order: &Vec<OperationId> =
+            &self.machine_orders[machine_move.actor_id];
+        let id: OperationId = order[machine_move.from];
+        if machine_move.from < machine_move.to {
+            order[machine_move.from + 1..=machine_move.to]
+                .iter()
+                .map(|other| (id, *other))
+                .collect()
+        } else {
+            order[machine_move.to..machine_move.from]
+                .iter()
+                .map(|other| (*other, id))
+                .collect()
+        }
+    }
+
+    /// トポロジカル順から染色体 (job_id 列) を生成
+    /// semi-active デコーダでデコードすると同じ処理順のスケジュールになる
+    pub fn to_chromosome(
+        &self,
+        evaluated: &GraphSchedule,
+    ) -> types::Chromosome {
+        evaluated
+            .order
+            .iter()
+            .map(|id| self.job_of(*id) as u16)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::score::{Decoder, SemiActiveDecoder};
+    use crate::optimization::test_fixtures::sample_job_master;
+
+    #[test]
+    fn test_evaluate_matches_decoder() {
+        let job_master = sample_job_master();
+        let chromosome: types::Chromosome = vec![0, 2, 2, 0, 1, 1];
+        let schedule = SemiActiveDecoder.decode(&job_master, &chromosome);
+        let graph = DisjunctiveGraph::from_schedule(&job_master, &schedule);
+        let evaluated = graph.evaluate().unwrap();
+        assert_eq!(evaluated.makespan, schedule.makespan);
+
+        // トポロジカル順の染色体は同じスケジュールにデコードされる
+        let rebuilt = graph.to_chromosome(&evaluated);
+        assert_eq!(
+            SemiActiveDecoder.decode(&job_master, &rebuilt).makespan,
+            schedule.makespan
+        );
+    }
+
+    #[test]
+    fn test_critical_path_and_blocks() {
+        // actor 0: job0(0-3) job2(3-7) job1(12-17)
+        // actor 1: job2(7-8) job0(8-10) job1(10-12)
+        let job_master = sample_job_master();
+        let schedule =
+            SemiActiveDecoder.decode(&job_master, &[0, 2, 2, 0, 1, 1]);
+        let graph = DisjunctiveGraph::from_schedule(&job_master, &schedule);
+        let evaluated = graph.evaluate().unwrap();
+        let path = graph.critical_path(&evaluated);
+        // job0 -> job2 (actor 0) -> job2 -> job0 -> job1 (actor 1) -> job1 (actor 0)
+        assert_eq!(path, vec![0, 4, 5, 1, 2, 3]);
+        assert_eq!(
+            graph.critical_blocks(&path),
+            vec![vec![0, 4], vec![5, 1, 2], vec![3]]
+        );
+
+        // 最初のブロックは末尾の入れ替え、2番目のブロックは先頭と末尾の入れ替え
+        let moves = graph.n5_moves(&path);
+        assert_eq!(
+            moves,
+            vec![
+                MachineMove {
+                    actor_id: 0,
+                    from: 0,
+                    to: 1
+                },
+                MachineMove {
+                    actor_id: 1,
+                    from: 0,
+                    to: 1
+                },
+                MachineMove {
+                    actor_id: 1,
+                    from: 1,
+                    to: 2
+                },
+            ]
+        );
+        assert!(moves.iter().all(|m| graph.n7_moves(&path).contains(m)));
+    }
+
+    #[test]
+    fn test_apply_detects_cycle() {
+        let job_master = sample_job_master();
+        let schedule =
+            SemiActiveDecoder.decode(&job_master, &[0, 2, 2, 0, 1, 1]);
+        let graph = DisjunctiveGraph::from_schedule(&job_master, &schedule);
+
+        // actor 1 の job1 (op 1) を先頭へ移動すると makespan が改善する
+        let improved = graph
+            .apply(&MachineMove {
+                actor_id: 1,
+                from: 2,
+                to: 0,
+            })
+            .evaluate()
+            .unwrap();
+        assert_eq!(improved.makespan, 12);
+
+        // job1 の op 0 (actor 1) を job1 の op 1 (actor 0) より後に処理できない順序は閉路になる
+        let mut cyclic = graph.clone();
+        cyclic.machine_orders[0] = vec![3, 0, 4];
+        cyclic.machine_orders[1] = vec![5, 1, 2];
+        assert!(cyclic.evaluate().is_none());
+    }
+
+    #[test]
+    fn test_reversed_arcs() {
+        let job_master = sample_job_master();
+        let graph = DisjunctiveGraph {
+            job_master: &job_master,
+            machine_orders: vec![vec![0, 3, 4], vec![2, 1, 5]],
+        };
+        // 後ろへの移動: 移動した operation と追い越した operation の arc が反転
+        assert_eq!(
+            graph.reversed_arcs(&MachineMove {
+                actor_id: 0,
+                from: 0,
+                to: 2
+            }),
+            vec![(0, 3), (0, 4)]
+        );
+        // 前への移動
+        assert_eq!(
+            graph.reversed_arcs(&MachineMove {
+                actor_id: 1,
+                from: 2,
+                to: 1
+            }),
+            vec![(1, 5)]
+        );
+    }
+}
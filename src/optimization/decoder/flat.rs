@@ -0,0 +1,376 @@
+use crate::optimization::preprocess::calendar::{self, Calendars};
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::Time;
+
+/// semi-active デコード用に平坦化した instance
+///
+/// operation は job * machine_series_size + operation の添字で参照する
+/// (JobMaster の Vec<Vec<_>> をたどるより参照が少ない)
+#[derive(Debug, Clone)]
+pub struct FlatInstance {
+    job_size: usize,
+    machine_series_size: usize,
+    exec_times: Vec<Time>,
+    actors: Vec<u16>,
+    release_dates: Vec<Time>,
+    /// 段取り時間 ((actor * job_size + 前の job) * job_size + 次の job の添字、
+    /// 段取り時間がない場合は空)
+    setup_times: Vec<Time>,
+    calendars: Option<Calendars>,
+}
+
+/// デコード途中の状態 (使い回すスクラッチバッファ)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeState {
+    /// job ごとに次に処理する operation の添字
+    next_operations: Vec<usize>,
+    /// job ごとの直前の operation の終了時刻
+    job_ready_times: Vec<Time>,
+    /// actor ごとの直前の operation の終了時刻
+    actor_ready_times: Vec<Time>,
+    /// actor ごとの直前の operation の job
+    actor_last_jobs: Vec<Option<u16>>,
+}
+
+impl FlatInstance {
+    /// FJSP・オープンショップは operation の actor・順序が決まっていないため
+    /// 平坦化できず None を返す
+    pub fn new(job_master: &JobMaster) -> Option<Self> {
+        if job_master.is_flexible() || job_master.open_shop {
+            return None;
+        }
+        Some(FlatInstance {
+            job_size: job_master.job_size,
+            machine_series_size: job_master.machine_series_size,
+            exec_times: job_master.exec_times.concat(),
+            actors: job_master.actor_sequences.concat(),
+            release_dates: (0..job_master.job_size)
+                .map(|job| job_master.release_date(job))
+                .collect(),
+            setup_times: job_master
+                .setup_times
+                .iter()
+                .flatten()
+                .flatten()
+                .flatten()
+                .copied()
+                .collect(),
+            calendars: job_master.calendars.clone(),
+        })
+    }
+
+    /// 初期状態のスクラッチバッファを作る (ここでのみ確保する)
+    pub fn state(&self) -> DecodeState {
+        let mut state: DecodeState = DecodeState {
+            next_operations: vec![0; self.job_size],
+            job_ready_times: vec![0; self.job_size],
+            actor_ready_times: vec![0; self.machine_series_size],
+            actor_last_jobs: vec![None; self.machine_series_size],
+        };
+        self.reset(&mut state);
+        state
+    }
+
+    /// スクラッチバッファを初期状態に戻す
+    pub fn reset(&self, state: &mut DecodeState) {
+        for (job, next_operation) in
+            state.next_operations.iter_mut().enumerate()
+        {
+            *next_operation = job * self.machine_series_size;
+        }
+        state.job_ready_times.copy_from_slice(&self.release_dates);
+        state.actor_ready_times.fill(0);
+        state.actor_last_jobs.fill(None);
+    }
+
+    /// job の次の operation を actor の末尾に割り当てる
+    #[inline]
+    fn step(&self, state: &mut DecodeState, job: u16) {
+        let job: usize = job as usize;
+        let index: usize = state.next_operations[job];
+        let actor: usize = self.actors[index] as usize;
+        let setup: Time = match state.actor_last_jobs[actor] {
+            Some(previous) if !self.setup_times.is_empty() => {
+                self.setup_times[(actor * self.job_size + previous as usize)
+                    * self.job_size
+                    + job]
+            }
+            _ => 0,
+        };
+        let (_, end): (Time, Time) = calendar::earliest_window(
+            self.calendars.as_ref(),
+            actor,
+            state.job_ready_times[job],
+            state.actor_ready_times[actor],
+            setup,
+            self.exec_times[index],
+        );
+        state.job_ready_times[job] = end;
+        state.actor_ready_times[actor] = end;
+        state.actor_last_jobs[actor] = Some(job as u16);
+        state.next_operations[job] = index + 1;
+    }
+
+    /// state までデコードした時点の makespan
+    #[inline]
+    fn current_makespan(state: &DecodeState) -> Time {
+        state.actor_ready_times.iter().copied().max().unwrap_or(0)
+    }
+
+    /// 染色体を semi-active デコードした makespan
+    /// state を使い回すため、呼び出しごとのメモリ確保はない
+    pub fn makespan(
+        &self,
+        chromosome: &[u16],
+        state: &mut DecodeState,
+    ) -> Time {
+        self.reset(state);
+        for job in chromosome {
+            self.step(state, *job);
+        }
+        FlatInstance::current_makespan(state)
+    }
+}
+
+/// 2つの遺伝子の交換 (swap 近傍) による makespan の変化を差分で評価する
+///
+/// interval 遺伝子ごとにデコード途中の状態 (チェックポイント) を保存しておき、
+/// 交換する位置の直前のチェックポイントから後ろだけをデコードし直す
+#[derive(Debug, Clone)]
+pub struct SwapEvaluator<'a> {
+    instance: &'a FlatInstance,
+    chromosome: Vec<u16>,
+    interval: usize,
+    /// checkpoints[c]: c * interval 番目の遺伝子をデコードする直前の状態
+    checkpoints: Vec<DecodeState>,
+    work: DecodeState,
+    makespan: Time,
+}
+
+impl<'a> SwapEvaluator<'a> {
+    /// interval は 1 以上 (染色体長の平方根程度が目安)
+    pub fn new(
+        instance: &'a FlatInstance,
+        chromosome: &[u16],
+        interval: usize,
+    ) -> Self {
+        let interval: usize = interval.max(1);
+        let work: DecodeState = instance.state();
+        let checkpoints: Vec<DecodeState> =
+            vec![work.clone(); chromosome.len().div_ceil(interval).max(1)];
+        let mut evaluator: SwapEvaluator = SwapEvaluator {
+            instance,
+            chromosome: chromosome.to_vec(),
+            interval,
+            checkpoints,
+            work,
+            makespan: 0,
+        };
+        evaluator.rebuild_from(0);
+        evaluator
+    }
+
+    /// 評価する染色体を chromosome に置き換える (長さは変えないこと)
+    pub fn reset(&mut self, chromosome: &[u16]) {
+        self.chromosome.copy_from_slice(chromosome);
+        self.rebuild_from(0);
+    }
+
+    pub fn chromosome(&self) -> &[u16] {
+        &self.chromosome
+    }
+
+    /// 現在の染色体の makespan
+    pub fn makespan(&self) -> Time {
+        self.makespan
+    }
+
+    /// first 番目と second 番目の遺伝子を交換した場合の makespan
+    /// (染色体は変更しない)
+    pub fn swap_makespan(&mut self, first: usize, second: usize) -> Time {
+        let (first, second) = (first.min(second), first.max(second));
+        if self.chromosome[first] == self.chromosome[second] {
+            return self.makespan;
+        }
+        let checkpoint: usize = first / self.interval;
+        let instance: &FlatInstance = self.instance;
+        let chromosome: &[u16] = &self.chromosome;
+        let work: &mut DecodeState = &mut self.work;
+        work.clone_from(&self.checkpoints[checkpoint]);
+        for job in &chromosome[checkpoint * self.interval..first] {
+            instance.step(work, *job);
+        }
+        instance.step(work, chromosome[second]);
+        for job in &chromosome[first + 1..second] {
+            instance.step(work, *job);
+        }
+        instance.step(work, chromosome[first]);
+        for job in &chromosome[second + 1..] {
+            instance.step(work, *job);
+        }
+        FlatInstance::current_makespan(work)
+    }
+
+    /// first 番目と second 番目の遺伝子を交換し、新しい makespan を返す
+    pub fn apply_swap(&mut self, first: usize, second: usize) -> Time {
+        if self.chromosome[first] != self.chromosome[second] {
+            self.chromosome.swap(first, second);
+            self.rebuild_from(first.min(second) / self.interval);
+        }
+        self.makespan
+    }
+
+    /// checkpoint 番目以降のチェックポイントと makespan を計算し直す
+    fn rebuild_from(&mut self, checkpoint: usize) {
+        self.work.clone_from(&self.checkpoints[checkpoint]);
+        for position in checkpoint * self.interval..self.chromosome.len() {
+            if position % self.interval == 0 {
+                self.checkpoints[position / self.interval]
+                    .clone_from(&self.work);
+            }
+            self.instance
+                .step(&mut self.work, self.chromosome[position]);
+        }
+        self.makespan = FlatInstance::current_makespan(&self.work);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::score::{Decoder, SemiActiveDecoder};
+    use crate::optimization::preprocess::calendar::{Downtime, Interruption};
+    use crate::optimization::preprocess::runner::Alternative;
+    use crate::optimization::test_fixtures::medium_job_master;
+    use crate::optimization::types;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// 4 job x 3 actor のインスタンス (job 2 のみリリース時刻あり)
+    fn released_job_master() -> JobMaster {
+        let mut job_master = medium_job_master();
+        job_master.release_dates = Some(vec![0, 0, 6, 0]);
+        job_master
+    }
+
+    fn random_chromosome(rng: &mut ChaCha8Rng) -> types::Chromosome {
+        let mut chromosome: types::Chromosome =
+            (0..12).map(|gene| gene % 4).collect();
+        chromosome.shuffle(rng);
+        chromosome
+    }
+
+    #[test]
+    fn test_makespan_matches_decoder() {
+        let job_master = released_job_master();
+        let instance = FlatInstance::new(&job_master).unwrap();
+        let mut state = instance.state();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..50 {
+            let chromosome = random_chromosome(&mut rng);
+            assert_eq!(
+                instance.makespan(&chromosome, &mut state),
+                SemiActiveDecoder.decode(&job_master, &chromosome).makespan
+            );
+        }
+    }
+
+    #[test]
+    fn test_makespan_with_setup_times() {
+        let mut job_master = released_job_master();
+        // actor ごとに (前の job + 次の job) % 3 の段取り時間
+        job_master.setup_times = Some(
+            (0..3)
+                .map(|_| {
+                    (0..4)
+                        .map(|from| (0..4).map(|to| (from + to) % 3).collect())
+                        .collect()
+                })
+                .collect(),
+        );
+        let instance = FlatInstance::new(&job_master).unwrap();
+        let mut state = instance.state();
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        for _ in 0..50 {
+            let chromosome = random_chromosome(&mut rng);
+            assert_eq!(
+                instance.makespan(&chromosome, &mut state),
+                SemiActiveDecoder.decode(&job_master, &chromosome).makespan
+            );
+        }
+    }
+
+    #[test]
+    fn test_makespan_with_calendars() {
+        let mut job_master = released_job_master();
+        let downtime = |start: Time, end: Time| Downtime { start, end };
+        let downtimes: Vec<Vec<Downtime>> = vec![
+            vec![downtime(3, 5), downtime(12, 20)],
+            vec![downtime(0, 2)],
+            vec![downtime(7, 9), downtime(10, 11)],
+        ];
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for interruption in
+            [Interruption::NonResumable, Interruption::Resumable]
+        {
+            job_master.calendars =
+                Some(Calendars::new(downtimes.clone(), interruption));
+            let instance = FlatInstance::new(&job_master).unwrap();
+            let mut state = instance.state();
+            for _ in 0..50 {
+                let chromosome = random_chromosome(&mut rng);
+                assert_eq!(
+                    instance.makespan(&chromosome, &mut state),
+                    SemiActiveDecoder.decode(&job_master, &chromosome).makespan
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_evaluator_matches_full_decode() {
+        let job_master = released_job_master();
+        let instance = FlatInstance::new(&job_master).unwrap();
+        let mut state = instance.state();
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for interval in [1, 3, 5, 12, 20] {
+            let chromosome = random_chromosome(&mut rng);
+            let mut evaluator =
+                SwapEvaluator::new(&instance, &chromosome, interval);
+            assert_eq!(
+                evaluator.makespan(),
+                instance.makespan(&chromosome, &mut state)
+            );
+            for _ in 0..50 {
+                let first: usize = rng.random_range(0..12);
+                let second: usize = rng.random_range(0..12);
+                let mut swapped: types::Chromosome =
+                    evaluator.chromosome().to_vec();
+                swapped.swap(first, second);
+                let expected: Time = instance.makespan(&swapped, &mut state);
+                assert_eq!(evaluator.swap_makespan(first, second), expected);
+                // 半分の移動だけ採用する
+                if rng.random_bool(0.5) {
+                    assert_eq!(evaluator.apply_swap(first, second), expected);
+                    assert_eq!(evaluator.chromosome(), swapped.as_slice());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_rejects_flexible_and_open_shop() {
+        let alternative =
+            |actor_id: u16, time: Time| Alternative { actor_id, time };
+        let flexible = JobMaster::try_new_flexible(
+            1,
+            2,
+            vec![vec![vec![alternative(0, 1), alternative(1, 2)]]],
+        )
+        .unwrap();
+        assert!(FlatInstance::new(&flexible).is_none());
+        let open_shop = released_job_master().to_open_shop().unwrap();
+        assert!(FlatInstance::new(&open_shop).is_none());
+    }
+}
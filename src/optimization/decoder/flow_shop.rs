@@ -0,0 +1,403 @@
+use crate::optimization::decoder::schedule::{Schedule, ScheduledOperation};
+use crate::optimization::decoder::score::Decoder;
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::Time;
+
+/// 順列フローショップ用のデコーダ
+///
+/// 染色体は job_id の順列 (各 job が1回ずつ現れる) で、
+/// すべての actor がこの順に job を処理する
+/// (job_master はフローショップである必要がある。JobMaster::is_flow_shop を参照)
+pub struct PermutationDecoder;
+
+impl Decoder for PermutationDecoder {
+    fn name(&self) -> &'static str {
+        "permutation"
+    }
+
+    fn permutation_flow_shop(&self) -> bool {
+        true
+    }
+
+    fn decode(&self, job_master: &JobMaster, chromosome: &[u16]) -> Schedule {
+        let mut operations: Vec<ScheduledOperation> = Vec::with_capacity(
+            chromosome.len() * job_master.machine_series_size,
+        );
+        assign_operations(job_master, chromosome, |operation| {
+            operations.push(operation)
+        });
+        Schedule::new(operations, job_master.machine_series_size)
+    }
+
+    /// decode と同じ計算で、スケジュールを組み立てずに makespan だけを求める
+    fn makespan(&self, job_master: &JobMaster, chromosome: &[u16]) -> Time {
+        let mut makespan: Time = 0;
+        assign_operations(job_master, chromosome, |operation| {
+            makespan = makespan.max(operation.end)
+        });
+        makespan
+    }
+}
+
+/// 順列の job の operation を actor 0, 1, ... の順に割り当て、on_operation に渡す
+fn assign_operations(
+    job_master: &JobMaster,
+    chromosome: &[u16],
+    mut on_operation: impl FnMut(ScheduledOperation),
+) {
+    // 各 actor の最後の operation の終了時刻
+    let mut actor_ready_times: Vec<Time> =
+        vec![0; job_master.machine_series_size];
+    // すべての actor で直前の job は順列上の1つ前の job になる
+    let mut previous_job: Option<usize> = None;
+    for job_id in chromosome.iter() {
+        let job: usize = *job_id as usize;
+        let mut ready: Time = job_master.release_date(job);
+        // フローショップでは operation 番号と actor_id が一致する
+        for (actor, time) in job_master.exec_times[job].iter().enumerate() {
+            let (setup, start, end): (Time, Time, Time) = job_master
+                .earliest_window(
+                    actor,
+                    previous_job,
+                    job,
+                    ready,
+                    actor_ready_times[actor],
+                    *time,
+                );
+            ready = end;
+            actor_ready_times[actor] = end;
+            on_operation(ScheduledOperation {
+                job: *job_id,
+                operation: actor,
+                actor_id: actor as u16,
+                start,
+                end,
+                setup,
+            });
+        }
+        previous_job = Some(job);
+    }
+}
+
+/// sequence (job の部分順列) の各位置に job を挿入したときの makespan
+/// (戻り値の i 番目は sequence[i] の前に挿入した場合、最後は末尾に挿入した場合)
+///
+/// Taillard の高速化により、全位置をまとめて O(job 数 x machine 数) で求める
+/// - head: 挿入位置より前の job の、各 actor での終了時刻
+/// - tail: 挿入位置以降の job の、各 actor での開始から全体の終了までの時間
+///
+/// リリース時刻・段取り時間・稼働カレンダーがある場合は head / tail が
+/// 挿入する job によって変わるため、位置ごとに計算し直す
+pub fn insertion_makespans(
+    job_master: &JobMaster,
+    sequence: &[u16],
+    job: u16,
+) -> Vec<Time> {
+    if !is_accelerable(job_master) {
+        return (0..=sequence.len())
+            .map(|position| {
+                let mut inserted: Vec<u16> = sequence.to_vec();
+                inserted.insert(position, job);
+                PermutationDecoder.makespan(job_master, &inserted)
+            })
+            .collect();
+    }
+
+    let heads: Vec<Vec<Time>> = heads(job_master, sequence);
+    let tails: Vec<Vec<Time>> = tails(job_master, sequence);
+    (0..=sequence.len())
+        .map(|position| {
+            inserted_makespan(
+                &job_master.exec_times[job as usize],
+                &heads[position],
+                &tails[position],
+            )
+        })
+        .collect()
+}
+
+/// Taillard の高速化を使えるか (head / tail が挿入する job によらないか)
+fn is_accelerable(job_master: &JobMaster) -> bool {
+    job_master.release_dates.is_none()
+        && job_master.setup_times.is_none()
+        && job_master.calendars.is_none()
+}
+
+/// head (各 actor の終了時刻) の後に、実行時間 times の job を処理する
+fn push_head(times: &[Time], head: &mut [Time]) {
+    let mut ready: Time = 0;
+    for (finish, time) in head.iter_mut().zip(times.iter()) {
+        ready = ready.max(*finish) + time;
+        *finish = ready;
+    }
+}
+
+/// tail (各 actor での開始から全体の終了までの時間) の前に、実行時間 times の job を処理する
+fn push_tail(times: &[Time], tail: &mut [Time]) {
+    let mut rest: Time = 0;
+    for (remaining, time) in tail.iter_mut().zip(times.iter()).rev() {
+        rest = rest.max(*remaining) + time;
+        *remaining = rest;
+    }
+}
+
+/// heads[i][k]: sequence[..i] を処理したときの actor k の終了時刻
+fn heads(job_master: &JobMaster, sequence: &[u16]) -> Vec<Vec<Time>> {
+    let mut heads: Vec<Vec<Time>> =
+        vec![vec![0; job_master.machine_series_size]; sequence.len() + 1];
+    update_heads(job_master, sequence, &mut heads, 0);
+    heads
+}
+
+/// tails[i][k]: sequence[i..] の actor k での開始から最後の終了までの時間
+fn tails(job_master: &JobMaster, sequence: &[u16]) -> Vec<Vec<Time>> {
+    let mut tails: Vec<Vec<Time>> =
+        vec![vec![0; job_master.machine_series_size]; sequence.len() + 1];
+    update_tails(job_master, sequence, &mut tails, sequence.len());
+    tails
+}
+
+/// heads[from + 1..] を heads[from] から計算し直す
+fn update_heads(
+    job_master: &JobMaster,
+    sequence: &[u16],
+    heads: &mut [Vec<Time>],
+    from: usize,
+) {
+    for i in from..sequence.len() {
+        let (done, rest) = heads.split_at_mut(i + 1);
+        rest[0].copy_from_slice(&done[i]);
+        push_head(&job_master.exec_times[sequence[i] as usize], &mut rest[0]);
+    }
+}
+
+/// tails[..to] を tails[to] から計算し直す
+fn update_tails(
+    job_master: &JobMaster,
+    sequence: &[u16],
+    tails: &mut [Vec<Time>],
+    to: usize,
+) {
+    for i in (0..to).rev() {
+        let (rest, done) = tails.split_at_mut(i + 1);
+        rest[i].copy_from_slice(&done[0]);
+        push_tail(&job_master.exec_times[sequence[i] as usize], &mut rest[i]);
+    }
+}
+
+/// head の後・tail の前に実行時間 times の job を挿入したときの makespan
+fn inserted_makespan(times: &[Time], head: &[Time], tail: &[Time]) -> Time {
+    let mut ready: Time = 0;
+    let mut makespan: Time = 0;
+    for ((time, finish), rest) in times.iter().zip(head).zip(tail) {
+        ready = ready.max(*finish) + time;
+        makespan = makespan.max(ready + rest);
+    }
+    makespan
+}
+
+/// 順列フローショップの挿入移動 (NeighborMove::Insert) の差分評価器
+///
+/// 現在の順列の head / tail (insertion_makespans を参照) を保持し、
+/// 移動で並びが変わる区間だけを計算し直して makespan を求める
+/// (区間の長さを d とすると O(d x machine 数))
+/// 焼きなまし法は ScoreCalculator::insertion_evaluator から作って使う
+pub struct InsertionEvaluator<'a> {
+    job_master: &'a JobMaster,
+    sequence: Vec<u16>,
+    heads: Vec<Vec<Time>>,
+    tails: Vec<Vec<Time>>,
+    /// 移動後の head / tail を計算する作業領域
+    buffer: Vec<Time>,
+}
+
+impl<'a> InsertionEvaluator<'a> {
+    /// Taillard の高速化を使えない (リリース時刻・段取り時間・
+    /// 稼働カレンダーがある) 場合は None
+    pub fn new(job_master: &'a JobMaster, sequence: &[u16]) -> Option<Self> {
+        if !is_accelerable(job_master) {
+            return None;
+        }
+        Some(InsertionEvaluator {
+            job_master,
+            sequence: sequence.to_vec(),
+            heads: heads(job_master, sequence),
+            tails: tails(job_master, sequence),
+            buffer: vec![0; job_master.machine_series_size],
+        })
+    }
+
+    /// 評価の基準にする順列を差し替える
+    pub fn reset(&mut self, sequence: &[u16]) {
+        self.sequence.copy_from_slice(sequence);
+        update_heads(self.job_master, &self.sequence, &mut self.heads, 0);
+        let len: usize = self.sequence.len();
+        update_tails(self.job_master, &self.sequence, &mut self.tails, len);
+    }
+
+    /// from の job を取り除き、取り除いた後の to の位置に挿入したときの makespan
+    pub fn insert_makespan(&mut self, from: usize, to: usize) -> Time {
+        let times = |job: u16| &self.job_master.exec_times[job as usize];
+        if to <= from {
+            // 挿入位置より前 (sequence[..to]) は元の順列と同じ
+            self.buffer.copy_from_slice(&self.tails[from + 1]);
+            for job in self.sequence[to..from].iter().rev() {
+                push_tail(times(*job), &mut self.buffer);
+            }
+            inserted_makespan(
+                times(self.sequence[from]),
+                &self.heads[to],
+                &self.buffer,
+            )
+        } else {
+            // 挿入位置以降は元の順列の sequence[to + 1..] と同じ
+            self.buffer.copy_from_slice(&self.heads[from]);
+            for job in self.sequence[from + 1..=to].iter() {
+                push_head(times(*job), &mut self.buffer);
+            }
+            inserted_makespan(
+                times(self.sequence[from]),
+                &self.buffer,
+                &self.tails[to + 1],
+            )
+        }
+    }
+
+    /// 挿入移動を受理し、評価の基準にする順列に反映する
+    pub fn apply_insert(&mut self, from: usize, to: usize) {
+        let job: u16 = self.sequence.remove(from);
+        self.sequence.insert(to, job);
+        update_heads(
+            self.job_master,
+            &self.sequence,
+            &mut self.heads,
+            from.min(to),
+        );
+        update_tails(
+            self.job_master,
+            &self.sequence,
+            &mut self.tails,
+            from.max(to) + 1,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::score::SemiActiveDecoder;
+    use crate::optimization::decoder::validator::validate;
+    use crate::optimization::test_fixtures::{
+        flow_shop_job_master, ta001_job_master,
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_permutation_decoder() {
+        let job_master = flow_shop_job_master();
+        let permutation: Vec<u16> = vec![3, 0, 2, 1];
+        let schedule = PermutationDecoder.decode(&job_master, &permutation);
+        assert_eq!(validate(&job_master, &schedule), vec![]);
+        assert_eq!(schedule.makespan, 44);
+        assert_eq!(
+            PermutationDecoder.makespan(&job_master, &permutation),
+            schedule.makespan
+        );
+
+        // job ごとに全 operation を続けて並べた染色体の semi-active デコードと同じ
+        let chromosome: Vec<u16> =
+            permutation.iter().flat_map(|job| [*job; 3]).collect();
+        assert_eq!(
+            SemiActiveDecoder.decode(&job_master, &chromosome),
+            schedule
+        );
+    }
+
+    #[test]
+    fn test_insertion_makespans() {
+        let mut job_master = flow_shop_job_master();
+        let sequence: Vec<u16> = vec![2, 0, 3];
+        let expected: Vec<Time> = (0..=sequence.len())
+            .map(|position| {
+                let mut inserted: Vec<u16> = sequence.clone();
+                inserted.insert(position, 1);
+                PermutationDecoder.makespan(&job_master, &inserted)
+            })
+            .collect();
+        assert_eq!(insertion_makespans(&job_master, &sequence, 1), expected);
+        assert_eq!(insertion_makespans(&job_master, &[], 1), vec![22]);
+
+        // リリース時刻がある場合も同じ値になる
+        job_master.release_dates = Some(vec![0, 30, 0, 5]);
+        let expected: Vec<Time> = (0..=sequence.len())
+            .map(|position| {
+                let mut inserted: Vec<u16> = sequence.clone();
+                inserted.insert(position, 1);
+                PermutationDecoder.makespan(&job_master, &inserted)
+            })
+            .collect();
+        assert_eq!(insertion_makespans(&job_master, &sequence, 1), expected);
+    }
+
+    #[test]
+    fn test_insertion_makespans_on_taillard_instance() {
+        let job_master = ta001_job_master();
+        let order: Vec<u16> = (0..20).collect();
+        // 各 job を、残りの job の順列のすべての位置に挿入する
+        for job in order.iter() {
+            let sequence: Vec<u16> =
+                order.iter().copied().filter(|other| other != job).collect();
+            let brute_force: Vec<Time> = (0..=sequence.len())
+                .map(|position| {
+                    let mut inserted: Vec<u16> = sequence.clone();
+                    inserted.insert(position, *job);
+                    PermutationDecoder.makespan(&job_master, &inserted)
+                })
+                .collect();
+            assert_eq!(
+                insertion_makespans(&job_master, &sequence, *job),
+                brute_force
+            );
+        }
+    }
+
+    #[test]
+    fn test_insertion_evaluator_matches_decoder() {
+        let job_master = ta001_job_master();
+        let mut sequence: Vec<u16> = (0..20).collect();
+        let mut evaluator =
+            InsertionEvaluator::new(&job_master, &sequence).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        for step in 0..200 {
+            let from: usize = rng.random_range(0..20);
+            let to: usize = rng.random_range(0..20);
+            let mut moved: Vec<u16> = sequence.clone();
+            let job: u16 = moved.remove(from);
+            moved.insert(to, job);
+            assert_eq!(
+                evaluator.insert_makespan(from, to),
+                PermutationDecoder.makespan(&job_master, &moved)
+            );
+            // 一部の移動だけを受理して、基準の順列を進める
+            if step % 3 == 0 {
+                evaluator.apply_insert(from, to);
+                sequence = moved;
+            }
+        }
+        // 基準の順列を差し替えても、差し替えた順列から評価する
+        let reversed: Vec<u16> = (0..20).rev().collect();
+        evaluator.reset(&reversed);
+        let mut moved: Vec<u16> = reversed[1..].to_vec();
+        moved.push(reversed[0]);
+        assert_eq!(
+            evaluator.insert_makespan(0, 19),
+            PermutationDecoder.makespan(&job_master, &moved)
+        );
+
+        // リリース時刻があると head / tail が挿入する job で変わるため使えない
+        let mut released = job_master.clone();
+        released.release_dates = Some(vec![0; 20]);
+        assert!(InsertionEvaluator::new(&released, &sequence).is_none());
+    }
+}
@@ -1 +1,8 @@
+pub mod disjunctive_graph;
+pub mod flat;
+pub mod flow_shop;
+pub mod objective;
+pub mod open_shop;
+pub mod schedule;
 pub mod score;
+pub mod validator;
@@ -0,0 +1,250 @@
+use crate::optimization::decoder::schedule::Schedule;
+use crate::optimization::preprocess::error::JobMasterError;
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::Time;
+
+/// デコード済みのスケジュールを評価する目的関数 (値が小さいほど良い)
+pub trait Objective: Send + Sync {
+    /// 目的関数の名前 (コマンドライン引数などで指定する名前)
+    fn name(&self) -> &'static str;
+
+    /// 評価に必要な job 属性 (納期など) が揃っているか確認
+    fn check(&self, _job_master: &JobMaster) -> Result<(), JobMasterError> {
+        Ok(())
+    }
+
+    /// スケジュールを評価して目的関数値を返す
+    fn evaluate(&self, job_master: &JobMaster, schedule: &Schedule) -> f64;
+
+    /// makespan そのものを目的関数値とする場合は true
+    /// (スケジュール全体のデコードを省略できる)
+    fn is_makespan(&self) -> bool {
+        false
+    }
+
+    /// 目的関数値をスコア (正の値、大きいほど良い) に変換
+    /// 最大納期ずれのように負になりうる値も扱えるよう、0 未満では 1 - value とする
+    fn to_score(&self, value: f64) -> f64 {
+        if value < 0.0 {
+            1.0 - value
+        } else {
+            1.0 / (1.0 + value)
+        }
+    }
+}
+
+/// 最大完了時刻
+pub struct Makespan;
+
+/// 総フロー時間 (各 job の完了時刻 - リリース時刻 の合計)
+pub struct TotalFlowTime;
+
+/// 重み付き総納期遅れ
+pub struct TotalWeightedTardiness;
+
+/// 最大納期ずれ (完了時刻 - 納期 の最大値、負の値もとりうる)
+pub struct MaximumLateness;
+
+/// 納期遅れ job 数
+pub struct NumberOfTardyJobs;
+
+/// 指定できる目的関数名
+pub const OBJECTIVE_NAMES: [&str; 5] = [
+    "makespan",
+    "total-flow-time",
+    "total-weighted-tardiness",
+    "maximum-lateness",
+    "tardy-jobs",
+];
+
+/// 名前から目的関数を取得 (未知の名前の場合は None)
+pub fn objective_from_name(name: &str) -> Option<Box<dyn Objective>> {
+    match name.to_lowercase().as_str() {
+        "makespan" => Some(Box::new(Makespan)),
+        "total-flow-time" => Some(Box::new(TotalFlowTime)),
+        "total-weighted-tardiness" => Some(Box::new(TotalWeightedTardiness)),
+        "maximum-lateness" => Some(Box::new(MaximumLateness)),
+        "tardy-jobs" => Some(Box::new(NumberOfTardyJobs)),
+        _ => None,
+    }
+}
+
+/// 納期が指定されていることを確認
+fn require_due_dates(job_master: &JobMaster) -> Result<(), JobMasterError> {
+    match job_master.due_dates {
+        Some(_) => Ok(()),
+        None => Err(JobMasterError::MissingJobAttribute { name: "due_date" }),
+    }
+}
+
+/// job ごとの (完了時刻 - 納期) を求める
+fn lateness(job_master: &JobMaster, schedule: &Schedule) -> Vec<f64> {
+    let due_dates: &Vec<Time> = job_master
+        .due_dates
+        .as_ref()
+        .expect("due dates are required for this objective");
+    schedule
+        .completion_times(job_master.job_size)
+        .iter()
+        .zip(due_dates.iter())
+        .map(|(completion, due)| *completion as f64 - *due as f64)
+        .collect()
+}
+
+impl Objective for Makespan {
+    fn name(&self) -> &'static str {
+        "makespan"
+    }
+
+    fn evaluate(&self, _job_master: &JobMaster, schedule: &Schedule) -> f64 {
+        schedule.makespan as f64
+    }
+
+    fn is_makespan(&self) -> bool {
+        true
+    }
+
+    fn to_score(&self, value: f64) -> f64 {
+        1.0 / value
+    }
+}
+
+impl Objective for TotalFlowTime {
+    fn name(&self) -> &'static str {
+        "total-flow-time"
+    }
+
+    fn evaluate(&self, job_master: &JobMaster, schedule: &Schedule) -> f64 {
+        schedule
+            .completion_times(job_master.job_size)
+            .iter()
+            .enumerate()
+            .map(|(job, completion)| {
+                *completion as f64 - job_master.release_date(job) as f64
+            })
+            .sum()
+    }
+}
+
+impl Objective for TotalWeightedTardiness {
+    fn name(&self) -> &'static str {
+        "total-weighted-tardiness"
+    }
+
+    fn check(&self, job_master: &JobMaster) -> Result<(), JobMasterError> {
+        require_due_dates(job_master)
+    }
+
+    fn evaluate(&self, job_master: &JobMaster, schedule: &Schedule) -> f64 {
+        lateness(job_master, schedule)
+            .iter()
+            .enumerate()
+            .map(|(job, lateness)| job_master.weight(job) * lateness.max(0.0))
+            .sum()
+    }
+}
+
+impl Objective for MaximumLateness {
+    fn name(&self) -> &'static str {
+        "maximum-lateness"
+    }
+
+    fn check(&self, job_master: &JobMaster) -> Result<(), JobMasterError> {
+        require_due_dates(job_master)
+    }
+
+    fn evaluate(&self, job_master: &JobMaster, schedule: &Schedule) -> f64 {
+        lateness(job_master, schedule)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+}
+
+impl Objective for NumberOfTardyJobs {
+    fn name(&self) -> &'static str {
+        "tardy-jobs"
+    }
+
+    fn check(&self, job_master: &JobMaster) -> Result<(), JobMasterError> {
+        require_due_dates(job_master)
+    }
+
+    fn evaluate(&self, job_master: &JobMaster, schedule: &Schedule) -> f64 {
+        lateness(job_master, schedule)
+            .iter()
+            .filter(|lateness| 0.0 < **lateness)
+            .count() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::score::{Decoder, SemiActiveDecoder};
+    use crate::optimization::test_fixtures;
+
+    /// 3 job x 2 actor の小さなインスタンス (納期・重み付き)
+    fn weighted_job_master() -> JobMaster {
+        let mut job_master = test_fixtures::sample_job_master();
+        job_master.due_dates = Some(vec![5, 10, 10]);
+        job_master.weights = Some(vec![1.0, 2.0, 3.0]);
+        job_master
+    }
+
+    #[test]
+    fn test_objectives() {
+        // 完了時刻は job 0: 5, job 1: 12, job 2: 8
+        let job_master = weighted_job_master();
+        let schedule =
+            SemiActiveDecoder.decode(&job_master, &[0, 1, 2, 0, 1, 2]);
+
+        let values: Vec<f64> = OBJECTIVE_NAMES
+            .iter()
+            .map(|name| {
+                let objective = objective_from_name(name).unwrap();
+                assert_eq!(objective.name(), *name);
+                objective.evaluate(&job_master, &schedule)
+            })
+            .collect();
+        assert_eq!(values, vec![12.0, 25.0, 4.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_release_dates_are_respected() {
+        let mut job_master = weighted_job_master();
+        job_master.release_dates = Some(vec![0, 0, 6]);
+        let schedule =
+            SemiActiveDecoder.decode(&job_master, &[0, 1, 2, 0, 1, 2]);
+        assert_eq!(schedule.job_operations(2)[0].start, 6);
+        // フロー時間はリリース時刻から数える
+        assert_eq!(
+            TotalFlowTime.evaluate(&job_master, &schedule),
+            5.0 + 15.0 + (11.0 - 6.0)
+        );
+        assert_eq!(
+            SemiActiveDecoder.makespan(&job_master, &[0, 1, 2, 0, 1, 2]),
+            schedule.makespan
+        );
+    }
+
+    #[test]
+    fn test_due_dates_are_required() {
+        let mut job_master = weighted_job_master();
+        job_master.due_dates = None;
+        assert!(Makespan.check(&job_master).is_ok());
+        assert!(TotalFlowTime.check(&job_master).is_ok());
+        assert_eq!(
+            TotalWeightedTardiness.check(&job_master).unwrap_err(),
+            JobMasterError::MissingJobAttribute { name: "due_date" }
+        );
+    }
+
+    #[test]
+    fn test_to_score_is_decreasing() {
+        let objective = MaximumLateness;
+        assert!(objective.to_score(-3.0) > objective.to_score(-1.0));
+        assert!(objective.to_score(-1.0) > objective.to_score(0.0));
+        assert!(objective.to_score(0.0) > objective.to_score(2.0));
+        assert_eq!(Makespan.to_score(10.0), 0.1);
+    }
+}
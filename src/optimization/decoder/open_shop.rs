@@ -0,0 +1,141 @@
+use crate::optimization::decoder::schedule::{Schedule, ScheduledOperation};
+use crate::optimization::decoder::score::Decoder;
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::{self, Time};
+
+/// オープンショップ用のデコーダ
+///
+/// 染色体は operation 番号 (job * machine 数 + actor_id) の順列で、
+/// 前から順に job が空き、actor の段取りが終わった後の最早の稼働時間帯に割り当てる
+/// (job 内の順序と actor 上の順序の両方を染色体で決める)
+pub struct OpenShopDecoder;
+
+impl Decoder for OpenShopDecoder {
+    fn name(&self) -> &'static str {
+        "open-shop"
+    }
+
+    fn decode(&self, job_master: &JobMaster, chromosome: &[u16]) -> Schedule {
+        let mut operations: Vec<ScheduledOperation> =
+            Vec::with_capacity(chromosome.len());
+        assign_operations(job_master, chromosome, |operation| {
+            operations.push(operation)
+        });
+        Schedule::new(operations, job_master.machine_series_size)
+    }
+
+    /// decode と同じ計算で、スケジュールを組み立てずに makespan だけを求める
+    fn makespan(&self, job_master: &JobMaster, chromosome: &[u16]) -> Time {
+        let mut makespan: Time = 0;
+        assign_operations(job_master, chromosome, |operation| {
+            makespan = makespan.max(operation.end)
+        });
+        makespan
+    }
+}
+
+/// 染色体の operation を前から順に最早の時間帯に割り当て、on_operation に渡す
+fn assign_operations(
+    job_master: &JobMaster,
+    chromosome: &[u16],
+    mut on_operation: impl FnMut(ScheduledOperation),
+) {
+    let machine_series_size: usize = job_master.machine_series_size;
+    let mut job_ready_times: Vec<Time> = (0..job_master.job_size)
+        .map(|job| job_master.release_date(job))
+        .collect();
+    let mut actor_ready_times: Vec<Time> = vec![0; machine_series_size];
+    let mut actor_last_jobs: Vec<Option<usize>> =
+        vec![None; machine_series_size];
+    for gene in chromosome.iter() {
+        let (job, actor) = split_gene(job_master, *gene);
+        let (setup, start, end): (Time, Time, Time) = job_master
+            .earliest_window(
+                actor,
+                actor_last_jobs[actor],
+                job,
+                job_ready_times[job],
+                actor_ready_times[actor],
+                job_master.exec_times[job][actor],
+            );
+        job_ready_times[job] = end;
+        actor_ready_times[actor] = end;
+        actor_last_jobs[actor] = Some(job);
+        on_operation(ScheduledOperation {
+            job: job as u16,
+            operation: actor,
+            actor_id: actor as u16,
+            start,
+            end,
+            setup,
+        });
+    }
+}
+
+/// 遺伝子 (operation 番号) を (job, actor_id) に分ける
+fn split_gene(job_master: &JobMaster, gene: u16) -> (usize, usize) {
+    let machine_series_size: usize = job_master.machine_series_size;
+    (
+        gene as usize / machine_series_size,
+        gene as usize % machine_series_size,
+    )
+}
+
+/// (job, actor_id) の operation 番号 (遺伝子の値)
+pub fn operation_gene(job_master: &JobMaster, job: u16, actor_id: u16) -> u16 {
+    (job as usize * job_master.machine_series_size + actor_id as usize) as u16
+}
+
+/// 割り当てを開始時刻順に並べ、その operation 番号の列を染色体にする
+/// (OpenShopDecoder でデコードすると、元のスケジュールを左に詰めたものになる)
+pub fn chromosome_from_operations(
+    job_master: &JobMaster,
+    operations: &[ScheduledOperation],
+) -> types::Chromosome {
+    let mut sorted: Vec<&ScheduledOperation> = operations.iter().collect();
+    sorted.sort_by_key(|op| (op.start, op.end, op.job, op.actor_id));
+    sorted
+        .iter()
+        .map(|op| operation_gene(job_master, op.job, op.actor_id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::validator::validate;
+    use crate::optimization::test_fixtures::open_shop_job_master;
+
+    #[test]
+    fn test_open_shop_decoder() {
+        let job_master = open_shop_job_master();
+        // job 0 は actor 1 → actor 0、job 1 は actor 0 → actor 1 の順
+        let chromosome: types::Chromosome = vec![1, 2, 0, 3];
+        let schedule = OpenShopDecoder.decode(&job_master, &chromosome);
+        assert_eq!(validate(&job_master, &schedule), vec![]);
+        assert_eq!(schedule.makespan, 6);
+        assert_eq!(
+            OpenShopDecoder.makespan(&job_master, &chromosome),
+            schedule.makespan
+        );
+
+        // どちらの job も actor 0 → actor 1 の順にすると待ちが生じる
+        let chromosome: types::Chromosome = vec![0, 1, 2, 3];
+        assert_eq!(OpenShopDecoder.makespan(&job_master, &chromosome), 9);
+    }
+
+    #[test]
+    fn test_chromosome_from_operations() {
+        let job_master = open_shop_job_master();
+        let chromosome: types::Chromosome = vec![2, 1, 3, 0];
+        let schedule = OpenShopDecoder.decode(&job_master, &chromosome);
+        let restored: types::Chromosome =
+            chromosome_from_operations(&job_master, &schedule.operations);
+        let mut expected: Vec<ScheduledOperation> = schedule.operations;
+        expected.sort_by_key(|op| (op.start, op.end, op.job, op.actor_id));
+        assert_eq!(
+            OpenShopDecoder.decode(&job_master, &restored).operations,
+            expected
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::types::Time;
+
+/// 割り当て済みの operation 1つ分の情報
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledOperation {
+    pub job: u16,
+    /// job 内での operation の番号 (0 始まり)
+    pub operation: usize,
+    pub actor_id: u16,
+    pub start: Time,
+    pub end: Time,
+    /// start の直前に actor で行う段取りの時間 (段取りがなければ 0)
+    /// 段取りのない解ファイルも読み込めるよう、省略時は 0 とする
+    #[serde(default)]
+    pub setup: Time,
+}
+
+/// 染色体をデコードして得られるスケジュール (ガントチャート)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// デコード順に並んだ operation
+    pub operations: Vec<ScheduledOperation>,
+    /// actor ごとの operation の処理順 (operations の index を開始時刻順に保持)
+    pub machine_sequences: Vec<Vec<usize>>,
+    pub makespan: Time,
+}
+
+impl Schedule {
+    /// operation の一覧から actor ごとの処理順と makespan を求めて Schedule を生成
+    pub fn new(
+        operations: Vec<ScheduledOperation>,
+        machine_series_size: usize,
+    ) -> Self {
+        let mut machine_sequences: Vec<Vec<usize>> =
+            vec![Vec::new(); machine_series_size];
+        for (index, operation) in operations.iter().enumerate() {
+            machine_sequences[operation.actor_id as usize].push(index);
+        }
+        for sequence in machine_sequences.iter_mut() {
+            sequence.sort_by_key(|index| {
+                (operations[*index].start, operations[*index].end)
+            });
+        }
+        let makespan: Time =
+            operations.iter().map(|op| op.end).max().unwrap_or(0);
+
+        Schedule {
+            operations,
+            machine_sequences,
+            makespan,
+        }
+    }
+
+    /// 指定した job の operation を operation 番号順に取得
+    pub fn job_operations(&self, job: u16) -> Vec<&ScheduledOperation> {
+        let mut operations: Vec<&ScheduledOperation> =
+            self.operations.iter().filter(|op| op.job == job).collect();
+        operations.sort_by_key(|op| op.operation);
+        operations
+    }
+
+    /// job ごとの完了時刻 (最後の operation の終了時刻)
+    pub fn completion_times(&self, job_size: usize) -> Vec<Time> {
+        let mut completion_times: Vec<Time> = vec![0; job_size];
+        for operation in self.operations.iter() {
+            let job: usize = operation.job as usize;
+            completion_times[job] = completion_times[job].max(operation.end);
+        }
+        completion_times
+    }
+}
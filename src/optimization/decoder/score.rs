@@ -1,86 +1,860 @@
-use crate::optimization::preprocess::runner::JobMaster;
-use crate::optimization::types;
-use std::collections::HashMap;
+use crate::optimization::decoder::flat::{
+    DecodeState, FlatInstance, SwapEvaluator,
+};
+use crate::optimization::decoder::flow_shop::InsertionEvaluator;
+use crate::optimization::decoder::objective::{Makespan, Objective};
+use crate::optimization::decoder::schedule::{Schedule, ScheduledOperation};
+use crate::optimization::preprocess::runner::{Alternative, JobMaster};
+use crate::optimization::types::Time;
+
+/// 染色体 (operation-based の job_id 列) をスケジュールに変換するデコーダ
+pub trait Decoder: Send + Sync {
+    /// デコーダの名前 (コマンドライン引数などで指定する名前)
+    fn name(&self) -> &'static str;
+
+    /// 染色体をデコードしてスケジュールを返す
+    fn decode(&self, job_master: &JobMaster, chromosome: &[u16]) -> Schedule;
+
+    /// 染色体をデコードして makespan のみを返す
+    fn makespan(&self, job_master: &JobMaster, chromosome: &[u16]) -> Time {
+        self.decode(job_master, chromosome).makespan
+    }
+
+    /// flat::FlatInstance と同じ semi-active スケジュールを返すか
+    /// (true の場合、ScoreCalculator はメモリ確保のない flat::FlatInstance で評価する)
+    fn flat_compatible(&self) -> bool {
+        false
+    }
+
+    /// 染色体を job の順列として復号する順列フローショップの復号器か
+    /// (true の場合、挿入移動を flow_shop::InsertionEvaluator で差分評価できる)
+    fn permutation_flow_shop(&self) -> bool {
+        false
+    }
+}
+
+/// 各 actor の末尾に operation を追加していくデコーダ (semi-active schedule)
+pub struct SemiActiveDecoder;
+
+/// Giffler–Thompson 法によるデコーダ (active schedule)
+///
+/// 最も早く終了できる operation の actor で競合する operation のうち、
+/// 染色体上で先に現れるものを優先して割り当てる
+/// actor の空き時間に後続の operation を前詰めできるため、
+/// semi-active schedule よりも無駄な待ち時間が少ない
+pub struct ActiveDecoder;
+
+/// 最も早く開始できる時刻に開始可能な operation のうち、
+/// 染色体上で先に現れるものを優先して割り当てるデコーダ (non-delay schedule)
+pub struct NonDelayDecoder;
+
+/// FJSP 用のデコーダ (semi-active schedule)
+///
+/// 染色体の前半 (operation 数分) は operation 順の job_id、
+/// 後半 (operation 数分、job・operation 順) は各 operation の actor の選択で、
+/// 選択した actor の末尾に operation を追加していく
+/// 後半がない場合は最初の選択肢を使うため、FJSP 以外では
+/// SemiActiveDecoder と同じスケジュールになる
+pub struct FlexibleDecoder;
+
+/// 指定できるデコーダ名
+pub const DECODER_NAMES: [&str; 4] =
+    ["semi-active", "active", "non-delay", "flexible"];
+
+/// 名前からデコーダを取得 (未知の名前の場合は None)
+pub fn decoder_from_name(name: &str) -> Option<Box<dyn Decoder>> {
+    match name.to_lowercase().as_str() {
+        "semi-active" => Some(Box::new(SemiActiveDecoder)),
+        "active" => Some(Box::new(ActiveDecoder)),
+        "non-delay" => Some(Box::new(NonDelayDecoder)),
+        "flexible" => Some(Box::new(FlexibleDecoder)),
+        _ => None,
+    }
+}
+
+impl Decoder for SemiActiveDecoder {
+    fn name(&self) -> &'static str {
+        "semi-active"
+    }
+
+    fn flat_compatible(&self) -> bool {
+        true
+    }
+
+    fn decode(&self, job_master: &JobMaster, chromosome: &[u16]) -> Schedule {
+        let mut operations: Vec<ScheduledOperation> =
+            Vec::with_capacity(chromosome.len());
+        semi_active(job_master, chromosome, |operation| {
+            operations.push(operation)
+        });
+        Schedule::new(operations, job_master.machine_series_size)
+    }
+
+    /// decode と同じ計算で、スケジュールを組み立てずに makespan だけを求める
+    /// (ScoreCalculator では、メモリ確保を避けるため flat::FlatInstance を使う)
+    fn makespan(&self, job_master: &JobMaster, chromosome: &[u16]) -> Time {
+        let mut makespan: Time = 0;
+        semi_active(job_master, chromosome, |operation| {
+            makespan = makespan.max(operation.end)
+        });
+        makespan
+    }
+}
+
+/// 染色体の順に、各 operation を actor の末尾に割り当てる (semi-active デコード)
+/// 割り当てた operation を順に on_operation に渡す
+fn semi_active(
+    job_master: &JobMaster,
+    chromosome: &[u16],
+    mut on_operation: impl FnMut(ScheduledOperation),
+) {
+    // 各 job の operation 進捗
+    let mut done_operations_count: Vec<usize> = vec![0; job_master.job_size];
+    // 各 job 内実行済み operation の終了時刻 (初期値はリリース時刻)
+    let mut job_ready_times: Vec<Time> = (0..job_master.job_size)
+        .map(|job| job_master.release_date(job))
+        .collect();
+    // 各 actor の最後の operation の終了時刻と job
+    let mut actor_ready_times: Vec<Time> =
+        vec![0; job_master.machine_series_size];
+    let mut actor_last_jobs: Vec<Option<usize>> =
+        vec![None; job_master.machine_series_size];
+
+    // gene は job_id
+    for job_id in chromosome.iter() {
+        let job: usize = *job_id as usize;
+        let operation_no: usize = done_operations_count[job];
+        let actor_id: u16 = job_master.actor_sequences[job][operation_no];
+        let actor: usize = actor_id as usize;
+
+        // job が空き、actor の段取りが終わった後の稼働時間帯に処理する
+        let (setup, start, end): (Time, Time, Time) = job_master
+            .earliest_window(
+                actor,
+                actor_last_jobs[actor],
+                job,
+                job_ready_times[job],
+                actor_ready_times[actor],
+                job_master.exec_times[job][operation_no],
+            );
+        job_ready_times[job] = end;
+        actor_ready_times[actor] = end;
+        actor_last_jobs[actor] = Some(job);
+        done_operations_count[job] += 1;
+
+        on_operation(ScheduledOperation {
+            job: *job_id,
+            operation: operation_no,
+            actor_id,
+            start,
+            end,
+            setup,
+        });
+    }
+}
+
+/// Giffler–Thompson 法 / non-delay 法で共通して使う、割り当て途中の状態
+struct DispatchState<'a> {
+    job_master: &'a JobMaster,
+    /// priorities[job][operation]: 染色体上で operation が現れる位置
+    priorities: Vec<Vec<usize>>,
+    done_operations_count: Vec<usize>,
+    job_ready_times: Vec<Time>,
+    actor_ready_times: Vec<Time>,
+    /// actor ごとの最後の operation の job (段取り時間を求めるため)
+    actor_last_jobs: Vec<Option<usize>>,
+    operations: Vec<ScheduledOperation>,
+}
+
+impl<'a> DispatchState<'a> {
+    fn new(job_master: &'a JobMaster, chromosome: &[u16]) -> Self {
+        let mut priorities: Vec<Vec<usize>> =
+            vec![Vec::new(); job_master.job_size];
+        for (position, job_id) in chromosome.iter().enumerate() {
+            priorities[*job_id as usize].push(position);
+        }
+
+        DispatchState {
+            job_master,
+            priorities,
+            done_operations_count: vec![0; job_master.job_size],
+            job_ready_times: (0..job_master.job_size)
+                .map(|job| job_master.release_date(job))
+                .collect(),
+            actor_ready_times: vec![0; job_master.machine_series_size],
+            actor_last_jobs: vec![None; job_master.machine_series_size],
+            operations: Vec::with_capacity(chromosome.len()),
+        }
+    }
+
+    /// 次に割り当て可能な operation を持つ job の一覧
+    fn schedulable_jobs(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.job_master.job_size).filter(|job| {
+            self.done_operations_count[*job] < self.priorities[*job].len()
+        })
+    }
+
+    /// job の次の operation の (actor_id, 段取り時間, 最早開始時刻, 最早終了時刻)
+    fn earliest_window(&self, job: usize) -> (u16, Time, Time, Time) {
+        let operation_no: usize = self.done_operations_count[job];
+        let actor_id: u16 = self.job_master.actor_sequences[job][operation_no];
+        let actor: usize = actor_id as usize;
+        let (setup, start, end): (Time, Time, Time) =
+            self.job_master.earliest_window(
+                actor,
+                self.actor_last_jobs[actor],
+                job,
+                self.job_ready_times[job],
+                self.actor_ready_times[actor],
+                self.job_master.exec_times[job][operation_no],
+            );
+        (actor_id, setup, start, end)
+    }
+
+    /// job の次の operation の (actor_id, 最早開始時刻, 最早終了時刻)
+    fn earliest_times(&self, job: usize) -> (u16, Time, Time) {
+        let (actor_id, _, start, end) = self.earliest_window(job);
+        (actor_id, start, end)
+    }
+
+    /// job の次の operation の染色体上の位置
+    fn priority(&self, job: usize) -> usize {
+        self.priorities[job][self.done_operations_count[job]]
+    }
+
+    /// job の次の operation を最早開始時刻に割り当てる
+    fn dispatch(&mut self, job: usize) {
+        let operation_no: usize = self.done_operations_count[job];
+        let (actor_id, setup, start, end) = self.earliest_window(job);
+        self.job_ready_times[job] = end;
+        self.actor_ready_times[actor_id as usize] = end;
+        self.actor_last_jobs[actor_id as usize] = Some(job);
+        self.done_operations_count[job] += 1;
+        self.operations.push(ScheduledOperation {
+            job: job as u16,
+            operation: operation_no,
+            actor_id,
+            start,
+            end,
+            setup,
+        });
+    }
+
+    fn into_schedule(self) -> Schedule {
+        Schedule::new(self.operations, self.job_master.machine_series_size)
+    }
+}
+
+impl Decoder for ActiveDecoder {
+    fn name(&self) -> &'static str {
+        "active"
+    }
+
+    fn decode(&self, job_master: &JobMaster, chromosome: &[u16]) -> Schedule {
+        let mut state: DispatchState =
+            DispatchState::new(job_master, chromosome);
+        for _ in 0..chromosome.len() {
+            // 最も早く終了できる operation の actor と終了時刻
+            let (actor_id, earliest_end) = state
+                .schedulable_jobs()
+                .map(|job| state.earliest_times(job))
+                .map(|(actor_id, _, end)| (actor_id, end))
+                .min_by_key(|(_, end)| *end)
+                .unwrap();
+
+            // 同じ actor でその終了時刻より前に開始できる operation が競合する
+            let selected: usize = state
+                .schedulable_jobs()
+                .filter(|job| {
+                    let (actor, start, _) = state.earliest_times(*job);
+                    actor == actor_id && start < earliest_end
+                })
+                .min_by_key(|job| state.priority(*job))
+                .unwrap();
+            state.dispatch(selected);
+        }
+        state.into_schedule()
+    }
+}
+
+impl Decoder for NonDelayDecoder {
+    fn name(&self) -> &'static str {
+        "non-delay"
+    }
+
+    fn decode(&self, job_master: &JobMaster, chromosome: &[u16]) -> Schedule {
+        let mut state: DispatchState =
+            DispatchState::new(job_master, chromosome);
+        for _ in 0..chromosome.len() {
+            // 最も早く開始できる時刻
+            let earliest_start: Time = state
+                .schedulable_jobs()
+                .map(|job| state.earliest_times(job).1)
+                .min()
+                .unwrap();
+
+            // その時刻に開始できる operation が競合する
+            let selected: usize = state
+                .schedulable_jobs()
+                .filter(|job| state.earliest_times(*job).1 == earliest_start)
+                .min_by_key(|job| state.priority(*job))
+                .unwrap();
+            state.dispatch(selected);
+        }
+        state.into_schedule()
+    }
+}
+
+impl Decoder for FlexibleDecoder {
+    fn name(&self) -> &'static str {
+        "flexible"
+    }
+
+    fn decode(&self, job_master: &JobMaster, chromosome: &[u16]) -> Schedule {
+        let (sequence, assignment) = split_flexible(job_master, chromosome);
+        // 各 job の先頭の operation の、染色体の後半での位置
+        let offsets: Vec<usize> = operation_offsets(job_master);
+        let mut done_operations_count: Vec<usize> =
+            vec![0; job_master.job_size];
+        let mut job_ready_times: Vec<Time> = (0..job_master.job_size)
+            .map(|job| job_master.release_date(job))
+            .collect();
+        let mut actor_ready_times: Vec<Time> =
+            vec![0; job_master.machine_series_size];
+        let mut actor_last_jobs: Vec<Option<usize>> =
+            vec![None; job_master.machine_series_size];
+
+        let mut operations: Vec<ScheduledOperation> =
+            Vec::with_capacity(sequence.len());
+        for job_id in sequence.iter() {
+            let job: usize = *job_id as usize;
+            let operation_no: usize = done_operations_count[job];
+            let choice: u16 = assignment
+                .get(offsets[job] + operation_no)
+                .copied()
+                .unwrap_or(0);
+            let alternative: Alternative =
+                job_master.alternative(job, operation_no, choice);
+            let actor: usize = alternative.actor_id as usize;
+            let (setup, start, end): (Time, Time, Time) = job_master
+                .earliest_window(
+                    actor,
+                    actor_last_jobs[actor],
+                    job,
+                    job_ready_times[job],
+                    actor_ready_times[actor],
+                    alternative.time,
+                );
+            job_ready_times[job] = end;
+            actor_ready_times[actor] = end;
+            actor_last_jobs[actor] = Some(job);
+            done_operations_count[job] += 1;
+
+            operations.push(ScheduledOperation {
+                job: *job_id,
+                operation: operation_no,
+                actor_id: alternative.actor_id,
+                start,
+                end,
+                setup,
+            });
+        }
+
+        Schedule::new(operations, job_master.machine_series_size)
+    }
+}
+
+/// FJSP の染色体を (operation 順, actor の選択) に分ける
+pub fn split_flexible<'a>(
+    job_master: &JobMaster,
+    chromosome: &'a [u16],
+) -> (&'a [u16], &'a [u16]) {
+    chromosome.split_at(job_master.total_operations().min(chromosome.len()))
+}
+
+/// job ごとの、先頭の operation の通し番号 (染色体の後半での位置)
+pub fn operation_offsets(job_master: &JobMaster) -> Vec<usize> {
+    let mut offset: usize = 0;
+    (0..job_master.job_size)
+        .map(|job| {
+            let start: usize = offset;
+            offset += job_master.operation_count(job);
+            start
+        })
+        .collect()
+}
+
+/// operation の割り当てから、FJSP の染色体の後半 (actor の選択) を求める
+/// 選択肢にない actor に割り当てられている operation は最初の選択肢とする
+pub fn assignment_from_operations(
+    job_master: &JobMaster,
+    operations: &[ScheduledOperation],
+) -> Vec<u16> {
+    let offsets: Vec<usize> = operation_offsets(job_master);
+    let mut assignment: Vec<u16> = vec![0; job_master.total_operations()];
+    for op in operations {
+        let (job, operation): (usize, usize) = (op.job as usize, op.operation);
+        if job_master.job_size <= job
+            || job_master.operation_count(job) <= operation
+        {
+            continue;
+        }
+        let choice: Option<usize> =
+            (0..job_master.alternative_count(job, operation)).find(|choice| {
+                job_master
+                    .alternative(job, operation, *choice as u16)
+                    .actor_id
+                    == op.actor_id
+            });
+        assignment[offsets[job] + operation] = choice.unwrap_or(0) as u16;
+    }
+    assignment
+}
+
+/// 染色体の評価結果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Evaluation {
+    pub makespan: Time,
+    /// 目的関数値 (小さいほど良い)
+    pub objective_value: f64,
+    /// 目的関数値から求めたスコア (大きいほど良い)
+    pub score: f64,
+}
 
 pub struct ScoreCalculator {
-    pub job_master: JobMaster,
+    job_master: JobMaster,
+    decoder: Box<dyn Decoder>,
+    pub objective: Box<dyn Objective>,
+    /// flat_compatible なデコーダの場合に、job_master を平坦化したもの
+    /// (それ以外のデコーダや、平坦化できない instance では None)
+    /// job_master・decoder は生成後に変更できないため、常に両者と一致する
+    flat: Option<FlatInstance>,
+}
+
+/// ScoreCalculator で評価を繰り返すための、スクラッチバッファを持つ評価器
+///
+/// バッファを使い回すため、評価のたびのメモリ確保がない
+/// (並列実行時はスレッド・探索ごとに ScoreCalculator::evaluator で作る)
+pub struct ScoreEvaluator<'a> {
+    calculator: &'a ScoreCalculator,
+    state: Option<DecodeState>,
 }
 
 impl ScoreCalculator {
     pub fn new(job_master: JobMaster) -> Self {
-        ScoreCalculator { job_master }
+        ScoreCalculator::with_decoder(job_master, Box::new(SemiActiveDecoder))
+    }
+
+    /// デコーダを指定して ScoreCalculator を生成
+    pub fn with_decoder(
+        job_master: JobMaster,
+        decoder: Box<dyn Decoder>,
+    ) -> Self {
+        let flat: Option<FlatInstance> = if decoder.flat_compatible() {
+            FlatInstance::new(&job_master)
+        } else {
+            None
+        };
+        ScoreCalculator {
+            job_master,
+            decoder,
+            objective: Box::new(Makespan),
+            flat,
+        }
     }
 
-    pub fn makespan_to_score(&self, makespan: u16) -> f64 {
+    /// 目的関数を差し替える (既定は makespan)
+    pub fn with_objective(mut self, objective: Box<dyn Objective>) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    pub fn job_master(&self) -> &JobMaster {
+        &self.job_master
+    }
+
+    pub fn decoder(&self) -> &dyn Decoder {
+        self.decoder.as_ref()
+    }
+
+    pub fn makespan_to_score(&self, makespan: Time) -> f64 {
         1.0 / makespan as f64
     }
 
-    pub fn calc_makespan(&self, chromosome: types::Chromosome) -> u16 {
-        /* ----------------------------------------------------------------
-        // 下準備 - カウンターの初期化
-        ---------------------------------------------------------------- */
-        // 各 job の operation 進捗
-        let mut done_operations_count: HashMap<u16, u8> = HashMap::new();
-        for job in 0..self.job_master.job_size as u16 {
-            done_operations_count.insert(job, 0);
-        }
-        // 各 job 内実行済み operation の経過時間
-        let mut elapsed_job_time_map: HashMap<u16, u16> = HashMap::new();
-        for job in 0..self.job_master.job_size as u16 {
-            elapsed_job_time_map.insert(job, 0);
-        }
-        // 各 actor の経過時間
-        let mut elapsed_actor_time_map: HashMap<u16, u16> = HashMap::new();
-        for actor_id in 0..self.job_master.machine_series_size as u16 {
-            elapsed_actor_time_map.insert(actor_id, 0);
-        }
-
-        /* ----------------------------------------------------------------
-        // makespan の計算
-        ---------------------------------------------------------------- */
-        // gene は job_id
-        for job_id in chromosome.iter() {
-            // ---------------------- 値の取得 ----------------------
-            // 次に実行する operation の番号
-            let operation_no: u8 = done_operations_count[job_id];
-
-            // 次の operation の実行時間を取得
-            let operation_time: u16 = self.job_master.exec_times
-                [*job_id as usize][operation_no as usize];
-
-            // 次の operation の actor_id を取得
-            let actor_id: u16 = self.job_master.actor_sequences
-                [*job_id as usize][operation_no as usize];
-
-            // ---------------------- 値の更新 ----------------------
-            // 該当 actor の経過時間を更新
-            elapsed_actor_time_map.insert(
-                actor_id,
-                elapsed_actor_time_map[&actor_id] + operation_time,
+    /// スクラッチバッファを持つ評価器を作る
+    pub fn evaluator(&self) -> ScoreEvaluator<'_> {
+        ScoreEvaluator {
+            calculator: self,
+            state: self.flat.as_ref().map(FlatInstance::state),
+        }
+    }
+
+    /// 染色体の makespan を求める (1回だけ評価する場合の簡易版)
+    ///
+    /// 呼び出しごとに evaluator() でスクラッチバッファを確保するため、
+    /// 探索中など評価を繰り返す場合は ScoreEvaluator を作って使い回す
+    pub fn calc_makespan(&self, chromosome: &[u16]) -> Time {
+        self.evaluator().calc_makespan(chromosome)
+    }
+
+    /// swap 近傍の差分評価器を作る
+    /// (flat_compatible なデコーダかつ目的関数が makespan の場合のみ)
+    pub fn swap_evaluator(
+        &self,
+        chromosome: &[u16],
+    ) -> Option<SwapEvaluator<'_>> {
+        if !self.objective.is_makespan() {
+            return None;
+        }
+        let interval: usize = (chromosome.len() as f64).sqrt().ceil() as usize;
+        self.flat
+            .as_ref()
+            .map(|instance| SwapEvaluator::new(instance, chromosome, interval))
+    }
+
+    /// 挿入近傍の差分評価器を作る
+    /// (順列フローショップの復号器かつ目的関数が makespan の場合のみ)
+    pub fn insertion_evaluator(
+        &self,
+        chromosome: &[u16],
+    ) -> Option<InsertionEvaluator<'_>> {
+        if !self.objective.is_makespan()
+            || !self.decoder.permutation_flow_shop()
+        {
+            return None;
+        }
+        InsertionEvaluator::new(&self.job_master, chromosome)
+    }
+
+    /// 染色体をデコードして、各 operation の開始・終了時刻を含むスケジュールを返す
+    pub fn decode_schedule(&self, chromosome: &[u16]) -> Schedule {
+        self.decoder.decode(&self.job_master, chromosome)
+    }
+
+    /// 染色体を目的関数で評価する (1回だけ評価する場合の簡易版)
+    ///
+    /// 呼び出しごとに evaluator() でスクラッチバッファを確保するため、
+    /// 探索中など評価を繰り返す場合は ScoreEvaluator を作って使い回す
+    pub fn evaluate(&self, chromosome: &[u16]) -> Evaluation {
+        self.evaluator().evaluate(chromosome)
+    }
+
+    /// デコード済みのスケジュール (ファイルに記録された割り当てなど) を評価する
+    pub fn evaluate_schedule(&self, schedule: &Schedule) -> Evaluation {
+        let objective_value: f64 =
+            self.objective.evaluate(&self.job_master, schedule);
+        Evaluation {
+            makespan: schedule.makespan,
+            objective_value,
+            score: self.objective.to_score(objective_value),
+        }
+    }
+
+    /// 目的関数が makespan の場合の評価結果
+    pub fn makespan_evaluation(&self, makespan: Time) -> Evaluation {
+        Evaluation {
+            makespan,
+            objective_value: makespan as f64,
+            score: self.objective.to_score(makespan as f64),
+        }
+    }
+}
+
+impl ScoreEvaluator<'_> {
+    pub fn calc_makespan(&mut self, chromosome: &[u16]) -> Time {
+        let calculator: &ScoreCalculator = self.calculator;
+        match (&calculator.flat, &mut self.state) {
+            (Some(instance), Some(state)) => {
+                instance.makespan(chromosome, state)
+            }
+            _ => calculator
+                .decoder
+                .makespan(&calculator.job_master, chromosome),
+        }
+    }
+
+    /// 染色体を目的関数で評価する
+    pub fn evaluate(&mut self, chromosome: &[u16]) -> Evaluation {
+        let calculator: &ScoreCalculator = self.calculator;
+        if calculator.objective.is_makespan() {
+            return calculator
+                .makespan_evaluation(self.calc_makespan(chromosome));
+        }
+        let schedule: Schedule = calculator.decode_schedule(chromosome);
+        let objective_value: f64 = calculator
+            .objective
+            .evaluate(&calculator.job_master, &schedule);
+        Evaluation {
+            makespan: schedule.makespan,
+            objective_value,
+            score: calculator.objective.to_score(objective_value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::validator::validate;
+    use crate::optimization::preprocess::calendar::{
+        Calendars, Downtime, Interruption,
+    };
+    use crate::optimization::test_fixtures::{
+        flexible_job_master, sample_job_master,
+    };
+    use crate::optimization::types;
+
+    fn sample_calculator() -> ScoreCalculator {
+        ScoreCalculator::new(sample_job_master())
+    }
+
+    /// validator で制約違反がないことを確認する
+    fn assert_feasible(job_master: &JobMaster, schedule: &Schedule) {
+        assert_eq!(validate(job_master, schedule), vec![]);
+    }
+
+    #[test]
+    fn test_decode_schedule() {
+        let calculator = sample_calculator();
+        let chromosome: types::Chromosome = vec![0, 1, 2, 0, 1, 2];
+        let schedule = calculator.decode_schedule(&chromosome);
+
+        let times: Vec<(u16, usize, u16, Time, Time)> = schedule
+            .operations
+            .iter()
+            .map(|op| (op.job, op.operation, op.actor_id, op.start, op.end))
+            .collect();
+        assert_eq!(
+            times,
+            vec![
+                (0, 0, 0, 0, 3),
+                (1, 0, 1, 0, 2),
+                (2, 0, 0, 3, 7),
+                (0, 1, 1, 3, 5),
+                (1, 1, 0, 7, 12),
+                (2, 1, 1, 7, 8),
+            ]
+        );
+        assert_eq!(
+            schedule.machine_sequences,
+            vec![vec![0, 2, 4], vec![1, 3, 5]]
+        );
+        assert_eq!(schedule.makespan, 12);
+    }
+
+    #[test]
+    fn test_decode_schedule_matches_calc_makespan() {
+        let calculator = sample_calculator();
+        for chromosome in [
+            vec![0, 1, 2, 0, 1, 2],
+            vec![2, 2, 1, 0, 0, 1],
+            vec![1, 1, 0, 2, 0, 2],
+        ] {
+            let schedule = calculator.decode_schedule(&chromosome);
+            assert_eq!(
+                schedule.makespan,
+                calculator.calc_makespan(&chromosome)
             );
-            // 該当 job の経過時間を更新
-            elapsed_job_time_map
-                .insert(*job_id, elapsed_job_time_map[job_id] + operation_time);
-
-            // 該当 job の経過時間と actor の経過時間のうち大きい方の時間で、小さい方の経過時間を更新
-            if elapsed_actor_time_map[&actor_id] < elapsed_job_time_map[job_id]
-            {
-                elapsed_actor_time_map
-                    .insert(actor_id, elapsed_job_time_map[job_id]);
-            } else {
-                elapsed_job_time_map
-                    .insert(*job_id, elapsed_actor_time_map[&actor_id]);
+        }
+    }
+
+    #[test]
+    fn test_swap_evaluator_only_for_semi_active_makespan() {
+        let chromosome: types::Chromosome = vec![0, 1, 2, 0, 1, 2];
+        let calculator = sample_calculator();
+        let evaluator = calculator.swap_evaluator(&chromosome).unwrap();
+        assert_eq!(evaluator.makespan(), calculator.calc_makespan(&chromosome));
+
+        let active = ScoreCalculator::with_decoder(
+            sample_job_master(),
+            Box::new(ActiveDecoder),
+        );
+        assert!(active.swap_evaluator(&chromosome).is_none());
+        let flow_time = sample_calculator().with_objective(Box::new(
+            crate::optimization::decoder::objective::TotalFlowTime,
+        ));
+        assert!(flow_time.swap_evaluator(&chromosome).is_none());
+    }
+
+    #[test]
+    fn test_calc_makespan_beyond_u16() {
+        // 100 job x 20 actor、実行時間 1000 では makespan が u16 を超える
+        let job_size: usize = 100;
+        let job_master = JobMaster::try_new(
+            job_size,
+            20,
+            vec![vec![1000; 20]; job_size],
+            vec![(0..20).collect(); job_size],
+        )
+        .unwrap();
+        let calculator = ScoreCalculator::new(job_master);
+        let chromosome: types::Chromosome = (0..job_size * 20)
+            .map(|gene| (gene % job_size) as u16)
+            .collect();
+        // 全 job が同じ順に actor を訪れるため、パイプラインの長さになる
+        let expected: Time = 1000 * (job_size as Time + 19);
+        assert_eq!(calculator.calc_makespan(&chromosome), expected);
+        assert_eq!(calculator.decode_schedule(&chromosome).makespan, expected);
+    }
+
+    #[test]
+    fn test_active_decoder_fills_idle_gap() {
+        // semi-active では job 1 の operation が actor 1 の末尾 (時刻 10) まで待たされるが、
+        // active では actor 1 の空き時間 (時刻 0) に前詰めされる
+        let job_master = sample_job_master();
+        let chromosome: types::Chromosome = vec![0, 2, 2, 0, 1, 1];
+        let semi_active = SemiActiveDecoder.decode(&job_master, &chromosome);
+        let active = ActiveDecoder.decode(&job_master, &chromosome);
+        assert_eq!(semi_active.job_operations(1)[0].start, 10);
+        assert_eq!(semi_active.makespan, 17);
+        assert_eq!(active.job_operations(1)[0].start, 0);
+        assert_eq!(active.makespan, 12);
+        assert_feasible(&job_master, &active);
+    }
+
+    #[test]
+    fn test_decoders_produce_feasible_schedules() {
+        let job_master = sample_job_master();
+        for chromosome in [
+            vec![0, 1, 2, 0, 1, 2],
+            vec![2, 2, 1, 0, 0, 1],
+            vec![1, 1, 0, 2, 0, 2],
+        ] {
+            for name in DECODER_NAMES {
+                let decoder = decoder_from_name(name).unwrap();
+                assert_eq!(decoder.name(), name);
+                let schedule = decoder.decode(&job_master, &chromosome);
+                assert_eq!(schedule.operations.len(), chromosome.len());
+                assert_feasible(&job_master, &schedule);
+                assert_eq!(
+                    decoder.makespan(&job_master, &chromosome),
+                    schedule.makespan
+                );
             }
+        }
+    }
 
-            // 該当 job の operation 進捗を更新
-            done_operations_count
-                .insert(*job_id, done_operations_count[job_id] + 1);
+    #[test]
+    fn test_decoders_with_setup_times() {
+        let mut job_master = sample_job_master();
+        // setup_times[actor][from][to]
+        job_master.setup_times = Some(vec![
+            vec![vec![0, 2, 1], vec![3, 0, 2], vec![1, 4, 0]],
+            vec![vec![0, 1, 5], vec![2, 0, 1], vec![3, 2, 0]],
+        ]);
+        for chromosome in [
+            vec![0, 1, 2, 0, 1, 2],
+            vec![2, 2, 1, 0, 0, 1],
+            vec![1, 1, 0, 2, 0, 2],
+        ] {
+            for name in DECODER_NAMES {
+                let decoder = decoder_from_name(name).unwrap();
+                let schedule = decoder.decode(&job_master, &chromosome);
+                assert_feasible(&job_master, &schedule);
+                assert_eq!(
+                    decoder.makespan(&job_master, &chromosome),
+                    schedule.makespan
+                );
+            }
         }
 
-        // 各 job の経過時間のうち最大値を取得
-        let makespan: u16 =
-            elapsed_job_time_map.values().max().unwrap().to_owned();
+        // actor 0 で job 0 → job 2 の間に段取り 1 が入る
+        let schedule =
+            SemiActiveDecoder.decode(&job_master, &[0, 1, 2, 0, 1, 2]);
+        let op = &schedule.operations[2];
+        assert_eq!((op.job, op.setup, op.start, op.end), (2, 1, 4, 8));
+    }
 
-        makespan
+    #[test]
+    fn test_decoders_with_calendars() {
+        let mut job_master = sample_job_master();
+        // actor 0 は [4, 6) と [9, 10)、actor 1 は [1, 3) に停止する
+        let downtime = |start: Time, end: Time| Downtime { start, end };
+        let downtimes: Vec<Vec<Downtime>> =
+            vec![vec![downtime(4, 6), downtime(9, 10)], vec![downtime(1, 3)]];
+        for (interruption, expected) in [
+            (Interruption::NonResumable, (10, 14)),
+            (Interruption::Resumable, (3, 9)),
+        ] {
+            job_master.calendars =
+                Some(Calendars::new(downtimes.clone(), interruption));
+            for chromosome in [
+                vec![0, 1, 2, 0, 1, 2],
+                vec![2, 2, 1, 0, 0, 1],
+                vec![1, 1, 0, 2, 0, 2],
+            ] {
+                for name in DECODER_NAMES {
+                    let decoder = decoder_from_name(name).unwrap();
+                    let schedule = decoder.decode(&job_master, &chromosome);
+                    assert_feasible(&job_master, &schedule);
+                    assert_eq!(
+                        decoder.makespan(&job_master, &chromosome),
+                        schedule.makespan
+                    );
+                }
+            }
+
+            // job 2 の最初の operation (actor 0、実行時間 4) は時刻 3 から処理できる
+            // (中断しない場合は [6, 9) にも収まらず、10 から処理する)
+            let schedule =
+                SemiActiveDecoder.decode(&job_master, &[0, 1, 2, 0, 1, 2]);
+            let op = &schedule.operations[2];
+            assert_eq!((op.job, op.start, op.end), (2, expected.0, expected.1));
+        }
+    }
+
+    #[test]
+    fn test_non_delay_decoder_starts_as_early_as_possible() {
+        // 時刻 0 には actor 0 も actor 1 も空いているので、
+        // actor 0 では染色体上で先に現れる job 2 が、actor 1 では job 1 が時刻 0 に開始する
+        let job_master = sample_job_master();
+        let chromosome: types::Chromosome = vec![2, 2, 0, 0, 1, 1];
+        let schedule = NonDelayDecoder.decode(&job_master, &chromosome);
+        assert_eq!(schedule.job_operations(1)[0].start, 0);
+        assert_eq!(schedule.job_operations(2)[0].start, 0);
+        assert!(decoder_from_name("unknown").is_none());
+    }
+
+    #[test]
+    fn test_flexible_decoder() {
+        let job_master = flexible_job_master();
+        // 前半が operation 順、後半が (job, operation) 順の actor の選択
+        let chromosome: types::Chromosome = vec![0, 1, 0, 1, 1, 1, 0, 0, 1, 0];
+        let schedule = FlexibleDecoder.decode(&job_master, &chromosome);
+        assert_feasible(&job_master, &schedule);
+        let times: Vec<(u16, usize, u16, Time, Time)> = schedule
+            .operations
+            .iter()
+            .map(|op| (op.job, op.operation, op.actor_id, op.start, op.end))
+            .collect();
+        assert_eq!(
+            times,
+            vec![
+                (0, 0, 1, 0, 5),
+                (1, 0, 0, 0, 4),
+                (0, 1, 1, 5, 7),
+                (1, 1, 1, 7, 8),
+                (1, 2, 1, 8, 11),
+            ]
+        );
+        assert_eq!(
+            assignment_from_operations(&job_master, &schedule.operations),
+            vec![1, 0, 0, 1, 0]
+        );
+
+        // 後半がない場合は最初の選択肢を使う
+        let schedule = FlexibleDecoder.decode(&job_master, &[0, 1, 0, 1, 1]);
+        assert_feasible(&job_master, &schedule);
+        assert_eq!(schedule.makespan, 12);
+    }
+
+    #[test]
+    fn test_flexible_decoder_matches_semi_active() {
+        let job_master = sample_job_master();
+        let chromosome: types::Chromosome = vec![1, 0, 2, 2, 0, 1];
+        assert_eq!(
+            FlexibleDecoder.decode(&job_master, &chromosome),
+            SemiActiveDecoder.decode(&job_master, &chromosome)
+        );
     }
 }
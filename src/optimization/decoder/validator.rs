@@ -0,0 +1,612 @@
+use std::fmt;
+
+use crate::optimization::decoder::schedule::{Schedule, ScheduledOperation};
+use crate::optimization::preprocess::runner::{Alternative, JobMaster};
+use crate::optimization::types::Time;
+
+/// スケジュールが満たしていない制約
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// job 番号・operation 番号がインスタンスの範囲外
+    UnknownOperation { job: u16, operation: usize },
+    /// 同じ operation が2回以上割り当てられている
+    DuplicateOperation { job: u16, operation: usize },
+    /// 割り当てられていない operation がある
+    MissingOperation { job: u16, operation: usize },
+    /// operation を処理する actor が指定と異なる
+    WrongActor {
+        job: u16,
+        operation: usize,
+        expected: u16,
+        actual: u16,
+    },
+    /// 終了時刻 - 開始時刻 が実行時間と一致しない
+    /// (稼働カレンダーで中断する場合は、途中の停止時間帯を含めた長さと一致しない)
+    WrongDuration {
+        job: u16,
+        operation: usize,
+        expected: Time,
+        start: Time,
+        end: Time,
+    },
+    /// actor の停止時間帯に開始している
+    /// (中断しない設定の場合は、処理時間が停止時間帯にかかっている)
+    Unavailable {
+        job: u16,
+        operation: usize,
+        actor_id: u16,
+        start: Time,
+    },
+    /// job のリリース時刻より前に開始している
+    BeforeRelease {
+        job: u16,
+        operation: usize,
+        release_date: Time,
+        start: Time,
+    },
+    /// job 内の前の operation が終わる前に開始している
+    Precedence {
+        job: u16,
+        operation: usize,
+        previous_end: Time,
+        start: Time,
+    },
+    /// オープンショップで、同じ job の2つの operation の処理時間が重なっている
+    JobOverlap {
+        job: u16,
+        first: usize,
+        second: usize,
+    },
+    /// 同じ actor 上で2つの operation の処理時間が重なっている
+    Overlap {
+        actor_id: u16,
+        first: (u16, usize),
+        second: (u16, usize),
+    },
+    /// 同じ actor 上で続く2つの operation の間が段取り時間より短い
+    SetupTime {
+        actor_id: u16,
+        first: (u16, usize),
+        second: (u16, usize),
+        required: Time,
+        gap: Time,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::UnknownOperation { job, operation } => write!(
+                f,
+                "job {}, operation {}: not in the instance",
+                job, operation
+            ),
+            Violation::DuplicateOperation { job, operation } => write!(
+                f,
+                "job {}, operation {}: scheduled more than once",
+                job, operation
+            ),
+            Violation::MissingOperation { job, operation } => write!(
+                f,
+                "job {}, operation {}: not scheduled",
+                job, operation
+            ),
+            Violation::WrongActor {
+                job,
+                operation,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "job {}, operation {}: processed on actor {}, but expected {}",
+                job, operation, actual, expected
+            ),
+            Violation::WrongDuration {
+                job,
+                operation,
+                expected,
+                start,
+                end,
+            } => write!(
+                f,
+                "job {}, operation {}: runs from {} to {}, but the processing time is {}",
+                job, operation, start, end, expected
+            ),
+            Violation::Unavailable {
+                job,
+                operation,
+                actor_id,
+                start,
+            } => write!(
+                f,
+                "job {}, operation {}: processing from {} conflicts with a downtime of actor {}",
+                job, operation, start, actor_id
+            ),
+            Violation::BeforeRelease {
+                job,
+                operation,
+                release_date,
+                start,
+            } => write!(
+                f,
+                "job {}, operation {}: starts at {} before the release date {}",
+                job, operation, start, release_date
+            ),
+            Violation::Precedence {
+                job,
+                operation,
+                previous_end,
+                start,
+            } => write!(
+                f,
+                "job {}, operation {}: starts at {} before the previous operation ends at {}",
+                job, operation, start, previous_end
+            ),
+            Violation::JobOverlap { job, first, second } => write!(
+                f,
+                "job {}: operation {} overlaps operation {}",
+                job, first, second
+            ),
+            Violation::Overlap {
+                actor_id,
+                first,
+                second,
+            } => write!(
+                f,
+                "actor {}: job {} operation {} overlaps job {} operation {}",
+                actor_id, first.0, first.1, second.0, second.1
+            ),
+            Violation::SetupTime {
+                actor_id,
+                first,
+                second,
+                required,
+                gap,
+            } => write!(
+                f,
+                "actor {}: only {} between job {} operation {} and job {} operation {}, but the setup time is {}",
+                actor_id, gap, first.0, first.1, second.0, second.1, required
+            ),
+        }
+    }
+}
+
+/// スケジュールが job_master の制約を満たすか確認し、違反の一覧を返す
+/// (デコーダーの実装によらず、schedule.operations だけから判定する)
+///
+/// - 全 operation がちょうど1回ずつ割り当てられていること
+/// - 指定された actor (FJSP の場合は選択肢のいずれか) で、
+///   実行時間どおりに処理されていること
+/// - 稼働カレンダーがある場合は、停止時間帯を避けて (中断できる場合は
+///   停止時間帯の間だけ中断して) 処理されていること
+/// - リリース時刻以降に開始し、job 内の順序を守っていること
+///   (オープンショップの場合は、job 内で処理時間が重ならないこと)
+/// - 同じ actor 上で処理時間が重ならず、続く operation の間に段取り時間があること
+pub fn validate(job_master: &JobMaster, schedule: &Schedule) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = Vec::new();
+    let mut assigned: Vec<Vec<Option<&ScheduledOperation>>> = (0..job_master
+        .job_size)
+        .map(|job| vec![None; job_master.operation_count(job)])
+        .collect();
+    let mut machine_operations: Vec<Vec<&ScheduledOperation>> =
+        vec![Vec::new(); job_master.machine_series_size];
+
+    for op in schedule.operations.iter() {
+        let (job, operation): (usize, usize) = (op.job as usize, op.operation);
+        let slot: &mut Option<&ScheduledOperation> = match assigned
+            .get_mut(job)
+            .and_then(|operations| operations.get_mut(operation))
+        {
+            Some(slot) => slot,
+            None => {
+                violations.push(Violation::UnknownOperation {
+                    job: op.job,
+                    operation,
+                });
+                continue;
+            }
+        };
+        if slot.is_some() {
+            violations.push(Violation::DuplicateOperation {
+                job: op.job,
+                operation,
+            });
+            continue;
+        }
+        *slot = Some(op);
+
+        // FJSP の場合は選択肢のいずれかの actor であればよい
+        let alternative: Option<Alternative> = (0..job_master
+            .alternative_count(job, operation))
+            .map(|choice| job_master.alternative(job, operation, choice as u16))
+            .find(|alternative| alternative.actor_id == op.actor_id);
+        let expected_time: Time = match alternative {
+            Some(alternative) => {
+                machine_operations[op.actor_id as usize].push(op);
+                alternative.time
+            }
+            None => {
+                violations.push(Violation::WrongActor {
+                    job: op.job,
+                    operation,
+                    expected: job_master.actor_sequences[job][operation],
+                    actual: op.actor_id,
+                });
+                job_master.exec_times[job][operation]
+            }
+        };
+        // 稼働カレンダーで中断する場合は、停止時間帯の分だけ終了が延びる
+        let expected_end: Option<Time> =
+            match (&job_master.calendars, alternative) {
+                (Some(calendars), Some(_)) => {
+                    let actor: usize = op.actor_id as usize;
+                    if calendars.earliest_start(actor, op.start, expected_time)
+                        != op.start
+                    {
+                        violations.push(Violation::Unavailable {
+                            job: op.job,
+                            operation,
+                            actor_id: op.actor_id,
+                            start: op.start,
+                        });
+                    }
+                    op.start.checked_add(expected_time).map(|_| {
+                        calendars.finish_time(actor, op.start, expected_time)
+                    })
+                }
+                _ => op.start.checked_add(expected_time),
+            };
+        if Some(op.end) != expected_end {
+            violations.push(Violation::WrongDuration {
+                job: op.job,
+                operation,
+                expected: expected_time,
+                start: op.start,
+                end: op.end,
+            });
+        }
+    }
+
+    for (job, operations) in assigned.iter().enumerate() {
+        let release_date: Time = job_master.release_date(job);
+        let mut previous_end: Option<Time> = None;
+        for (operation, op) in operations.iter().enumerate() {
+            let op: &ScheduledOperation = match op {
+                Some(op) => op,
+                None => {
+                    violations.push(Violation::MissingOperation {
+                        job: job as u16,
+                        operation,
+                    });
+                    continue;
+                }
+            };
+            if op.start < release_date {
+                violations.push(Violation::BeforeRelease {
+                    job: op.job,
+                    operation,
+                    release_date,
+                    start: op.start,
+                });
+            }
+            // オープンショップでは順序を問わないため、後で重なりだけを確認する
+            if let Some(previous_end) =
+                previous_end.filter(|_| !job_master.open_shop)
+            {
+                if op.start < previous_end {
+                    violations.push(Violation::Precedence {
+                        job: op.job,
+                        operation,
+                        previous_end,
+                        start: op.start,
+                    });
+                }
+            }
+            previous_end = Some(op.end);
+        }
+
+        if job_master.open_shop {
+            let mut sorted: Vec<&ScheduledOperation> =
+                operations.iter().flatten().copied().collect();
+            sorted.sort_by_key(|op| (op.start, op.end));
+            for pair in sorted.windows(2) {
+                if pair[1].start < pair[0].end {
+                    violations.push(Violation::JobOverlap {
+                        job: job as u16,
+                        first: pair[0].operation,
+                        second: pair[1].operation,
+                    });
+                }
+            }
+        }
+    }
+
+    for (actor_id, operations) in machine_operations.iter_mut().enumerate() {
+        operations.sort_by_key(|op| (op.start, op.end));
+        for pair in operations.windows(2) {
+            if pair[1].start < pair[0].end {
+                violations.push(Violation::Overlap {
+                    actor_id: actor_id as u16,
+                    first: (pair[0].job, pair[0].operation),
+                    second: (pair[1].job, pair[1].operation),
+                });
+                continue;
+            }
+            let required: Time = job_master.setup_time(
+                actor_id,
+                Some(pair[0].job as usize),
+                pair[1].job as usize,
+            );
+            let gap: Time = pair[1].start - pair[0].end;
+            if gap < required {
+                violations.push(Violation::SetupTime {
+                    actor_id: actor_id as u16,
+                    first: (pair[0].job, pair[0].operation),
+                    second: (pair[1].job, pair[1].operation),
+                    required,
+                    gap,
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::preprocess::calendar::{
+        Calendars, Downtime, Interruption,
+    };
+    use crate::optimization::test_fixtures::small_job_master;
+
+    fn op(
+        job: u16,
+        operation: usize,
+        actor_id: u16,
+        start: Time,
+        end: Time,
+    ) -> ScheduledOperation {
+        ScheduledOperation {
+            job,
+            operation,
+            actor_id,
+            start,
+            end,
+            setup: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_feasible_schedule() {
+        let schedule = Schedule::new(
+            vec![
+                op(0, 0, 0, 0, 3),
+                op(1, 0, 1, 0, 2),
+                op(0, 1, 1, 3, 5),
+                op(1, 1, 0, 3, 7),
+            ],
+            2,
+        );
+        assert_eq!(validate(&small_job_master(), &schedule), vec![]);
+    }
+
+    #[test]
+    fn test_validate_reports_violations() {
+        let mut job_master = small_job_master();
+        job_master.release_dates = Some(vec![0, 1]);
+        let schedule = Schedule::new(
+            vec![
+                op(0, 0, 0, 0, 3),
+                // リリース時刻より前に開始し、実行時間も短い
+                op(1, 0, 1, 0, 1),
+                // job 0 の前の operation (終了 3) より前に開始し、
+                // actor 1 上で job 1 の operation と重なる
+                op(0, 1, 1, 0, 2),
+                op(0, 1, 1, 5, 7),
+                op(2, 0, 0, 0, 1),
+            ],
+            2,
+        );
+        assert_eq!(
+            validate(&job_master, &schedule),
+            vec![
+                Violation::WrongDuration {
+                    job: 1,
+                    operation: 0,
+                    expected: 2,
+                    start: 0,
+                    end: 1
+                },
+                Violation::DuplicateOperation {
+                    job: 0,
+                    operation: 1
+                },
+                Violation::UnknownOperation {
+                    job: 2,
+                    operation: 0
+                },
+                Violation::Precedence {
+                    job: 0,
+                    operation: 1,
+                    previous_end: 3,
+                    start: 0
+                },
+                Violation::BeforeRelease {
+                    job: 1,
+                    operation: 0,
+                    release_date: 1,
+                    start: 0
+                },
+                Violation::MissingOperation {
+                    job: 1,
+                    operation: 1
+                },
+                Violation::Overlap {
+                    actor_id: 1,
+                    first: (1, 0),
+                    second: (0, 1)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_wrong_actor() {
+        let schedule = Schedule::new(
+            vec![
+                op(0, 0, 1, 0, 3),
+                op(1, 0, 1, 3, 5),
+                op(0, 1, 1, 5, 7),
+                op(1, 1, 0, 5, 9),
+            ],
+            2,
+        );
+        assert_eq!(
+            validate(&small_job_master(), &schedule),
+            vec![Violation::WrongActor {
+                job: 0,
+                operation: 0,
+                expected: 0,
+                actual: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_flexible_schedule() {
+        let alternative =
+            |actor_id: u16, time: Time| Alternative { actor_id, time };
+        let job_master = JobMaster::try_new_flexible(
+            1,
+            2,
+            vec![vec![
+                vec![alternative(0, 3), alternative(1, 5)],
+                vec![alternative(1, 2)],
+            ]],
+        )
+        .unwrap();
+        // 選択肢のいずれの actor でもよく、実行時間は選んだ actor のもの
+        let schedule =
+            Schedule::new(vec![op(0, 0, 1, 0, 5), op(0, 1, 1, 5, 7)], 2);
+        assert_eq!(validate(&job_master, &schedule), vec![]);
+
+        let schedule =
+            Schedule::new(vec![op(0, 0, 1, 0, 3), op(0, 1, 0, 3, 5)], 2);
+        assert_eq!(
+            validate(&job_master, &schedule),
+            vec![
+                Violation::WrongDuration {
+                    job: 0,
+                    operation: 0,
+                    expected: 5,
+                    start: 0,
+                    end: 3
+                },
+                Violation::WrongActor {
+                    job: 0,
+                    operation: 1,
+                    expected: 1,
+                    actual: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_open_shop_schedule() {
+        let job_master =
+            JobMaster::try_new_open_shop(1, 2, vec![vec![3, 2]]).unwrap();
+        // operation の順序は問わない
+        let schedule =
+            Schedule::new(vec![op(0, 1, 1, 0, 2), op(0, 0, 0, 2, 5)], 2);
+        assert_eq!(validate(&job_master, &schedule), vec![]);
+
+        let schedule =
+            Schedule::new(vec![op(0, 1, 1, 0, 2), op(0, 0, 0, 1, 4)], 2);
+        assert_eq!(
+            validate(&job_master, &schedule),
+            vec![Violation::JobOverlap {
+                job: 0,
+                first: 1,
+                second: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_calendar() {
+        let mut job_master = small_job_master();
+        // actor 1 は [1, 4) に停止する
+        let downtimes: Vec<Vec<Downtime>> =
+            vec![vec![], vec![Downtime { start: 1, end: 4 }]];
+        job_master.calendars = Some(Calendars::new(
+            downtimes.clone(),
+            Interruption::NonResumable,
+        ));
+        let schedule = Schedule::new(
+            vec![
+                op(0, 0, 0, 0, 3),
+                op(1, 0, 1, 4, 6),
+                op(0, 1, 1, 6, 8),
+                op(1, 1, 0, 6, 10),
+            ],
+            2,
+        );
+        assert_eq!(validate(&job_master, &schedule), vec![]);
+
+        // 停止時間帯をまたいで処理している
+        let schedule = Schedule::new(
+            vec![
+                op(0, 0, 0, 0, 3),
+                op(1, 0, 1, 0, 2),
+                op(0, 1, 1, 4, 6),
+                op(1, 1, 0, 3, 7),
+            ],
+            2,
+        );
+        assert_eq!(
+            validate(&job_master, &schedule),
+            vec![Violation::Unavailable {
+                job: 1,
+                operation: 0,
+                actor_id: 1,
+                start: 0
+            }]
+        );
+
+        // 中断できる場合は、停止時間帯の分だけ終了が延びる
+        job_master.calendars =
+            Some(Calendars::new(downtimes, Interruption::Resumable));
+        let schedule = Schedule::new(
+            vec![
+                op(0, 0, 0, 0, 3),
+                op(1, 0, 1, 0, 5),
+                op(0, 1, 1, 5, 7),
+                op(1, 1, 0, 5, 9),
+            ],
+            2,
+        );
+        assert_eq!(validate(&job_master, &schedule), vec![]);
+        let schedule = Schedule::new(
+            vec![
+                op(0, 0, 0, 0, 3),
+                op(1, 0, 1, 0, 2),
+                op(0, 1, 1, 5, 7),
+                op(1, 1, 0, 5, 9),
+            ],
+            2,
+        );
+        assert_eq!(
+            validate(&job_master, &schedule),
+            vec![Violation::WrongDuration {
+                job: 1,
+                operation: 0,
+                expected: 2,
+                start: 0,
+                end: 2
+            }]
+        );
+    }
+}
@@ -3,4 +3,7 @@ pub mod decoder;
 // pub mod initializer;
 pub mod preprocess;
 pub mod runner;
+/// 複数のモジュールのテストで共有するインスタンス
+#[cfg(test)]
+pub(crate) mod test_fixtures;
 pub mod types;
@@ -0,0 +1,144 @@
+use std::path::Path;
+
+use crate::optimization::types::Time;
+
+/// 代表的なベンチマークインスタンスの最適値 (または既知の最良値, BKS)
+///
+/// 出典: OR-Library / Taillard の公開値。abz8, abz9 は最適性が未証明
+pub const BEST_KNOWN_MAKESPANS: [(&str, Time); 68] = [
+    ("ft06", 55),
+    ("ft10", 930),
+    ("ft20", 1165),
+    ("la01", 666),
+    ("la02", 655),
+    ("la03", 597),
+    ("la04", 590),
+    ("la05", 593),
+    ("la06", 926),
+    ("la07", 890),
+    ("la08", 863),
+    ("la09", 951),
+    ("la10", 958),
+    ("la11", 1222),
+    ("la12", 1039),
+    ("la13", 1150),
+    ("la14", 1292),
+    ("la15", 1207),
+    ("la16", 945),
+    ("la17", 784),
+    ("la18", 848),
+    ("la19", 842),
+    ("la20", 902),
+    ("la21", 1046),
+    ("la22", 927),
+    ("la23", 1032),
+    ("la24", 935),
+    ("la25", 977),
+    ("la26", 1218),
+    ("la27", 1235),
+    ("la28", 1216),
+    ("la29", 1152),
+    ("la30", 1355),
+    ("la31", 1784),
+    ("la32", 1850),
+    ("la33", 1719),
+    ("la34", 1721),
+    ("la35", 1888),
+    ("la36", 1268),
+    ("la37", 1397),
+    ("la38", 1196),
+    ("la39", 1233),
+    ("la40", 1222),
+    ("abz5", 1234),
+    ("abz6", 943),
+    ("abz7", 656),
+    ("abz8", 648),
+    ("abz9", 678),
+    ("orb01", 1059),
+    ("orb02", 888),
+    ("orb03", 1005),
+    ("orb04", 1005),
+    ("orb05", 887),
+    ("orb06", 1010),
+    ("orb07", 397),
+    ("orb08", 899),
+    ("orb09", 934),
+    ("orb10", 944),
+    ("ta01", 1231),
+    ("ta02", 1244),
+    ("ta03", 1218),
+    ("ta04", 1175),
+    ("ta05", 1224),
+    ("ta06", 1238),
+    ("ta07", 1227),
+    ("ta08", 1217),
+    ("ta09", 1274),
+    ("ta10", 1241),
+];
+
+/// インスタンスファイルのパスから、表を引くための名前 (小文字のファイル名、拡張子なし) を求める
+pub fn instance_name<P: AsRef<Path>>(path: P) -> String {
+    path.as_ref()
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 既知の最良値を探す (extra に同名があれば BEST_KNOWN_MAKESPANS より優先する)
+pub fn best_known_makespan(
+    name: &str,
+    extra: &[(String, Time)],
+) -> Option<Time> {
+    let name: String = name.to_lowercase();
+    extra
+        .iter()
+        .find(|(known, _)| known.to_lowercase() == name)
+        .map(|(_, makespan)| *makespan)
+        .or_else(|| {
+            BEST_KNOWN_MAKESPANS
+                .iter()
+                .find(|(known, _)| *known == name)
+                .map(|(_, makespan)| *makespan)
+        })
+}
+
+/// "インスタンス名,makespan" の行からなる表を読み込む
+/// 空行と、makespan が数値でない行 (見出し行など) は読み飛ばす
+pub fn parse_best_known_table(text: &str) -> Vec<(String, Time)> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split(',').map(|field| field.trim());
+            let name: &str = fields.next()?;
+            let makespan: Time = fields.next()?.parse::<Time>().ok()?;
+            (!name.is_empty()).then(|| (name.to_string(), makespan))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_name() {
+        assert_eq!(instance_name("data/input/LA40.txt"), "la40");
+        assert_eq!(instance_name("ft06"), "ft06");
+    }
+
+    #[test]
+    fn test_best_known_makespan() {
+        assert_eq!(best_known_makespan("ft06", &[]), Some(55));
+        assert_eq!(best_known_makespan("LA40", &[]), Some(1222));
+        assert_eq!(best_known_makespan("factory", &[]), None);
+
+        let extra = parse_best_known_table(
+            "instance,makespan\nfactory, 1500\n\nft06,54\nbroken\n",
+        );
+        assert_eq!(
+            extra,
+            vec![("factory".to_string(), 1500), ("ft06".to_string(), 54)]
+        );
+        assert_eq!(best_known_makespan("factory", &extra), Some(1500));
+        assert_eq!(best_known_makespan("ft06", &extra), Some(54));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::optimization::types::Time;
+
+/// actor を使えない時間帯 [start, end) (夜間・計画保全など)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Downtime {
+    pub start: Time,
+    pub end: Time,
+}
+
+/// 処理中に停止時間帯にかかる operation の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interruption {
+    /// 停止時間帯をまたげない (またぐ場合は停止時間帯の後まで開始を遅らせる)
+    #[default]
+    NonResumable,
+    /// 停止時間帯で中断し、停止時間帯の後に残りを処理する
+    Resumable,
+}
+
+/// 指定できる中断の扱いの名前
+pub const INTERRUPTION_NAMES: [&str; 2] = ["non-resumable", "resumable"];
+
+impl Interruption {
+    /// 名前から取得 (未知の名前の場合は None)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "non-resumable" => Some(Interruption::NonResumable),
+            "resumable" => Some(Interruption::Resumable),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Interruption::NonResumable => "non-resumable",
+            Interruption::Resumable => "resumable",
+        }
+    }
+}
+
+/// actor ごとの稼働カレンダー
+///
+/// 停止時間帯以外はいつでも処理でき、最後の停止時間帯の後はずっと稼働している
+/// (段取りは停止時間帯にも行えるものとし、operation の処理にだけ適用する)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Calendars {
+    /// actor ごとの停止時間帯 (開始時刻順で、重なり・接する時間帯はまとめてある)
+    downtimes: Vec<Vec<Downtime>>,
+    pub interruption: Interruption,
+}
+
+impl Calendars {
+    /// downtimes[actor_id] の停止時間帯は順不同・重なりがあってもよい
+    /// (長さ 0 の時間帯は無視する)
+    pub fn new(
+        downtimes: Vec<Vec<Downtime>>,
+        interruption: Interruption,
+    ) -> Self {
+        let downtimes: Vec<Vec<Downtime>> = downtimes
+            .into_iter()
+            .map(|mut windows| {
+                windows.retain(|window| window.start < window.end);
+                windows.sort_by_key(|window| (window.start, window.end));
+                let mut merged: Vec<Downtime> =
+                    Vec::with_capacity(windows.len());
+                for window in windows {
+                    match merged.last_mut() {
+                        Some(last) if window.start <= last.end => {
+                            last.end = last.end.max(window.end)
+                        }
+                        _ => merged.push(window),
+                    }
+                }
+                merged
+            })
+            .collect();
+        Calendars {
+            downtimes,
+            interruption,
+        }
+    }
+
+    /// カレンダーを持つ actor の数
+    pub fn actor_count(&self) -> usize {
+        self.downtimes.len()
+    }
+
+    /// actor の停止時間帯 (開始時刻順)
+    pub fn downtimes(&self, actor_id: usize) -> &[Downtime] {
+        &self.downtimes[actor_id]
+    }
+
+    /// 最も遅い停止時間帯の終了時刻 (停止時間帯がない場合は 0)
+    pub fn latest_end(&self) -> Time {
+        self.downtimes
+            .iter()
+            .filter_map(|windows| windows.last())
+            .map(|window| window.end)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// actor で ready 以降に duration の operation を開始できる最早時刻
+    pub fn earliest_start(
+        &self,
+        actor_id: usize,
+        ready: Time,
+        duration: Time,
+    ) -> Time {
+        let windows: &[Downtime] = self.remaining(actor_id, ready);
+        let mut start: Time = ready;
+        for window in windows {
+            // 停止時間帯の前に開始できる (中断しない場合は終了もできる) か
+            let fits: bool = match self.interruption {
+                Interruption::NonResumable => {
+                    start < window.start && start + duration <= window.start
+                }
+                Interruption::Resumable => start < window.start,
+            };
+            if fits {
+                break;
+            }
+            start = start.max(window.end);
+        }
+        start
+    }
+
+    /// actor で start に開始した duration の operation の終了時刻
+    /// (中断する場合は、途中の停止時間帯の長さを含む)
+    pub fn finish_time(
+        &self,
+        actor_id: usize,
+        start: Time,
+        duration: Time,
+    ) -> Time {
+        if self.interruption == Interruption::NonResumable {
+            return start + duration;
+        }
+        let mut time: Time = start;
+        let mut remaining: Time = duration;
+        for window in self.remaining(actor_id, start) {
+            if time + remaining <= window.start {
+                break;
+            }
+            remaining -= window.start.saturating_sub(time);
+            time = time.max(window.end);
+        }
+        time + remaining
+    }
+
+    /// actor で ready 以降に duration の operation を処理する (開始時刻, 終了時刻)
+    pub fn schedule(
+        &self,
+        actor_id: usize,
+        ready: Time,
+        duration: Time,
+    ) -> (Time, Time) {
+        let start: Time = self.earliest_start(actor_id, ready, duration);
+        (start, self.finish_time(actor_id, start, duration))
+    }
+
+    /// time より後に終わる停止時間帯
+    fn remaining(&self, actor_id: usize, time: Time) -> &[Downtime] {
+        let windows: &[Downtime] = &self.downtimes[actor_id];
+        let first: usize = windows.partition_point(|window| window.end <= time);
+        &windows[first..]
+    }
+}
+
+/// job が job_ready、actor が actor_ready に空くとき、actor の段取り (setup) を
+/// 終えてから duration の operation を処理する (開始時刻, 終了時刻)
+/// (稼働カレンダーがない場合は、空いた時刻からそのまま処理する)
+#[inline]
+pub fn earliest_window(
+    calendars: Option<&Calendars>,
+    actor_id: usize,
+    job_ready: Time,
+    actor_ready: Time,
+    setup: Time,
+    duration: Time,
+) -> (Time, Time) {
+    let ready: Time = job_ready.max(actor_ready + setup);
+    match calendars {
+        Some(calendars) => calendars.schedule(actor_id, ready, duration),
+        None => (ready, ready + duration),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// actor 0 は [5, 8) と [10, 12) に停止、actor 1 は停止なし
+    fn sample_calendars(interruption: Interruption) -> Calendars {
+        let downtime = |start: Time, end: Time| Downtime { start, end };
+        Calendars::new(
+            vec![
+                vec![downtime(10, 11), downtime(5, 8), downtime(11, 12)],
+                vec![],
+            ],
+            interruption,
+        )
+    }
+
+    #[test]
+    fn test_new_merges_downtimes() {
+        let calendars = sample_calendars(Interruption::NonResumable);
+        assert_eq!(
+            calendars.downtimes(0),
+            &[
+                Downtime { start: 5, end: 8 },
+                Downtime { start: 10, end: 12 }
+            ]
+        );
+        assert_eq!(calendars.downtimes(1), &[]);
+        assert_eq!(calendars.latest_end(), 12);
+    }
+
+    #[test]
+    fn test_non_resumable() {
+        let calendars = sample_calendars(Interruption::NonResumable);
+        // 停止時間帯の前に収まる
+        assert_eq!(calendars.schedule(0, 0, 5), (0, 5));
+        // 停止時間帯にかかるため後ろにずらす (次の隙間 [8, 10) にも収まらない)
+        assert_eq!(calendars.schedule(0, 2, 4), (12, 16));
+        assert_eq!(calendars.schedule(0, 6, 2), (8, 10));
+        // 停止時間帯のない actor
+        assert_eq!(calendars.schedule(1, 6, 4), (6, 10));
+    }
+
+    #[test]
+    fn test_resumable() {
+        let calendars = sample_calendars(Interruption::Resumable);
+        // 3 だけ処理して中断し、8 から残りの 1 を処理する
+        assert_eq!(calendars.schedule(0, 2, 4), (2, 9));
+        // 2 つの停止時間帯をまたぐ
+        assert_eq!(calendars.schedule(0, 4, 5), (4, 14));
+        // 停止時間帯中には開始できない
+        assert_eq!(calendars.schedule(0, 6, 2), (8, 10));
+        assert_eq!(calendars.schedule(0, 3, 2), (3, 5));
+    }
+}
@@ -0,0 +1,202 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::optimization::types::Time;
+
+/// JobMaster の構築に失敗した理由
+///
+/// row / column はいずれも 1 始まりで、入力ファイル上の行番号と列番号を指す
+/// (ファイルを介さずに構築した場合は row が job 番号、column が operation 番号)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobMasterError {
+    /// ヘッダー行 (job 数, machine 数) が存在しない
+    MissingHeader,
+    /// ヘッダー行に必要な列 (job 数, machine 数) が足りない
+    MissingHeaderValue { column: usize },
+    /// ヘッダー行の値を数値として解釈できない
+    InvalidHeader { column: usize, value: String },
+    /// 値を数値として解釈できない (範囲外を含む)
+    InvalidNumber {
+        row: usize,
+        column: usize,
+        value: String,
+    },
+    /// 時刻 (リリース時刻・納期) を 0 以上の整数として解釈できない (範囲外を含む)
+    InvalidTime {
+        row: usize,
+        column: usize,
+        value: String,
+    },
+    /// ヘッダー行に同じ列名が2回以上ある
+    DuplicateColumn { column: usize, name: String },
+    /// job の行数がヘッダーの job 数と一致しない
+    RowCount {
+        block: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// 最後の job の行より後に、読み込まれない行が残っている
+    ExtraRow { row: usize },
+    /// job 内の operation 数が machine 数と一致しない
+    OperationCount {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// 1 始まりの actor_id に 0 が含まれている
+    ZeroActorId { row: usize, column: usize },
+    /// actor_id (0 始まり) が machine 数の範囲外
+    ActorIdOutOfRange {
+        row: usize,
+        column: usize,
+        actor_id: u16,
+        machine_series_size: usize,
+    },
+    /// job 番号 (0 始まり) が job 数の範囲外
+    JobIdOutOfRange {
+        row: usize,
+        column: usize,
+        job: usize,
+        job_size: usize,
+    },
+    /// 停止時間帯の開始時刻が終了時刻より前でない
+    InvalidDowntime { row: usize, start: Time, end: Time },
+    /// 同一 job 内で同じ actor を2回以上訪れている
+    DuplicateActor {
+        row: usize,
+        column: usize,
+        actor_id: u16,
+    },
+    /// 目的関数の評価に必要な job 属性 (納期など) が指定されていない
+    MissingJobAttribute { name: &'static str },
+    /// job 数・machine 数が 0
+    EmptyInstance { name: &'static str },
+    /// job 数・machine 数が番号 (u16) で表せる範囲を超えている
+    SizeOutOfRange {
+        name: &'static str,
+        size: usize,
+        max: usize,
+    },
+    /// 実行時間の合計 (+ リリース時刻) が Time で表せる範囲を超えている
+    TimeOverflow { horizon: u64, max: u64 },
+    /// FJSP で処理できる actor が1つもない operation がある
+    NoAlternative { row: usize, column: usize },
+}
+
+impl fmt::Display for JobMasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobMasterError::MissingHeader => {
+                write!(f, "header row (job_size, machine_series_size) is missing")
+            }
+            JobMasterError::MissingHeaderValue { column } => {
+                write!(f, "header column {}: value is missing", column)
+            }
+            JobMasterError::InvalidHeader { column, value } => write!(
+                f,
+                "header column {}: {:?} is not a valid size",
+                column, value
+            ),
+            JobMasterError::InvalidNumber { row, column, value } => write!(
+                f,
+                "row {}, column {}: {:?} is not a valid number",
+                row, column, value
+            ),
+            JobMasterError::InvalidTime { row, column, value } => write!(
+                f,
+                "row {}, column {}: {:?} is not a valid time (a non-negative integer)",
+                row, column, value
+            ),
+            JobMasterError::DuplicateColumn { column, name } => write!(
+                f,
+                "header column {}: column {:?} appears more than once",
+                column, name
+            ),
+            JobMasterError::RowCount {
+                block,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} block: expected {} rows (job_size), but got {}",
+                block, expected, actual
+            ),
+            JobMasterError::ExtraRow { row } => write!(
+                f,
+                "row {}: unexpected row after the last job",
+                row
+            ),
+            JobMasterError::OperationCount {
+                row,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row {}: expected {} operations (machine_series_size), but got {}",
+                row, expected, actual
+            ),
+            JobMasterError::ZeroActorId { row, column } => write!(
+                f,
+                "row {}, column {}: actor id must start from 1",
+                row, column
+            ),
+            JobMasterError::ActorIdOutOfRange {
+                row,
+                column,
+                actor_id,
+                machine_series_size,
+            } => write!(
+                f,
+                "row {}, column {}: actor id {} (0-based) is out of range for {} machines",
+                row, column, actor_id, machine_series_size
+            ),
+            JobMasterError::JobIdOutOfRange {
+                row,
+                column,
+                job,
+                job_size,
+            } => write!(
+                f,
+                "row {}, column {}: job id {} (0-based) is out of range for {} jobs",
+                row, column, job, job_size
+            ),
+            JobMasterError::InvalidDowntime { row, start, end } => write!(
+                f,
+                "row {}: downtime start {} must be before its end {}",
+                row, start, end
+            ),
+            JobMasterError::DuplicateActor {
+                row,
+                column,
+                actor_id,
+            } => write!(
+                f,
+                "row {}, column {}: actor id {} (0-based) is visited more than once in the job",
+                row, column, actor_id
+            ),
+            JobMasterError::MissingJobAttribute { name } => {
+                write!(f, "job attribute {:?} is required but not given", name)
+            }
+            JobMasterError::EmptyInstance { name } => {
+                write!(f, "{} must be at least 1", name)
+            }
+            JobMasterError::SizeOutOfRange { name, size, max } => write!(
+                f,
+                "{} {} exceeds the maximum {}",
+                name, size, max
+            ),
+            JobMasterError::TimeOverflow { horizon, max } => write!(
+                f,
+                "total processing time (plus release date) {} exceeds the maximum time {}",
+                horizon, max
+            ),
+            JobMasterError::NoAlternative { row, column } => write!(
+                f,
+                "row {}, column {}: the operation has no eligible actor",
+                row, column
+            ),
+        }
+    }
+}
+
+impl Error for JobMasterError {}
@@ -0,0 +1,759 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::optimization::preprocess::error::JobMasterError;
+use crate::optimization::preprocess::runner::{Alternative, Block, JobMaster};
+use crate::optimization::types::Time;
+
+/// ベンチマークインスタンスのファイル形式
+///
+/// - OrLibrary: 1行目に "job数 machine数"、以降 job ごとに
+///   "machine time" のペアが並ぶ形式 (machine は 0 始まり)
+///   例: ft10, la01 - la40
+/// - Taillard: 1行目に "job数 machine数"、以降 job 数分の実行時間の行列、
+///   job 数分の machine の行列が続く形式 (machine は 1 始まり)
+///   "Times" / "Machines" の見出し行があってもなくてもよい
+///   例: ta01 - ta80
+/// - Fjs: FJSP の Brandimarte / Hurink 形式。1行目に "job数 machine数"
+///   (3つ目の値は平均の選択肢数で、読み飛ばす)、以降 job ごとに1行で
+///   "operation数" に続けて operation ごとに "選択肢数 (machine time)..."
+///   が並ぶ形式 (machine は 1 始まり)
+///   例: mk01 - mk10
+/// - TaillardOpenShop: Taillard のオープンショップ形式。並びは Taillard 形式と
+///   同じで、"processing times" / "machines" の見出し行がある。
+///   machine の行列は各実行時間を処理する machine を表す (順序はない)
+///   例: tai4_4 - tai20_20
+/// - GueretPrins: Guéret–Prins のオープンショップ形式。1行目に
+///   "job数 machine数"、以降 job ごとに machine 順の実行時間が並ぶ形式
+///   例: gp03-01 - gp10-10
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceFormat {
+    OrLibrary,
+    Taillard,
+    Fjs,
+    TaillardOpenShop,
+    GueretPrins,
+}
+
+/// コマンドライン引数などで指定できる形式名
+/// "auto" の場合は内容から形式を自動判定する
+pub const FORMAT_NAMES: [&str; 6] =
+    ["auto", "orlib", "taillard", "fjs", "taillard-os", "gp"];
+
+impl InstanceFormat {
+    /// 形式名から InstanceFormat を取得 ("auto" や未知の名前は None)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "orlib" | "or-library" => Some(InstanceFormat::OrLibrary),
+            "taillard" => Some(InstanceFormat::Taillard),
+            "fjs" => Some(InstanceFormat::Fjs),
+            "taillard-os" => Some(InstanceFormat::TaillardOpenShop),
+            "gp" | "gueret-prins" => Some(InstanceFormat::GueretPrins),
+            _ => None,
+        }
+    }
+}
+
+/// 数値のみで構成された行
+struct NumericLine {
+    line_no: usize,
+    values: Vec<u64>,
+}
+
+/// テキストを数値行と見出し行に分ける
+/// 数値として解釈できないトークンを含む行 (コメント、見出しなど) は見出し行とする
+fn split_lines(text: &str) -> (Vec<NumericLine>, Vec<String>) {
+    let mut numeric_lines: Vec<NumericLine> = Vec::new();
+    let mut labels: Vec<String> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let trimmed: &str = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let values: Option<Vec<u64>> = trimmed
+            .split_whitespace()
+            .map(|token| token.parse::<u64>().ok())
+            .collect();
+        match values {
+            Some(values) => numeric_lines.push(NumericLine {
+                line_no: i + 1,
+                values,
+            }),
+            None => labels.push(trimmed.to_lowercase()),
+        }
+    }
+
+    (numeric_lines, labels)
+}
+
+/// 先頭の数値行から job 数と machine 数を取得
+fn read_sizes(
+    numeric_lines: &[NumericLine],
+) -> Result<(usize, usize), JobMasterError> {
+    let first: &NumericLine =
+        numeric_lines.first().ok_or(JobMasterError::MissingHeader)?;
+    if first.values.len() < 2 {
+        return Err(JobMasterError::MissingHeaderValue {
+            column: first.values.len() + 1,
+        });
+    }
+    Ok((first.values[0] as usize, first.values[1] as usize))
+}
+
+/// 数値を u16 / Time などに変換 (範囲外の場合はエラー)
+fn convert<T: TryFrom<u64>>(
+    value: u64,
+    row: usize,
+    column: usize,
+) -> Result<T, JobMasterError> {
+    T::try_from(value).map_err(|_| JobMasterError::InvalidNumber {
+        row,
+        column,
+        value: value.to_string(),
+    })
+}
+
+/// job ごとのデータ行を取り出し、列数を検証する
+/// values_per_operation は operation 1つあたりの値の数
+fn take_rows<'a>(
+    numeric_lines: &'a [NumericLine],
+    offset: usize,
+    job_size: usize,
+    machine_series_size: usize,
+    values_per_operation: usize,
+    block_name: &'static str,
+) -> Result<&'a [NumericLine], JobMasterError> {
+    if numeric_lines.len() < offset + job_size {
+        return Err(JobMasterError::RowCount {
+            block: block_name,
+            expected: job_size,
+            actual: numeric_lines.len().saturating_sub(offset),
+        });
+    }
+    let rows: &[NumericLine] = &numeric_lines[offset..offset + job_size];
+    for row in rows {
+        if row.values.len() != machine_series_size * values_per_operation {
+            return Err(JobMasterError::OperationCount {
+                row: row.line_no,
+                expected: machine_series_size,
+                actual: row.values.len() / values_per_operation,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// end 番目以降に数値行が残っていないことを確認する
+/// (見出し行やコメントは split_lines で除いているため対象外)
+fn ensure_no_extra_rows(
+    numeric_lines: &[NumericLine],
+    end: usize,
+) -> Result<(), JobMasterError> {
+    match numeric_lines.get(end) {
+        Some(row) => Err(JobMasterError::ExtraRow { row: row.line_no }),
+        None => Ok(()),
+    }
+}
+
+/// 内容から形式を判定する
+/// 判定できない場合は None を返す
+pub fn detect_format(text: &str) -> Option<InstanceFormat> {
+    let (numeric_lines, labels) = split_lines(text);
+    // Taillard のジョブショップの見出しは "Times" で、オープンショップは
+    // "processing times"
+    if labels
+        .iter()
+        .any(|label| label.starts_with("processing times"))
+        && labels.iter().any(|label| label.starts_with("machines"))
+    {
+        return Some(InstanceFormat::TaillardOpenShop);
+    }
+    if labels.iter().any(|label| {
+        label.starts_with("times") || label.starts_with("machines")
+    }) {
+        return Some(InstanceFormat::Taillard);
+    }
+
+    let first_row_length: Option<(usize, usize)> = read_sizes(&numeric_lines)
+        .ok()
+        .and_then(|(_, machine_series_size)| {
+            let first_row: &NumericLine = numeric_lines.get(1)?;
+            Some((first_row.values.len(), machine_series_size))
+        });
+    let job_size: usize = read_sizes(&numeric_lines).map_or(0, |(n, _)| n);
+    match first_row_length {
+        // job 数分の行だけが続く場合は machine 順の実行時間の行列とみなす
+        Some((length, machine_series_size))
+            if length == machine_series_size
+                && numeric_lines.len() == 1 + job_size =>
+        {
+            Some(InstanceFormat::GueretPrins)
+        }
+        Some((length, machine_series_size))
+            if length == 2 * machine_series_size =>
+        {
+            Some(InstanceFormat::OrLibrary)
+        }
+        Some((length, machine_series_size))
+            if length == machine_series_size =>
+        {
+            Some(InstanceFormat::Taillard)
+        }
+        // FJSP の形式は job ごとの行の長さが一定でないため、読み込めるかで判定する
+        _ => parse_fjs(text).ok().map(|_| InstanceFormat::Fjs),
+    }
+}
+
+/// OR-Library 形式のインスタンスを JobMaster に変換
+pub fn parse_or_library(text: &str) -> Result<JobMaster, JobMasterError> {
+    let (numeric_lines, _) = split_lines(text);
+    let (job_size, machine_series_size) = read_sizes(&numeric_lines)?;
+    let rows: &[NumericLine] = take_rows(
+        &numeric_lines,
+        1,
+        job_size,
+        machine_series_size,
+        2,
+        "operation",
+    )?;
+    ensure_no_extra_rows(&numeric_lines, 1 + job_size)?;
+
+    let mut exec_times: Vec<Vec<Time>> = Vec::with_capacity(job_size);
+    let mut actor_sequences: Vec<Vec<u16>> = Vec::with_capacity(job_size);
+    for row in rows {
+        let mut times: Vec<Time> = Vec::with_capacity(machine_series_size);
+        let mut actors: Vec<u16> = Vec::with_capacity(machine_series_size);
+        // "machine time" のペアが operation の順に並んでいる
+        for (operation, pair) in row.values.chunks(2).enumerate() {
+            actors.push(convert(pair[0], row.line_no, 2 * operation + 1)?);
+            times.push(convert(pair[1], row.line_no, 2 * operation + 2)?);
+        }
+        exec_times.push(times);
+        actor_sequences.push(actors);
+    }
+
+    let job_master: JobMaster = JobMaster {
+        job_size,
+        machine_series_size,
+        exec_times,
+        actor_sequences,
+        ..Default::default()
+    };
+    job_master.validate_with(&|block, job, operation| match block {
+        Block::ExecTimes => (rows[job].line_no, 2 * operation + 2),
+        Block::Actors => (rows[job].line_no, 2 * operation + 1),
+    })?;
+    Ok(job_master)
+}
+
+/// Taillard 形式のインスタンスを JobMaster に変換
+pub fn parse_taillard(text: &str) -> Result<JobMaster, JobMasterError> {
+    let (numeric_lines, _) = split_lines(text);
+    let (job_size, machine_series_size) = read_sizes(&numeric_lines)?;
+    let time_rows: &[NumericLine] = take_rows(
+        &numeric_lines,
+        1,
+        job_size,
+        machine_series_size,
+        1,
+        "times",
+    )?;
+    let machine_rows: &[NumericLine] = take_rows(
+        &numeric_lines,
+        1 + job_size,
+        job_size,
+        machine_series_size,
+        1,
+        "machines",
+    )?;
+    ensure_no_extra_rows(&numeric_lines, 1 + 2 * job_size)?;
+
+    let mut exec_times: Vec<Vec<Time>> = Vec::with_capacity(job_size);
+    for row in time_rows {
+        let times: Vec<Time> = row
+            .values
+            .iter()
+            .enumerate()
+            .map(|(column, value)| convert(*value, row.line_no, column + 1))
+            .collect::<Result<_, _>>()?;
+        exec_times.push(times);
+    }
+
+    let mut actor_sequences: Vec<Vec<u16>> = Vec::with_capacity(job_size);
+    for row in machine_rows {
+        let mut actors: Vec<u16> = Vec::with_capacity(machine_series_size);
+        for (column, value) in row.values.iter().enumerate() {
+            // NOTE: machine は 1 始まりなので -1 しておく
+            if *value == 0 {
+                return Err(JobMasterError::ZeroActorId {
+                    row: row.line_no,
+                    column: column + 1,
+                });
+            }
+            actors.push(convert(*value - 1, row.line_no, column + 1)?);
+        }
+        actor_sequences.push(actors);
+    }
+
+    let job_master: JobMaster = JobMaster {
+        job_size,
+        machine_series_size,
+        exec_times,
+        actor_sequences,
+        ..Default::default()
+    };
+    job_master.validate_with(&|block, job, operation| match block {
+        Block::ExecTimes => (time_rows[job].line_no, operation + 1),
+        Block::Actors => (machine_rows[job].line_no, operation + 1),
+    })?;
+    Ok(job_master)
+}
+
+/// Taillard のオープンショップ形式のインスタンスを JobMaster に変換
+pub fn parse_taillard_open_shop(
+    text: &str,
+) -> Result<JobMaster, JobMasterError> {
+    // 並びは Taillard 形式と同じで、machine の行列は順序を持たない
+    let job_master: JobMaster = parse_taillard(text)?;
+    Ok(job_master
+        .to_open_shop()
+        .expect("Taillard instances are not flexible"))
+}
+
+/// Guéret–Prins のオープンショップ形式のインスタンスを JobMaster に変換
+pub fn parse_gueret_prins(text: &str) -> Result<JobMaster, JobMasterError> {
+    let (numeric_lines, _) = split_lines(text);
+    let (job_size, machine_series_size) = read_sizes(&numeric_lines)?;
+    let rows: &[NumericLine] = take_rows(
+        &numeric_lines,
+        1,
+        job_size,
+        machine_series_size,
+        1,
+        "processing times",
+    )?;
+    ensure_no_extra_rows(&numeric_lines, 1 + job_size)?;
+
+    let mut exec_times: Vec<Vec<Time>> = Vec::with_capacity(job_size);
+    for row in rows {
+        let times: Vec<Time> = row
+            .values
+            .iter()
+            .enumerate()
+            .map(|(column, value)| convert(*value, row.line_no, column + 1))
+            .collect::<Result<_, _>>()?;
+        exec_times.push(times);
+    }
+
+    // NOTE: machine 数が u16 に収まらない場合は validate でエラーにする
+    let actor_sequence: Vec<u16> =
+        (0..machine_series_size).map(|actor| actor as u16).collect();
+    let job_master: JobMaster = JobMaster {
+        job_size,
+        machine_series_size,
+        exec_times,
+        actor_sequences: vec![actor_sequence; job_size],
+        open_shop: true,
+        ..Default::default()
+    };
+    job_master.validate_with(&|_, job, operation| {
+        (rows[job].line_no, operation + 1)
+    })?;
+    Ok(job_master)
+}
+
+/// FJSP の Brandimarte / Hurink 形式 (.fjs) のインスタンスを JobMaster に変換
+pub fn parse_fjs(text: &str) -> Result<JobMaster, JobMasterError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    // 3つ目の値 (平均の選択肢数) は小数のことがあるため、先頭の2つだけを読む
+    let (_, header) = lines.next().ok_or(JobMasterError::MissingHeader)?;
+    let sizes: Vec<&str> = header.split_whitespace().collect();
+    let size = |column: usize| -> Result<usize, JobMasterError> {
+        let value: &str = sizes
+            .get(column)
+            .copied()
+            .ok_or(JobMasterError::MissingHeaderValue { column: column + 1 })?;
+        value
+            .parse::<usize>()
+            .map_err(|_| JobMasterError::InvalidHeader {
+                column: column + 1,
+                value: value.to_string(),
+            })
+    };
+    let job_size: usize = size(0)?;
+    let machine_series_size: usize = size(1)?;
+
+    let mut alternatives: Vec<Vec<Vec<Alternative>>> =
+        Vec::with_capacity(job_size);
+    // エラー位置を示すため、job ごとの行番号と、operation ごとの
+    // (行番号, 選択肢数の列番号)
+    let mut job_lines: Vec<usize> = Vec::with_capacity(job_size);
+    let mut positions: Vec<Vec<(usize, usize)>> = Vec::with_capacity(job_size);
+    for job in 0..job_size {
+        let (line_no, line) = lines.next().ok_or(JobMasterError::RowCount {
+            block: "jobs",
+            expected: job_size,
+            actual: job,
+        })?;
+        let values: Vec<u64> = line
+            .split_whitespace()
+            .enumerate()
+            .map(|(column, token)| {
+                token.parse::<u64>().map_err(|_| {
+                    JobMasterError::InvalidNumber {
+                        row: line_no,
+                        column: column + 1,
+                        value: token.to_string(),
+                    }
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let operation_count: usize =
+            values.first().copied().unwrap_or(0) as usize;
+        let mut operations: Vec<Vec<Alternative>> = Vec::new();
+        let mut operation_positions: Vec<(usize, usize)> = Vec::new();
+        // values[cursor] が次に読む値 (列番号は cursor + 1)
+        let mut cursor: usize = 1;
+        let truncated = |actual: usize| JobMasterError::OperationCount {
+            row: line_no,
+            expected: operation_count,
+            actual,
+        };
+        for operation in 0..operation_count {
+            let choice_count: usize =
+                *values.get(cursor).ok_or_else(|| truncated(operation))?
+                    as usize;
+            operation_positions.push((line_no, cursor + 1));
+            cursor += 1;
+            let mut choices: Vec<Alternative> =
+                Vec::with_capacity(choice_count);
+            for _ in 0..choice_count {
+                let (actor, time) = match values.get(cursor..cursor + 2) {
+                    Some(pair) => (pair[0], pair[1]),
+                    None => return Err(truncated(operation)),
+                };
+                // NOTE: machine は 1 始まりなので -1 しておく
+                if actor == 0 {
+                    return Err(JobMasterError::ZeroActorId {
+                        row: line_no,
+                        column: cursor + 1,
+                    });
+                }
+                choices.push(Alternative {
+                    actor_id: convert(actor - 1, line_no, cursor + 1)?,
+                    time: convert(time, line_no, cursor + 2)?,
+                });
+                cursor += 2;
+            }
+            operations.push(choices);
+        }
+        if cursor < values.len() {
+            // operation 数より多くの値が並んでいる
+            return Err(truncated(operation_count + 1));
+        }
+        alternatives.push(operations);
+        job_lines.push(line_no);
+        positions.push(operation_positions);
+    }
+    if let Some((line_no, _)) = lines.next() {
+        return Err(JobMasterError::ExtraRow { row: line_no });
+    }
+
+    let job_master: JobMaster =
+        JobMaster::flexible(job_size, machine_series_size, alternatives);
+    job_master.validate_with(&|_, job, operation| {
+        positions[job]
+            .get(operation)
+            .copied()
+            .unwrap_or((job_lines[job], 1))
+    })?;
+    Ok(job_master)
+}
+
+/// 指定された形式 (None の場合は自動判定) でインスタンスを JobMaster に変換
+pub fn parse_instance(
+    text: &str,
+    format: Option<InstanceFormat>,
+) -> Result<JobMaster, Box<dyn Error>> {
+    let format: InstanceFormat = match format {
+        Some(format) => format,
+        None => detect_format(text).ok_or(
+            "failed to detect instance format (OR-Library/Taillard/fjs/taillard-os/gp)",
+        )?,
+    };
+    let job_master: JobMaster = match format {
+        InstanceFormat::OrLibrary => parse_or_library(text)?,
+        InstanceFormat::Taillard => parse_taillard(text)?,
+        InstanceFormat::Fjs => parse_fjs(text)?,
+        InstanceFormat::TaillardOpenShop => parse_taillard_open_shop(text)?,
+        InstanceFormat::GueretPrins => parse_gueret_prins(text)?,
+    };
+    Ok(job_master)
+}
+
+/// ファイルからインスタンスを読み込む (形式は自動判定)
+pub fn load_instance<P: AsRef<Path>>(
+    path: P,
+) -> Result<JobMaster, Box<dyn Error>> {
+    let text: String = fs::read_to_string(path)?;
+    parse_instance(&text, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OR_LIBRARY_TEXT: &str = "\
+ instance sample
+ +++++++++++++++
+ 3 2
+ 0 3 1 2
+ 1 2 0 5
+ 0 4 1 1
+";
+
+    const TAILLARD_TEXT: &str = "\
+Nb of jobs, Nb of Machines, Time seed, Machine seed, Upper bound, Lower bound
+           3           2   840612802   398197754        12        10
+Times
+ 3 2
+ 2 5
+ 4 1
+Machines
+ 1 2
+ 2 1
+ 1 2
+";
+
+    fn assert_sample(job_master: &JobMaster) {
+        assert_eq!(job_master.job_size, 3);
+        assert_eq!(job_master.machine_series_size, 2);
+        assert_eq!(job_master.exec_times, vec![[3, 2], [2, 5], [4, 1]]);
+        assert_eq!(job_master.actor_sequences, vec![[0, 1], [1, 0], [0, 1]]);
+    }
+
+    #[test]
+    fn test_parse_or_library() {
+        assert_sample(&parse_or_library(OR_LIBRARY_TEXT).unwrap());
+    }
+
+    #[test]
+    fn test_parse_taillard() {
+        assert_sample(&parse_taillard(TAILLARD_TEXT).unwrap());
+    }
+
+    #[test]
+    fn test_parse_taillard_without_labels() {
+        let text = "3 2\n3 2\n2 5\n4 1\n1 2\n2 1\n1 2\n";
+        assert_eq!(detect_format(text), Some(InstanceFormat::Taillard));
+        assert_sample(&parse_instance(text, None).unwrap());
+    }
+
+    #[test]
+    fn test_parse_large_times() {
+        // u16 を超える実行時間も読み込める
+        let job_master = parse_or_library("1 2\n0 70000 1 3\n").unwrap();
+        assert_eq!(job_master.exec_times, vec![[70_000, 3]]);
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            detect_format(OR_LIBRARY_TEXT),
+            Some(InstanceFormat::OrLibrary)
+        );
+        assert_eq!(
+            detect_format(TAILLARD_TEXT),
+            Some(InstanceFormat::Taillard)
+        );
+        assert_eq!(detect_format("3 2\n1 2 3\n"), None);
+    }
+
+    #[test]
+    fn test_parse_open_shop() {
+        let text = "\
+number of jobs, number of machines, time seed, machine seed, upper bound, lower bound :
+           2           2   1166510396   164000672         9         9
+processing times :
+ 3 2
+ 2 4
+machines :
+ 1 2
+ 2 1
+";
+        assert_eq!(detect_format(text), Some(InstanceFormat::TaillardOpenShop));
+        let job_master: JobMaster = parse_instance(text, None).unwrap();
+        assert!(job_master.open_shop);
+        // 実行時間は machine 順に並べ替える
+        assert_eq!(job_master.exec_times, vec![[3, 2], [4, 2]]);
+
+        let text = "gp02-01\n2 2\n3 2\n4 2\n";
+        assert_eq!(detect_format(text), Some(InstanceFormat::GueretPrins));
+        let job_master: JobMaster = parse_instance(text, None).unwrap();
+        assert!(job_master.open_shop);
+        assert_eq!(job_master.exec_times, vec![[3, 2], [4, 2]]);
+        assert_eq!(job_master.actor_sequences, vec![[0, 1], [0, 1]]);
+
+        assert_eq!(
+            parse_gueret_prins("2 2\n3 2\n4\n").unwrap_err(),
+            JobMasterError::OperationCount {
+                row: 3,
+                expected: 2,
+                actual: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // ヘッダー行がない・値が足りない
+        assert_eq!(
+            parse_or_library("\n").unwrap_err(),
+            JobMasterError::MissingHeader
+        );
+        assert_eq!(
+            parse_or_library("3\n").unwrap_err(),
+            JobMasterError::MissingHeaderValue { column: 2 }
+        );
+        assert_eq!(parse_fjs("").unwrap_err(), JobMasterError::MissingHeader);
+        assert_eq!(
+            parse_fjs("2\n").unwrap_err(),
+            JobMasterError::MissingHeaderValue { column: 2 }
+        );
+        // 行数が足りない
+        assert_eq!(
+            parse_or_library("3 2\n0 3 1 2\n").unwrap_err(),
+            JobMasterError::RowCount {
+                block: "operation",
+                expected: 3,
+                actual: 1
+            }
+        );
+        // job 数より多くの行がある
+        assert_eq!(
+            parse_or_library("1 2\n0 3 1 2\n\n1 2 0 5\n").unwrap_err(),
+            JobMasterError::ExtraRow { row: 4 }
+        );
+        assert_eq!(
+            parse_taillard("1 2\n3 2\n1 2\n4 1\n").unwrap_err(),
+            JobMasterError::ExtraRow { row: 4 }
+        );
+        assert_eq!(
+            parse_gueret_prins("1 2\n3 2\n4 1\n").unwrap_err(),
+            JobMasterError::ExtraRow { row: 3 }
+        );
+        // 末尾の見出し行・空行は無視する
+        assert!(parse_or_library("1 2\n0 3 1 2\n\n+++ end\n").is_ok());
+        // 列数が足りない
+        assert_eq!(
+            parse_or_library("1 2\n0 3 1\n").unwrap_err(),
+            JobMasterError::OperationCount {
+                row: 2,
+                expected: 2,
+                actual: 1
+            }
+        );
+        // Taillard 形式で machine に 0 が含まれる
+        assert_eq!(
+            parse_taillard("1 2\n3 2\n0 1\n").unwrap_err(),
+            JobMasterError::ZeroActorId { row: 3, column: 1 }
+        );
+        // machine の範囲外と重複は元の位置を示す
+        assert_eq!(
+            parse_or_library("1 2\n0 3 2 2\n").unwrap_err(),
+            JobMasterError::ActorIdOutOfRange {
+                row: 2,
+                column: 3,
+                actor_id: 2,
+                machine_series_size: 2
+            }
+        );
+        assert_eq!(
+            parse_taillard("1 2\n3 2\n2 2\n").unwrap_err(),
+            JobMasterError::DuplicateActor {
+                row: 3,
+                column: 2,
+                actor_id: 1
+            }
+        );
+    }
+
+    /// job 0: (M1 3 | M2 5) → M2 2、job 1: M1 4 → (M1 2 | M2 1) → M2 3
+    const FJS_TEXT: &str = "\
+2 2 1.5
+2 2 1 3 2 5 1 2 2
+3 1 1 4 2 1 2 2 1 1 2 3
+";
+
+    #[test]
+    fn test_parse_fjs() {
+        assert_eq!(detect_format(FJS_TEXT), Some(InstanceFormat::Fjs));
+        let job_master = parse_instance(FJS_TEXT, None).unwrap();
+        assert!(job_master.is_flexible());
+        assert_eq!(job_master.total_operations(), 5);
+        let alternative =
+            |actor_id: u16, time: Time| Alternative { actor_id, time };
+        assert_eq!(
+            job_master.alternatives.as_ref().unwrap()[1],
+            vec![
+                vec![alternative(0, 4)],
+                vec![alternative(0, 2), alternative(1, 1)],
+                vec![alternative(1, 3)],
+            ]
+        );
+        // exec_times / actor_sequences には最短の選択肢が入る
+        assert_eq!(job_master.exec_times, vec![vec![3, 2], vec![4, 1, 3]]);
+        assert_eq!(job_master.actor_sequences, vec![vec![0, 1], vec![0, 1, 1]]);
+    }
+
+    #[test]
+    fn test_parse_fjs_errors() {
+        // operation 数より値が足りない
+        assert_eq!(
+            parse_fjs("1 1\n2 1 1 3\n").unwrap_err(),
+            JobMasterError::OperationCount {
+                row: 2,
+                expected: 2,
+                actual: 1
+            }
+        );
+        assert_eq!(
+            parse_fjs("1 1\n1 1 0 3\n").unwrap_err(),
+            JobMasterError::ZeroActorId { row: 2, column: 3 }
+        );
+        // 選択肢がない operation と範囲外の machine は選択肢数の位置を示す
+        assert_eq!(
+            parse_fjs("1 1\n1 0\n").unwrap_err(),
+            JobMasterError::NoAlternative { row: 2, column: 2 }
+        );
+        assert_eq!(
+            parse_fjs("1 1\n1 1 2 3\n").unwrap_err(),
+            JobMasterError::ActorIdOutOfRange {
+                row: 2,
+                column: 2,
+                actor_id: 1,
+                machine_series_size: 1
+            }
+        );
+        assert_eq!(
+            parse_fjs("2 1\n1 1 1 3\n").unwrap_err(),
+            JobMasterError::RowCount {
+                block: "jobs",
+                expected: 2,
+                actual: 1
+            }
+        );
+        assert_eq!(
+            parse_fjs("1 1\n1 1 1 3\n\n1 1 1 2\n").unwrap_err(),
+            JobMasterError::ExtraRow { row: 4 }
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::Time;
+
+/// makespan の下界
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowerBounds {
+    /// job ごとの (リリース時刻 + 実行時間の合計) の最大値
+    pub job_length: Time,
+    /// actor ごとの (最小の head + 負荷 + 最小の tail) の最大値
+    /// (FJSP の場合は、最短の実行時間の合計を machine 数で割った値)
+    pub machine_load: Time,
+    /// 1機械緩和 (head / tail 付き、中断可能) を Jackson の
+    /// preemptive schedule で解いた値の最大値 (FJSP の場合は 0)
+    pub one_machine: Time,
+}
+
+/// 1機械緩和での operation (head: 開始できる最早時刻, tail: 終了後に残る時間)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Relaxed {
+    pub(crate) head: Time,
+    pub(crate) time: Time,
+    pub(crate) tail: Time,
+}
+
+impl LowerBounds {
+    pub fn new(job_master: &JobMaster) -> Self {
+        if job_master.is_flexible() {
+            return LowerBounds::flexible(job_master);
+        }
+        if job_master.open_shop {
+            return LowerBounds::open_shop(job_master);
+        }
+        let mut relaxed: Vec<Vec<Relaxed>> =
+            vec![Vec::new(); job_master.machine_series_size];
+        let mut job_length: Time = 0;
+        for (job, (times, actors)) in job_master
+            .exec_times
+            .iter()
+            .zip(job_master.actor_sequences.iter())
+            .enumerate()
+        {
+            let total: Time = times.iter().sum();
+            let mut head: Time = job_master.release_date(job);
+            job_length = job_length.max(head + total);
+            let mut tail: Time = total;
+            for (time, actor) in times.iter().zip(actors.iter()) {
+                tail -= time;
+                relaxed[*actor as usize].push(Relaxed {
+                    head,
+                    time: *time,
+                    tail,
+                });
+                head += time;
+            }
+        }
+
+        let machine_load: Time = relaxed
+            .iter()
+            .filter(|operations| !operations.is_empty())
+            .map(|operations| {
+                operations.iter().map(|op| op.head).min().unwrap_or(0)
+                    + operations.iter().map(|op| op.time).sum::<Time>()
+                    + operations.iter().map(|op| op.tail).min().unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0);
+        let one_machine: Time = relaxed
+            .iter_mut()
+            .map(|operations| jackson_preemptive(operations))
+            .max()
+            .unwrap_or(0);
+
+        LowerBounds {
+            job_length,
+            machine_load,
+            one_machine,
+        }
+    }
+
+    /// FJSP の下界 (actor が決まっていないため、最短の実行時間で見積もる)
+    /// exec_times には operation ごとの最短の実行時間が入っている
+    fn flexible(job_master: &JobMaster) -> Self {
+        let job_length: Time = job_master
+            .exec_times
+            .iter()
+            .enumerate()
+            .map(|(job, times)| {
+                job_master.release_date(job) + times.iter().sum::<Time>()
+            })
+            .max()
+            .unwrap_or(0);
+        let total: u64 = job_master
+            .exec_times
+            .iter()
+            .flatten()
+            .map(|time| *time as u64)
+            .sum();
+        let machines: u64 = job_master.machine_series_size.max(1) as u64;
+        LowerBounds {
+            job_length,
+            machine_load: total.div_ceil(machines) as Time,
+            one_machine: 0,
+        }
+    }
+
+    /// オープンショップの下界 (順序が決まっていないため、head はリリース時刻、
+    /// tail は 0 として actor ごとに緩和する)
+    fn open_shop(job_master: &JobMaster) -> Self {
+        let mut relaxed: Vec<Vec<Relaxed>> =
+            vec![Vec::new(); job_master.machine_series_size];
+        let mut job_length: Time = 0;
+        for (job, times) in job_master.exec_times.iter().enumerate() {
+            let head: Time = job_master.release_date(job);
+            job_length = job_length.max(head + times.iter().sum::<Time>());
+            for (actor, time) in times.iter().enumerate() {
+                relaxed[actor].push(Relaxed {
+                    head,
+                    time: *time,
+                    tail: 0,
+                });
+            }
+        }
+        let machine_load: Time = relaxed
+            .iter()
+            .filter(|operations| !operations.is_empty())
+            .map(|operations| {
+                operations.iter().map(|op| op.head).min().unwrap_or(0)
+                    + operations.iter().map(|op| op.time).sum::<Time>()
+            })
+            .max()
+            .unwrap_or(0);
+        let one_machine: Time = relaxed
+            .iter_mut()
+            .map(|operations| jackson_preemptive(operations))
+            .max()
+            .unwrap_or(0);
+
+        LowerBounds {
+            job_length,
+            machine_load,
+            one_machine,
+        }
+    }
+
+    /// 最も強い (大きい) 下界
+    pub fn best(&self) -> Time {
+        self.job_length.max(self.machine_load).max(self.one_machine)
+    }
+}
+
+/// Jackson の preemptive schedule (tail の大きい operation を優先し、
+/// より tail の大きい operation が開始可能になったら中断する) で
+/// 1機械緩和を解き、max(終了時刻 + tail) を返す (中断可能な場合の最適値)
+pub(crate) fn jackson_preemptive(operations: &mut [Relaxed]) -> Time {
+    operations.sort_by_key(|op| op.head);
+    // (tail, 番号, 残りの実行時間) を tail の大きい順に取り出す
+    let mut ready: BinaryHeap<(Time, Reverse<usize>, Time)> = BinaryHeap::new();
+    let mut next: usize = 0;
+    let mut now: Time = 0;
+    let mut bound: Time = 0;
+    while next < operations.len() || !ready.is_empty() {
+        if ready.is_empty() {
+            now = now.max(operations[next].head);
+        }
+        while next < operations.len() && operations[next].head <= now {
+            let op: Relaxed = operations[next];
+            ready.push((op.tail, Reverse(next), op.time));
+            next += 1;
+        }
+        let (tail, index, remaining) = ready.pop().unwrap();
+        // 次の operation が開始可能になるまで (または完了するまで) 処理する
+        let run: Time = match operations.get(next) {
+            Some(op) => remaining.min(op.head - now),
+            None => remaining,
+        };
+        now += run;
+        if run < remaining {
+            ready.push((tail, index, remaining - run));
+        } else {
+            bound = bound.max(now + tail);
+        }
+    }
+    bound
+}
+
+/// 下界 (または既知の最良値) に対する makespan の差の割合 (%)
+pub fn gap(makespan: Time, bound: Time) -> f64 {
+    if bound == 0 {
+        return 0.0;
+    }
+    (makespan as f64 - bound as f64) / bound as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::preprocess::runner::Alternative;
+    use crate::optimization::test_fixtures::{
+        open_shop_job_master, sample_job_master,
+    };
+
+    #[test]
+    fn test_lower_bounds() {
+        let job_master = sample_job_master();
+        let bounds = LowerBounds::new(&job_master);
+        assert_eq!(bounds.job_length, 7);
+        // actor 0: head 0 + 負荷 12 + tail 0
+        assert_eq!(bounds.machine_load, 12);
+        // actor 0 で job 1 (head 2) を待つ必要はないため 12 のまま
+        assert_eq!(bounds.one_machine, 12);
+        assert_eq!(bounds.best(), 12);
+    }
+
+    #[test]
+    fn test_lower_bounds_with_release_dates() {
+        // job 0: actor 1 (10) → actor 0 (1)、job 1: actor 0 (1) → actor 1 (1)
+        // job 1 のリリース時刻 9 のため、actor 1 では job 0 の後に処理するしかない
+        let mut job_master = JobMaster::try_new(
+            2,
+            2,
+            vec![vec![10, 1], vec![1, 1]],
+            vec![vec![1, 0], vec![0, 1]],
+        )
+        .unwrap();
+        job_master.release_dates = Some(vec![0, 9]);
+        let bounds = LowerBounds::new(&job_master);
+        assert_eq!(bounds.job_length, 11);
+        // actor 1: 最小の head 0 + 負荷 11 + 最小の tail 0
+        assert_eq!(bounds.machine_load, 11);
+        // actor 1: job 0 (0-10, tail 1)、job 1 (head 10 から 1)
+        assert_eq!(bounds.one_machine, 11);
+        assert_eq!(bounds.best(), 11);
+    }
+
+    #[test]
+    fn test_jackson_preemptive() {
+        // 後から開始可能になる tail の大きい operation が割り込む
+        let mut operations = vec![
+            Relaxed {
+                head: 0,
+                time: 5,
+                tail: 0,
+            },
+            Relaxed {
+                head: 1,
+                time: 2,
+                tail: 10,
+            },
+        ];
+        // 0-1: op0, 1-3: op1 (3 + 10), 3-7: op0 (7 + 0)
+        assert_eq!(jackson_preemptive(&mut operations), 13);
+    }
+
+    #[test]
+    fn test_jackson_preemptive_exceeds_load_bound() {
+        let relaxed =
+            |head: Time, time: Time, tail: Time| Relaxed { head, time, tail };
+        // 最小の head 0 + 負荷 9 + 最小の tail 0 = 9 より強い下界になる
+        // 0-1: op0, 1-4: op1 (4 + 4), 4-7: op2 (7 + 4), 7-9: op0 (9 + 0)
+        let mut operations =
+            vec![relaxed(0, 3, 0), relaxed(1, 3, 4), relaxed(1, 3, 4)];
+        assert_eq!(jackson_preemptive(&mut operations), 11);
+    }
+
+    #[test]
+    fn test_gap() {
+        assert_eq!(gap(110, 100), 10.0);
+        assert_eq!(gap(55, 55), 0.0);
+        assert_eq!(gap(10, 0), 0.0);
+    }
+
+    #[test]
+    fn test_lower_bounds_flexible() {
+        let alternative =
+            |actor_id: u16, time: Time| Alternative { actor_id, time };
+        let job_master = JobMaster::try_new_flexible(
+            2,
+            2,
+            vec![
+                vec![vec![alternative(0, 3), alternative(1, 5)]],
+                vec![vec![alternative(0, 4)], vec![alternative(1, 2)]],
+            ],
+        )
+        .unwrap();
+        let bounds = LowerBounds::new(&job_master);
+        assert_eq!(bounds.job_length, 6);
+        // 最短の実行時間の合計 9 を 2 台で分ける
+        assert_eq!(bounds.machine_load, 5);
+        assert_eq!(bounds.one_machine, 0);
+        assert_eq!(bounds.best(), 6);
+    }
+
+    #[test]
+    fn test_lower_bounds_open_shop() {
+        let mut job_master = open_shop_job_master();
+        job_master.release_dates = Some(vec![0, 3]);
+        let bounds = LowerBounds::new(&job_master);
+        assert_eq!(bounds.job_length, 9);
+        assert_eq!(bounds.machine_load, 6);
+        // actor 1 は job 1 のリリースまで空く
+        assert_eq!(bounds.one_machine, 7);
+        assert_eq!(bounds.best(), 9);
+    }
+}
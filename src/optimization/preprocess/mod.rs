@@ -1 +1,6 @@
+pub mod best_known;
+pub mod calendar;
+pub mod error;
+pub mod instance_format;
+pub mod lower_bound;
 pub mod runner;
@@ -1,78 +1,1493 @@
-use std::collections::HashMap;
+use std::str::FromStr;
 
 use csv::StringRecord;
 
-#[derive(Debug, Clone)]
+use crate::optimization::preprocess::calendar::{
+    self, Calendars, Downtime, Interruption,
+};
+use crate::optimization::preprocess::error::JobMasterError;
+use crate::optimization::types::Time;
+
+#[derive(Debug, Clone, Default)]
 pub struct JobMaster {
     pub job_size: usize,
     pub machine_series_size: usize,
-    pub exec_times: Vec<Vec<u16>>,
+    pub exec_times: Vec<Vec<Time>>,
     pub actor_sequences: Vec<Vec<u16>>,
+    /// job ごとの納期 (任意)
+    pub due_dates: Option<Vec<Time>>,
+    /// job ごとのリリース時刻 (任意、この時刻より前には開始できない)
+    pub release_dates: Option<Vec<Time>>,
+    /// job ごとの重み (任意、重み付き納期遅れの計算に使う)
+    pub weights: Option<Vec<f64>>,
+    /// FJSP の場合、job ごと・operation ごとの処理可能な actor と実行時間
+    /// (None の場合は actor_sequences / exec_times のとおり1つに固定)
+    ///
+    /// FJSP では job ごとの operation 数は machine 数と一致しなくてよく、
+    /// exec_times / actor_sequences には最も短い実行時間の選択肢を入れておく
+    pub alternatives: Option<Vec<Vec<Vec<Alternative>>>>,
+    /// オープンショップ (job 内の operation の順序が決まっていない) か
+    ///
+    /// オープンショップでは exec_times[job][actor_id] が実行時間で、
+    /// actor_sequences は 0, 1, ... (operation 番号と actor_id が一致) にしておく
+    pub open_shop: bool,
+    /// actor ごとの段取り時間 (任意)
+    ///
+    /// setup_times[actor_id][前の job][次の job] は、同じ actor で前の job の
+    /// operation の後に次の job の operation を処理するとき、間に必要な時間
+    /// (actor の最初の operation の前には段取りは不要)
+    pub setup_times: Option<Vec<Vec<Vec<Time>>>>,
+    /// actor ごとの稼働カレンダー (任意、停止時間帯には operation を処理しない)
+    pub calendars: Option<Calendars>,
+}
+
+/// FJSP で operation を処理できる actor の1つ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alternative {
+    pub actor_id: u16,
+    pub time: Time,
 }
 
-/// CSVファイルのヘッダーから job 数と machine_series 数を取得
-fn extract_size(header: Option<StringRecord>, target_name: String) -> usize {
-    let mapping: HashMap<String, usize> = HashMap::from([
-        ("job_size".to_string(), 0),
-        ("machine_series_size".to_string(), 1),
-    ]);
+/// job 番号・actor_id を u16 で表すため、job 数・machine 数の上限
+pub const MAX_ID_COUNT: usize = u16::MAX as usize + 1;
 
-    let index: usize = *mapping
-        .get(&target_name)
-        .unwrap_or_else(|| panic!("{:?} not found", target_name));
+/// job 属性ファイルで指定できる列名
+pub const JOB_ATTRIBUTE_NAMES: [&str; 3] =
+    ["release_date", "due_date", "weight"];
 
-    let size: usize = header
-        .unwrap()
-        .get(index)
-        .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or(0); // パースに失敗した場合は0をデフォルト値とする
+/// 段取り時間ファイルの列名 (順不同、すべて必須)
+pub const SETUP_TIME_COLUMNS: [&str; 4] =
+    ["actor_id", "from_job", "to_job", "setup_time"];
 
-    size
+/// 稼働カレンダーファイルの列名 (順不同、すべて必須)
+pub const CALENDAR_COLUMNS: [&str; 3] = ["actor_id", "start", "end"];
+
+/// 検証対象のブロック (実行時間 / actor_id)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Block {
+    ExecTimes,
+    Actors,
 }
 
-pub fn run(header: Option<StringRecord>, rows: Vec<StringRecord>) -> JobMaster {
-    /* -------------------------------
-    rows を Vec<Vec<i16>> に変換
+impl Block {
+    fn name(&self) -> &'static str {
+        match self {
+            Block::ExecTimes => "exec_times",
+            Block::Actors => "actor_sequences",
+        }
+    }
+}
+
+/// (block, job, operation) から入力上の (row, column) を求める関数
+pub(crate) type Locate<'a> = &'a dyn Fn(Block, usize, usize) -> (usize, usize);
+
+impl JobMaster {
+    /// 値を検証したうえで JobMaster を生成
+    /// actor_id は 0 始まりで指定する
+    pub fn try_new(
+        job_size: usize,
+        machine_series_size: usize,
+        exec_times: Vec<Vec<Time>>,
+        actor_sequences: Vec<Vec<u16>>,
+    ) -> Result<Self, JobMasterError> {
+        let job_master: JobMaster = JobMaster {
+            job_size,
+            machine_series_size,
+            exec_times,
+            actor_sequences,
+            ..Default::default()
+        };
+        job_master.validate()?;
+        Ok(job_master)
+    }
+
+    /// FJSP の JobMaster を生成 (値の検証は呼び出し側で行う)
+    /// alternatives[job][operation] は処理可能な actor と実行時間の一覧
+    pub(crate) fn flexible(
+        job_size: usize,
+        machine_series_size: usize,
+        alternatives: Vec<Vec<Vec<Alternative>>>,
+    ) -> Self {
+        // 最も短い実行時間の選択肢 (同じ場合は先に書かれたもの)
+        let fastest = |choices: &Vec<Alternative>| {
+            choices
+                .iter()
+                .min_by_key(|alternative| alternative.time)
+                .copied()
+        };
+        let mut exec_times: Vec<Vec<Time>> = Vec::with_capacity(job_size);
+        let mut actor_sequences: Vec<Vec<u16>> = Vec::with_capacity(job_size);
+        for operations in alternatives.iter() {
+            let fastest: Vec<Alternative> = operations
+                .iter()
+                .map(|choices| {
+                    // NOTE: 選択肢が空の operation は validate でエラーにする
+                    fastest(choices).unwrap_or(Alternative {
+                        actor_id: 0,
+                        time: 0,
+                    })
+                })
+                .collect();
+            exec_times.push(fastest.iter().map(|choice| choice.time).collect());
+            actor_sequences
+                .push(fastest.iter().map(|choice| choice.actor_id).collect());
+        }
+        JobMaster {
+            job_size,
+            machine_series_size,
+            exec_times,
+            actor_sequences,
+            alternatives: Some(alternatives),
+            ..Default::default()
+        }
+    }
+
+    /// 値を検証したうえで FJSP の JobMaster を生成
+    pub fn try_new_flexible(
+        job_size: usize,
+        machine_series_size: usize,
+        alternatives: Vec<Vec<Vec<Alternative>>>,
+    ) -> Result<Self, JobMasterError> {
+        let job_master: JobMaster =
+            JobMaster::flexible(job_size, machine_series_size, alternatives);
+        job_master.validate()?;
+        Ok(job_master)
+    }
+
+    /// 値を検証したうえで順列フローショップの JobMaster を生成
+    /// すべての job が actor 0, 1, ... の順に処理される
+    pub fn try_new_flow_shop(
+        job_size: usize,
+        machine_series_size: usize,
+        exec_times: Vec<Vec<Time>>,
+    ) -> Result<Self, JobMasterError> {
+        let actor_sequence: Vec<u16> =
+            (0..machine_series_size as u16).collect();
+        JobMaster::try_new(
+            job_size,
+            machine_series_size,
+            exec_times,
+            vec![actor_sequence; job_size],
+        )
+    }
+
+    /// 値を検証したうえでオープンショップの JobMaster を生成
+    /// exec_times[job][actor_id] は job の actor での実行時間
+    pub fn try_new_open_shop(
+        job_size: usize,
+        machine_series_size: usize,
+        exec_times: Vec<Vec<Time>>,
+    ) -> Result<Self, JobMasterError> {
+        let mut job_master: JobMaster = JobMaster::try_new_flow_shop(
+            job_size,
+            machine_series_size,
+            exec_times,
+        )?;
+        job_master.open_shop = true;
+        Ok(job_master)
+    }
+
+    /// 順序を無視して、同じ実行時間のオープンショップに変換する
+    /// (FJSP は actor が決まっていないため変換できず None を返す)
+    pub fn to_open_shop(&self) -> Option<JobMaster> {
+        if self.is_flexible() {
+            return None;
+        }
+        if self.open_shop {
+            return Some(self.clone());
+        }
+        let mut exec_times: Vec<Vec<Time>> =
+            vec![vec![0; self.machine_series_size]; self.job_size];
+        for (job, (times, actors)) in self
+            .exec_times
+            .iter()
+            .zip(self.actor_sequences.iter())
+            .enumerate()
+        {
+            for (time, actor_id) in times.iter().zip(actors.iter()) {
+                exec_times[job][*actor_id as usize] = *time;
+            }
+        }
+        let actor_sequence: Vec<u16> =
+            (0..self.machine_series_size as u16).collect();
+        Some(JobMaster {
+            exec_times,
+            actor_sequences: vec![actor_sequence; self.job_size],
+            open_shop: true,
+            ..self.clone()
+        })
+    }
+
+    /// 独自 CSV 形式のレコードから JobMaster を生成
+    ///
+    /// - header: job 数, machine 数
+    /// - rows: job 数分の実行時間の行と、job 数分の actor_id (1 始まり) の行
+    pub fn try_from_records(
+        header: Option<StringRecord>,
+        rows: &[StringRecord],
+    ) -> Result<Self, JobMasterError> {
+        let header: StringRecord =
+            header.ok_or(JobMasterError::MissingHeader)?;
+        let job_size: usize = extract_size(&header, JOB_SIZE_COLUMN)?;
+        let machine_series_size: usize =
+            extract_size(&header, MACHINE_SERIES_SIZE_COLUMN)?;
+
+        if rows.len() != 2 * job_size {
+            return Err(JobMasterError::RowCount {
+                block: "records",
+                expected: 2 * job_size,
+                actual: rows.len(),
+            });
+        }
+
+        // NOTE: ヘッダーが1行目なので、rows[i] はファイル上の i + 2 行目
+        let mut exec_times: Vec<Vec<Time>> = Vec::with_capacity(job_size);
+        let mut actor_id_sequences: Vec<Vec<u16>> =
+            Vec::with_capacity(job_size);
+        for (i, row) in rows.iter().enumerate() {
+            let row_no: usize = i + 2;
+
+            // job 内各 operation の所要時間の整理
+            if i < job_size {
+                exec_times.push(parse_row(row, row_no)?);
+            // job 内各 operation のを実行可能なマシンの整理
+            } else {
+                let mut row_data: Vec<u16> = parse_row(row, row_no)?;
+                // NOTE: actor_id は +1 されている (0が登場しない) ので、-1 しておく
+                for (column, val) in row_data.iter_mut().enumerate() {
+                    if *val == 0 {
+                        return Err(JobMasterError::ZeroActorId {
+                            row: row_no,
+                            column: column + 1,
+                        });
+                    }
+                    *val -= 1;
+                }
+                actor_id_sequences.push(row_data);
+            }
+        }
+
+        let job_master: JobMaster = JobMaster {
+            job_size,
+            machine_series_size,
+            exec_times,
+            actor_sequences: actor_id_sequences,
+            ..Default::default()
+        };
+        job_master.validate_with(&|block, job, operation| match block {
+            Block::ExecTimes => (job + 2, operation + 1),
+            Block::Actors => (job_size + job + 2, operation + 1),
+        })?;
+        Ok(job_master)
+    }
+
+    /// job 属性 (release_date, due_date, weight) のレコードを読み込んで設定する
+    ///
+    /// - header: 列名 (JOB_ATTRIBUTE_NAMES のいずれか、順不同)
+    /// - rows: job ごとに1行
+    pub fn try_with_job_attributes(
+        mut self,
+        header: Option<StringRecord>,
+        rows: &[StringRecord],
+    ) -> Result<Self, JobMasterError> {
+        let header: StringRecord =
+            header.ok_or(JobMasterError::MissingHeader)?;
+        for (column, name) in header.iter().enumerate() {
+            if !JOB_ATTRIBUTE_NAMES.contains(&name.trim()) {
+                return Err(JobMasterError::InvalidHeader {
+                    column: column + 1,
+                    value: name.to_string(),
+                });
+            }
+            if header
+                .iter()
+                .take(column)
+                .any(|previous| previous.trim() == name.trim())
+            {
+                return Err(JobMasterError::DuplicateColumn {
+                    column: column + 1,
+                    name: name.trim().to_string(),
+                });
+            }
+        }
+        if rows.len() != self.job_size {
+            return Err(JobMasterError::RowCount {
+                block: "job_attributes",
+                expected: self.job_size,
+                actual: rows.len(),
+            });
+        }
+
+        for (column, name) in header.iter().enumerate() {
+            // NOTE: ヘッダーが1行目なので、rows[i] はファイル上の i + 2 行目
+            let fields = rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| (i + 2, row.get(column).unwrap_or("").trim()));
+            match name.trim() {
+                "weight" => {
+                    let weights: Vec<f64> = fields
+                        .map(|(row, field)| {
+                            field
+                                .parse::<f64>()
+                                .ok()
+                                .filter(|value| {
+                                    value.is_finite() && 0.0 <= *value
+                                })
+                                .ok_or_else(|| JobMasterError::InvalidNumber {
+                                    row,
+                                    column: column + 1,
+                                    value: field.to_string(),
+                                })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    self.weights = Some(weights);
+                }
+                name => {
+                    let times: Vec<Time> = fields
+                        .map(|(row, field)| {
+                            field.parse::<Time>().map_err(|_| {
+                                JobMasterError::InvalidTime {
+                                    row,
+                                    column: column + 1,
+                                    value: field.to_string(),
+                                }
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    if name == "release_date" {
+                        self.release_dates = Some(times);
+                    } else {
+                        self.due_dates = Some(times);
+                    }
+                }
+            }
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// 段取り時間のレコードを読み込んで設定する
+    ///
+    /// - header: 列名 (SETUP_TIME_COLUMNS、順不同)
+    /// - rows: (actor_id, from_job, to_job) の組ごとに1行 (いずれも 0 始まり)
+    ///
+    /// 指定のない組の段取り時間は 0 で、同じ組が複数ある場合は後の行を使う
+    pub fn try_with_setup_times(
+        mut self,
+        header: Option<StringRecord>,
+        rows: &[StringRecord],
+    ) -> Result<Self, JobMasterError> {
+        let header: StringRecord =
+            header.ok_or(JobMasterError::MissingHeader)?;
+        let positions: [usize; 4] =
+            column_positions(&header, SETUP_TIME_COLUMNS)?;
+
+        let mut setup_times: Vec<Vec<Vec<Time>>> =
+            vec![
+                vec![vec![0; self.job_size]; self.job_size];
+                self.machine_series_size
+            ];
+        for (i, row) in rows.iter().enumerate() {
+            // NOTE: ヘッダーが1行目なので、rows[i] はファイル上の i + 2 行目
+            let row_no: usize = i + 2;
+            let [actor_id, from_job, to_job, setup_time] =
+                parse_columns(row, row_no, positions)?;
+            if self.machine_series_size as u64 <= actor_id {
+                return Err(JobMasterError::ActorIdOutOfRange {
+                    row: row_no,
+                    column: positions[0] + 1,
+                    actor_id: actor_id.min(u16::MAX as u64) as u16,
+                    machine_series_size: self.machine_series_size,
+                });
+            }
+            for (job, position) in
+                [(from_job, positions[1]), (to_job, positions[2])]
+            {
+                if self.job_size as u64 <= job {
+                    return Err(JobMasterError::JobIdOutOfRange {
+                        row: row_no,
+                        column: position + 1,
+                        job: job as usize,
+                        job_size: self.job_size,
+                    });
+                }
+            }
+            let [setup_time]: [Time; 1] =
+                parse_times(row_no, [(setup_time, positions[3])])?;
+            setup_times[actor_id as usize][from_job as usize]
+                [to_job as usize] = setup_time;
+        }
+
+        self.setup_times = Some(setup_times);
+        self.validate()?;
+        Ok(self)
+    }
 
-    - header から job 数を取得
-    - rows から実行時間と operation ごとの actor_id のを取得
-    ------------------------------- */
+    /// 稼働カレンダー (actor ごとの停止時間帯) のレコードを読み込んで設定する
+    ///
+    /// - header: 列名 (CALENDAR_COLUMNS、順不同)
+    /// - rows: 停止時間帯ごとに1行 (actor_id は 0 始まり、end の時刻は含まない)
+    ///
+    /// 同じ actor の停止時間帯は順不同で、重なっていてもよい
+    /// (繰り返すシフトは時間帯ごとに1行ずつ指定する)
+    pub fn try_with_calendar(
+        mut self,
+        header: Option<StringRecord>,
+        rows: &[StringRecord],
+        interruption: Interruption,
+    ) -> Result<Self, JobMasterError> {
+        let header: StringRecord =
+            header.ok_or(JobMasterError::MissingHeader)?;
+        let positions: [usize; 3] =
+            column_positions(&header, CALENDAR_COLUMNS)?;
 
-    // headerから job 数を取得
-    let job_size: usize = extract_size(header.clone(), "job_size".to_string());
-    let machine_series_size: usize =
-        extract_size(header, "machine_series_size".to_string());
+        let mut downtimes: Vec<Vec<Downtime>> =
+            vec![Vec::new(); self.machine_series_size];
+        for (i, row) in rows.iter().enumerate() {
+            // NOTE: ヘッダーが1行目なので、rows[i] はファイル上の i + 2 行目
+            let row_no: usize = i + 2;
+            let [actor_id, start, end] = parse_columns(row, row_no, positions)?;
+            if self.machine_series_size as u64 <= actor_id {
+                return Err(JobMasterError::ActorIdOutOfRange {
+                    row: row_no,
+                    column: positions[0] + 1,
+                    actor_id: actor_id.min(u16::MAX as u64) as u16,
+                    machine_series_size: self.machine_series_size,
+                });
+            }
+            let [start, end]: [Time; 2] = parse_times(
+                row_no,
+                [(start, positions[1]), (end, positions[2])],
+            )?;
+            if end <= start {
+                return Err(JobMasterError::InvalidDowntime {
+                    row: row_no,
+                    start,
+                    end,
+                });
+            }
+            downtimes[actor_id as usize].push(Downtime { start, end });
+        }
 
-    // rows を Vec<Vec<i16>> に変換
-    let mut exec_times: Vec<Vec<u16>> = Vec::new();
-    let mut actor_id_sequences: Vec<Vec<u16>> = Vec::new();
-    for (i, row) in rows.iter().enumerate() {
-        let mut row_data: Vec<u16> = Vec::new();
-        for field in row.iter() {
-            if let Ok(value) = field.parse::<u16>() {
-                row_data.push(value);
+        self.calendars = Some(Calendars::new(downtimes, interruption));
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// actor で previous_job の operation の後に、job の duration の operation を
+    /// 処理するときの (段取り時間, 開始時刻, 終了時刻)
+    /// (job は job_ready、actor は actor_ready に空く。各デコーダはこれで時刻を決める)
+    #[inline]
+    pub fn earliest_window(
+        &self,
+        actor_id: usize,
+        previous_job: Option<usize>,
+        job: usize,
+        job_ready: Time,
+        actor_ready: Time,
+        duration: Time,
+    ) -> (Time, Time, Time) {
+        let setup: Time = self.setup_time(actor_id, previous_job, job);
+        let (start, end): (Time, Time) = calendar::earliest_window(
+            self.calendars.as_ref(),
+            actor_id,
+            job_ready,
+            actor_ready,
+            setup,
+            duration,
+        );
+        (setup, start, end)
+    }
+
+    /// actor で previous_job の operation の後に job の operation を処理するときの
+    /// 段取り時間 (previous_job が None の場合や、段取り時間の指定がない場合は 0)
+    pub fn setup_time(
+        &self,
+        actor_id: usize,
+        previous_job: Option<usize>,
+        job: usize,
+    ) -> Time {
+        match (&self.setup_times, previous_job) {
+            (Some(setup_times), Some(previous_job)) => {
+                setup_times[actor_id][previous_job][job]
             }
+            _ => 0,
         }
+    }
+
+    /// job のリリース時刻 (未指定の場合は 0)
+    pub fn release_date(&self, job: usize) -> Time {
+        self.release_dates
+            .as_ref()
+            .map_or(0, |release_dates| release_dates[job])
+    }
 
-        // job 内各 operation の所要時間の整理
-        if i < job_size {
-            exec_times.push(row_data);
-        // job 内各 operation のを実行可能なマシンの整理
+    /// job の重み (未指定の場合は 1)
+    pub fn weight(&self, job: usize) -> f64 {
+        self.weights.as_ref().map_or(1.0, |weights| weights[job])
+    }
+
+    /// FJSP (operation ごとに actor を選べる) のインスタンスか
+    pub fn is_flexible(&self) -> bool {
+        self.alternatives.is_some()
+    }
+
+    /// フローショップ (すべての job が actor 0, 1, ... の順に処理される) か
+    pub fn is_flow_shop(&self) -> bool {
+        !self.is_flexible()
+            && !self.open_shop
+            && self.actor_sequences.iter().all(|sequence| {
+                sequence.len() == self.machine_series_size
+                    && sequence
+                        .iter()
+                        .enumerate()
+                        .all(|(index, actor_id)| *actor_id as usize == index)
+            })
+    }
+
+    /// job の operation 数 (FJSP 以外では machine 数と同じ)
+    pub fn operation_count(&self, job: usize) -> usize {
+        self.exec_times[job].len()
+    }
+
+    /// 全 job の operation 数の合計
+    pub fn total_operations(&self) -> usize {
+        self.exec_times.iter().map(Vec::len).sum()
+    }
+
+    /// operation の actor の選択肢の数 (FJSP 以外では 1)
+    pub fn alternative_count(&self, job: usize, operation: usize) -> usize {
+        self.alternatives
+            .as_ref()
+            .map_or(1, |alternatives| alternatives[job][operation].len())
+    }
+
+    /// operation の choice 番目の選択肢 (選択肢の数で割った余りを使う)
+    pub fn alternative(
+        &self,
+        job: usize,
+        operation: usize,
+        choice: u16,
+    ) -> Alternative {
+        match &self.alternatives {
+            Some(alternatives) => {
+                let choices: &[Alternative] = &alternatives[job][operation];
+                choices[choice as usize % choices.len()]
+            }
+            None => Alternative {
+                actor_id: self.actor_sequences[job][operation],
+                time: self.exec_times[job][operation],
+            },
+        }
+    }
+
+    /// 値の整合性を検証 (row は job 番号、column は operation 番号)
+    pub fn validate(&self) -> Result<(), JobMasterError> {
+        self.validate_with(&|_, job, operation| (job + 1, operation + 1))
+    }
+
+    /// 値の整合性を検証し、エラー位置を locate で入力上の位置に変換する
+    ///
+    /// - job 数と各ブロックの行数が一致すること
+    /// - 各 job の operation 数と machine 数が一致すること
+    ///   (FJSP の場合は alternatives の operation 数と一致すること)
+    /// - actor_id が machine 数の範囲内であること
+    /// - 各 job が同じ actor を2回以上訪れないこと (FJSP の場合は除く)
+    /// - FJSP の場合は各 operation に1つ以上の選択肢があること
+    /// - job 属性 (納期など) が指定されている場合は job 数と一致すること
+    /// - 段取り時間が指定されている場合は (machine 数 x job 数 x job 数) であること
+    /// - 稼働カレンダーが指定されている場合は machine 数分あること
+    /// - job 数・machine 数が 1 以上で、u16 の番号で表せること
+    /// - 実行時間の合計 (+ リリース時刻) が Time に収まること
+    pub(crate) fn validate_with(
+        &self,
+        locate: Locate,
+    ) -> Result<(), JobMasterError> {
+        for (name, size) in [
+            ("job_size", self.job_size),
+            ("machine_series_size", self.machine_series_size),
+        ] {
+            if size == 0 {
+                return Err(JobMasterError::EmptyInstance { name });
+            }
+            if MAX_ID_COUNT < size {
+                return Err(JobMasterError::SizeOutOfRange {
+                    name,
+                    size,
+                    max: MAX_ID_COUNT,
+                });
+            }
+        }
+
+        for (block, lengths) in [
+            (
+                Block::ExecTimes,
+                self.exec_times.iter().map(Vec::len).collect::<Vec<usize>>(),
+            ),
+            (
+                Block::Actors,
+                self.actor_sequences.iter().map(Vec::len).collect(),
+            ),
+        ] {
+            if lengths.len() != self.job_size {
+                return Err(JobMasterError::RowCount {
+                    block: block.name(),
+                    expected: self.job_size,
+                    actual: lengths.len(),
+                });
+            }
+            for (job, length) in lengths.iter().enumerate() {
+                let expected: usize = match &self.alternatives {
+                    Some(alternatives) => alternatives
+                        .get(job)
+                        .map_or(self.machine_series_size, Vec::len),
+                    None => self.machine_series_size,
+                };
+                if *length != expected {
+                    return Err(JobMasterError::OperationCount {
+                        row: locate(block, job, 0).0,
+                        expected,
+                        actual: *length,
+                    });
+                }
+            }
+        }
+        if let Some(alternatives) = &self.alternatives {
+            self.validate_alternatives(alternatives, locate)?;
+        }
+
+        for (name, size) in [
+            ("due_dates", self.due_dates.as_ref().map(|v| v.len())),
+            (
+                "release_dates",
+                self.release_dates.as_ref().map(|v| v.len()),
+            ),
+            ("weights", self.weights.as_ref().map(|v| v.len())),
+        ] {
+            if let Some(size) = size {
+                if size != self.job_size {
+                    return Err(JobMasterError::RowCount {
+                        block: name,
+                        expected: self.job_size,
+                        actual: size,
+                    });
+                }
+            }
+        }
+
+        if let Some(setup_times) = &self.setup_times {
+            if setup_times.len() != self.machine_series_size {
+                return Err(JobMasterError::RowCount {
+                    block: "setup_times",
+                    expected: self.machine_series_size,
+                    actual: setup_times.len(),
+                });
+            }
+            // actor ごとに (job 数 x job 数) の行列であること
+            for matrix in setup_times.iter() {
+                for size in std::iter::once(matrix.len())
+                    .chain(matrix.iter().map(Vec::len))
+                {
+                    if size != self.job_size {
+                        return Err(JobMasterError::RowCount {
+                            block: "setup_times",
+                            expected: self.job_size,
+                            actual: size,
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(calendars) = &self.calendars {
+            if calendars.actor_count() != self.machine_series_size {
+                return Err(JobMasterError::RowCount {
+                    block: "calendars",
+                    expected: self.machine_series_size,
+                    actual: calendars.actor_count(),
+                });
+            }
+        }
+
+        // FJSP では同じ actor を何度訪れてもよい
+        // (選択肢の actor_id は validate_alternatives で検証済み)
+        let actor_sequences: &[Vec<u16>] = if self.is_flexible() {
+            &[]
         } else {
-            // NOTE: actor_id は +1 されている (0が登場しない) ので、-1 しておく
-            row_data.iter_mut().for_each(|val| {
-                *val -= 1;
+            &self.actor_sequences
+        };
+        for (job, actors) in actor_sequences.iter().enumerate() {
+            let mut visited: Vec<bool> = vec![false; self.machine_series_size];
+            for (operation, actor_id) in actors.iter().enumerate() {
+                let (row, column) = locate(Block::Actors, job, operation);
+                let index: usize = *actor_id as usize;
+                if self.machine_series_size <= index {
+                    return Err(JobMasterError::ActorIdOutOfRange {
+                        row,
+                        column,
+                        actor_id: *actor_id,
+                        machine_series_size: self.machine_series_size,
+                    });
+                }
+                if visited[index] {
+                    return Err(JobMasterError::DuplicateActor {
+                        row,
+                        column,
+                        actor_id: *actor_id,
+                    });
+                }
+                visited[index] = true;
+            }
+        }
+
+        // どの順に割り当てても、終了時刻は (最も遅いリリース時刻 +
+        // 全 operation の実行時間の合計) 以下になるため、これが Time に
+        // 収まればデコード中に桁あふれしない
+        let latest_release: Time = self
+            .release_dates
+            .as_ref()
+            .and_then(|release_dates| release_dates.iter().copied().max())
+            .unwrap_or(0);
+        // FJSP の場合は operation ごとに最も長い選択肢で見積もる
+        let horizon: u64 = match &self.alternatives {
+            Some(alternatives) => alternatives
+                .iter()
+                .flatten()
+                .map(|choices| {
+                    choices.iter().map(|choice| choice.time as u64).max()
+                })
+                .map(|time| time.unwrap_or(0))
+                .sum::<u64>(),
+            None => self
+                .exec_times
+                .iter()
+                .flatten()
+                .map(|time| *time as u64)
+                .sum::<u64>(),
+        } + latest_release as u64
+            // 各 operation の前に最も長い段取りが入っても収まること
+            + self.total_operations() as u64
+                * self
+                    .setup_times
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .flatten()
+                    .map(|time| *time as u64)
+                    .max()
+                    .unwrap_or(0)
+            // 最後の停止時間帯が終わるまで待っても収まること
+            + self
+                .calendars
+                .as_ref()
+                .map_or(0, |calendars| calendars.latest_end() as u64);
+        if (Time::MAX as u64) < horizon {
+            return Err(JobMasterError::TimeOverflow {
+                horizon,
+                max: Time::MAX as u64,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// FJSP の選択肢を検証 (行数は検証済み)
+    fn validate_alternatives(
+        &self,
+        alternatives: &[Vec<Vec<Alternative>>],
+        locate: Locate,
+    ) -> Result<(), JobMasterError> {
+        for (job, operations) in alternatives.iter().enumerate() {
+            for (operation, choices) in operations.iter().enumerate() {
+                let (row, column) = locate(Block::Actors, job, operation);
+                if choices.is_empty() {
+                    return Err(JobMasterError::NoAlternative { row, column });
+                }
+                for choice in choices {
+                    if self.machine_series_size <= choice.actor_id as usize {
+                        return Err(JobMasterError::ActorIdOutOfRange {
+                            row,
+                            column,
+                            actor_id: choice.actor_id,
+                            machine_series_size: self.machine_series_size,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// CSVファイルのヘッダーで job 数が書かれている列 (0 始まり)
+const JOB_SIZE_COLUMN: usize = 0;
+/// CSVファイルのヘッダーで machine_series 数が書かれている列 (0 始まり)
+const MACHINE_SERIES_SIZE_COLUMN: usize = 1;
+
+/// CSVファイルのヘッダーの index 列目から job 数や machine_series 数を取得
+fn extract_size(
+    header: &StringRecord,
+    index: usize,
+) -> Result<usize, JobMasterError> {
+    let value: &str = header
+        .get(index)
+        .ok_or(JobMasterError::MissingHeaderValue { column: index + 1 })?;
+    value
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| JobMasterError::InvalidHeader {
+            column: index + 1,
+            value: value.to_string(),
+        })
+}
+
+/// 1行分のフィールドを数値に変換 (空のフィールドは無視する)
+fn parse_row<T: FromStr>(
+    row: &StringRecord,
+    row_no: usize,
+) -> Result<Vec<T>, JobMasterError> {
+    let mut row_data: Vec<T> = Vec::new();
+    for (column, field) in row.iter().enumerate() {
+        let field: &str = field.trim();
+        if field.is_empty() {
+            continue;
+        }
+        let value: T =
+            field
+                .parse::<T>()
+                .map_err(|_| JobMasterError::InvalidNumber {
+                    row: row_no,
+                    column: column + 1,
+                    value: field.to_string(),
+                })?;
+        row_data.push(value);
+    }
+    Ok(row_data)
+}
+
+/// names の各列が header の何列目にあるか (すべて必須で、ほかの列は許さない)
+fn column_positions<const N: usize>(
+    header: &StringRecord,
+    names: [&str; N],
+) -> Result<[usize; N], JobMasterError> {
+    let mut positions: [usize; N] = [0; N];
+    for (position, name) in positions.iter_mut().zip(names) {
+        *position = header
+            .iter()
+            .position(|column| column.trim() == name)
+            .ok_or_else(|| JobMasterError::InvalidHeader {
+                column: header.len() + 1,
+                value: name.to_string(),
+            })?;
+    }
+    for (column, name) in header.iter().enumerate() {
+        if !names.contains(&name.trim()) {
+            return Err(JobMasterError::InvalidHeader {
+                column: column + 1,
+                value: name.to_string(),
             });
-            actor_id_sequences.push(row_data);
         }
     }
+    Ok(positions)
+}
+
+/// row の positions 列目の値をそれぞれ 0 以上の整数に変換
+fn parse_columns<const N: usize>(
+    row: &StringRecord,
+    row_no: usize,
+    positions: [usize; N],
+) -> Result<[u64; N], JobMasterError> {
+    let mut values: [u64; N] = [0; N];
+    for (value, position) in values.iter_mut().zip(positions) {
+        let field: &str = row.get(position).unwrap_or("").trim();
+        *value = field.parse::<u64>().map_err(|_| {
+            JobMasterError::InvalidNumber {
+                row: row_no,
+                column: position + 1,
+                value: field.to_string(),
+            }
+        })?;
+    }
+    Ok(values)
+}
+
+/// parse_columns で読んだ (値, 列位置) を Time に変換 (範囲外はエラー)
+fn parse_times<const N: usize>(
+    row_no: usize,
+    values: [(u64, usize); N],
+) -> Result<[Time; N], JobMasterError> {
+    let mut times: [Time; N] = [0; N];
+    for (time, (value, position)) in times.iter_mut().zip(values) {
+        *time = Time::try_from(value).map_err(|_| {
+            JobMasterError::InvalidNumber {
+                row: row_no,
+                column: position + 1,
+                value: value.to_string(),
+            }
+        })?;
+    }
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::test_fixtures::small_job_master;
+
+    fn records(lines: &[&[&str]]) -> Vec<StringRecord> {
+        lines
+            .iter()
+            .map(|line| StringRecord::from(line.to_vec()))
+            .collect()
+    }
+
+    fn header(
+        job_size: &str,
+        machine_series_size: &str,
+    ) -> Option<StringRecord> {
+        Some(StringRecord::from(vec![job_size, machine_series_size]))
+    }
+
+    #[test]
+    fn test_try_from_records() {
+        let rows =
+            records(&[&["3", "2"], &["2", "5"], &["1", "2"], &["2", "1"]]);
+        let job_master =
+            JobMaster::try_from_records(header("2", "2"), &rows).unwrap();
+        assert_eq!(job_master.exec_times, vec![[3, 2], [2, 5]]);
+        assert_eq!(job_master.actor_sequences, vec![[0, 1], [1, 0]]);
+    }
+
+    #[test]
+    fn test_try_from_records_header_errors() {
+        assert_eq!(
+            JobMaster::try_from_records(None, &[]).unwrap_err(),
+            JobMasterError::MissingHeader
+        );
+        assert_eq!(
+            JobMaster::try_from_records(header("x", "2"), &[]).unwrap_err(),
+            JobMasterError::InvalidHeader {
+                column: 1,
+                value: "x".to_string()
+            }
+        );
+        assert_eq!(
+            JobMaster::try_from_records(
+                Some(StringRecord::from(vec!["2"])),
+                &[]
+            )
+            .unwrap_err(),
+            JobMasterError::MissingHeaderValue { column: 2 }
+        );
+    }
+
+    #[test]
+    fn test_try_from_records_row_errors() {
+        // 行数が足りない
+        let rows = records(&[&["3", "2"], &["1", "2"]]);
+        assert_eq!(
+            JobMaster::try_from_records(header("2", "2"), &rows).unwrap_err(),
+            JobMasterError::RowCount {
+                block: "records",
+                expected: 4,
+                actual: 2
+            }
+        );
+
+        // 数値でない値
+        let rows = records(&[&["3", "a"], &["1", "2"]]);
+        assert_eq!(
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap_err(),
+            JobMasterError::InvalidNumber {
+                row: 2,
+                column: 2,
+                value: "a".to_string()
+            }
+        );
+
+        // operation 数が machine 数と一致しない
+        let rows = records(&[&["3"], &["1", "2"]]);
+        assert_eq!(
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap_err(),
+            JobMasterError::OperationCount {
+                row: 2,
+                expected: 2,
+                actual: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_from_records_actor_errors() {
+        let rows = records(&[&["3", "2"], &["0", "2"]]);
+        assert_eq!(
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap_err(),
+            JobMasterError::ZeroActorId { row: 3, column: 1 }
+        );
+
+        let rows = records(&[&["3", "2"], &["1", "3"]]);
+        assert_eq!(
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap_err(),
+            JobMasterError::ActorIdOutOfRange {
+                row: 3,
+                column: 2,
+                actor_id: 2,
+                machine_series_size: 2
+            }
+        );
+
+        let rows = records(&[&["3", "2"], &["2", "2"]]);
+        assert_eq!(
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap_err(),
+            JobMasterError::DuplicateActor {
+                row: 3,
+                column: 2,
+                actor_id: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_time_overflow() {
+        let job_master = JobMaster::try_new(
+            2,
+            1,
+            vec![vec![70_000], vec![70_000]],
+            vec![vec![0], vec![0]],
+        )
+        .unwrap();
+        assert_eq!(job_master.exec_times, vec![[70_000], [70_000]]);
+
+        let mut job_master = job_master;
+        job_master.exec_times[1][0] = Time::MAX;
+        assert_eq!(
+            job_master.validate().unwrap_err(),
+            JobMasterError::TimeOverflow {
+                horizon: Time::MAX as u64 + 70_000,
+                max: Time::MAX as u64
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_size_out_of_range() {
+        let job_master = JobMaster {
+            job_size: MAX_ID_COUNT + 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            job_master.validate().unwrap_err(),
+            JobMasterError::SizeOutOfRange {
+                name: "job_size",
+                size: MAX_ID_COUNT + 1,
+                max: MAX_ID_COUNT
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_empty_instance() {
+        for (job_size, machine_series_size, name) in [
+            (0, 0, "job_size"),
+            (0, 3, "job_size"),
+            (2, 0, "machine_series_size"),
+        ] {
+            let job_master = JobMaster {
+                job_size,
+                machine_series_size,
+                exec_times: vec![vec![]; job_size],
+                actor_sequences: vec![vec![]; job_size],
+                ..Default::default()
+            };
+            assert_eq!(
+                job_master.validate().unwrap_err(),
+                JobMasterError::EmptyInstance { name }
+            );
+        }
+        assert_eq!(
+            JobMaster::try_from_records(header("0", "3"), &[]).unwrap_err(),
+            JobMasterError::EmptyInstance { name: "job_size" }
+        );
+    }
+
+    #[test]
+    fn test_try_with_job_attributes() {
+        let rows =
+            records(&[&["3", "2"], &["2", "5"], &["1", "2"], &["2", "1"]]);
+        let job_master =
+            JobMaster::try_from_records(header("2", "2"), &rows).unwrap();
+        assert_eq!(job_master.release_date(1), 0);
+        assert_eq!(job_master.weight(1), 1.0);
+
+        let attributes = records(&[&["10", "0", "2.5"], &["20", "3", "1"]]);
+        let job_master = job_master
+            .try_with_job_attributes(
+                Some(StringRecord::from(vec![
+                    "due_date",
+                    "release_date",
+                    "weight",
+                ])),
+                &attributes,
+            )
+            .unwrap();
+        assert_eq!(job_master.due_dates, Some(vec![10, 20]));
+        assert_eq!(job_master.release_dates, Some(vec![0, 3]));
+        assert_eq!(job_master.release_date(1), 3);
+        assert_eq!(job_master.weight(0), 2.5);
+    }
+
+    #[test]
+    fn test_try_with_job_attributes_errors() {
+        let rows = records(&[&["3", "2"], &["1", "2"]]);
+        let job_master =
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap();
+
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_job_attributes(
+                    Some(StringRecord::from(vec!["deadline"])),
+                    &records(&[&["10"]]),
+                )
+                .unwrap_err(),
+            JobMasterError::InvalidHeader {
+                column: 1,
+                value: "deadline".to_string()
+            }
+        );
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_job_attributes(
+                    Some(StringRecord::from(vec!["due_date"])),
+                    &records(&[&["10"], &["20"]]),
+                )
+                .unwrap_err(),
+            JobMasterError::RowCount {
+                block: "job_attributes",
+                expected: 1,
+                actual: 2
+            }
+        );
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_job_attributes(
+                    Some(StringRecord::from(vec!["due_date", "due_date"])),
+                    &records(&[&["10", "20"]]),
+                )
+                .unwrap_err(),
+            JobMasterError::DuplicateColumn {
+                column: 2,
+                name: "due_date".to_string()
+            }
+        );
+        // 時刻は 0 以上の整数のみ (小数を切り捨てたりしない)
+        for value in ["-1", "2.5", "4294967296"] {
+            assert_eq!(
+                job_master
+                    .clone()
+                    .try_with_job_attributes(
+                        Some(StringRecord::from(vec!["weight", "due_date"])),
+                        &records(&[&["1", value]]),
+                    )
+                    .unwrap_err(),
+                JobMasterError::InvalidTime {
+                    row: 2,
+                    column: 2,
+                    value: value.to_string()
+                }
+            );
+        }
+        assert_eq!(
+            job_master
+                .try_with_job_attributes(
+                    Some(StringRecord::from(vec!["weight"])),
+                    &records(&[&["-1"]]),
+                )
+                .unwrap_err(),
+            JobMasterError::InvalidNumber {
+                row: 2,
+                column: 1,
+                value: "-1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_with_setup_times() {
+        let rows =
+            records(&[&["3", "2"], &["2", "5"], &["1", "2"], &["2", "1"]]);
+        let job_master =
+            JobMaster::try_from_records(header("2", "2"), &rows).unwrap();
+        assert_eq!(job_master.setup_time(0, Some(0), 1), 0);
+
+        // 列の順序は自由で、同じ組が重複した場合は後の行を使う
+        let setups = records(&[
+            &["4", "1", "0", "1"],
+            &["5", "0", "1", "0"],
+            &["1", "1", "0", "1"],
+        ]);
+        let job_master = job_master
+            .try_with_setup_times(
+                Some(StringRecord::from(vec![
+                    "setup_time",
+                    "actor_id",
+                    "from_job",
+                    "to_job",
+                ])),
+                &setups,
+            )
+            .unwrap();
+        assert_eq!(job_master.setup_time(1, Some(0), 1), 1);
+        assert_eq!(job_master.setup_time(0, Some(1), 0), 5);
+        assert_eq!(job_master.setup_time(1, Some(1), 0), 0);
+        // 最初の operation の前には段取りが要らない
+        assert_eq!(job_master.setup_time(0, None, 0), 0);
+    }
 
-    let job_master: JobMaster = JobMaster {
-        job_size,
-        machine_series_size,
-        exec_times: exec_times.clone(),
-        actor_sequences: actor_id_sequences.clone(),
-    };
+    #[test]
+    fn test_try_with_setup_times_errors() {
+        let rows = records(&[&["3", "2"], &["1", "2"]]);
+        let job_master =
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap();
+        let columns = || Some(StringRecord::from(SETUP_TIME_COLUMNS.to_vec()));
 
-    job_master
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_setup_times(
+                    Some(StringRecord::from(vec![
+                        "actor_id", "from_job", "to_job"
+                    ])),
+                    &[],
+                )
+                .unwrap_err(),
+            JobMasterError::InvalidHeader {
+                column: 4,
+                value: "setup_time".to_string()
+            }
+        );
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_setup_times(columns(), &records(&[&["0", "0"]]))
+                .unwrap_err(),
+            JobMasterError::InvalidNumber {
+                row: 2,
+                column: 3,
+                value: "".to_string()
+            }
+        );
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_setup_times(
+                    columns(),
+                    &records(&[&["2", "0", "0", "1"]])
+                )
+                .unwrap_err(),
+            JobMasterError::ActorIdOutOfRange {
+                row: 2,
+                column: 1,
+                actor_id: 2,
+                machine_series_size: 2
+            }
+        );
+        assert_eq!(
+            job_master
+                .try_with_setup_times(
+                    columns(),
+                    &records(&[&["0", "0", "1", "1"]])
+                )
+                .unwrap_err(),
+            JobMasterError::JobIdOutOfRange {
+                row: 2,
+                column: 3,
+                job: 1,
+                job_size: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_with_calendar() {
+        let rows =
+            records(&[&["3", "2"], &["2", "5"], &["1", "2"], &["2", "1"]]);
+        let job_master =
+            JobMaster::try_from_records(header("2", "2"), &rows).unwrap();
+        assert_eq!(job_master.earliest_window(1, None, 0, 4, 0, 3), (0, 4, 7));
+        // job と actor の遅い方に空いた時刻から処理する
+        assert_eq!(
+            job_master.earliest_window(1, Some(1), 0, 2, 4, 3),
+            (0, 4, 7)
+        );
+
+        let downtimes = records(&[&["5", "1", "8"], &["2", "1", "3"]]);
+        let job_master = job_master
+            .try_with_calendar(
+                Some(StringRecord::from(vec!["start", "actor_id", "end"])),
+                &downtimes,
+                Interruption::Resumable,
+            )
+            .unwrap();
+        let calendars: &Calendars = job_master.calendars.as_ref().unwrap();
+        assert_eq!(calendars.downtimes(0), &[]);
+        assert_eq!(
+            calendars.downtimes(1),
+            &[Downtime { start: 2, end: 3 }, Downtime { start: 5, end: 8 }]
+        );
+        assert_eq!(job_master.earliest_window(0, None, 0, 4, 0, 3), (0, 4, 7));
+        assert_eq!(job_master.earliest_window(1, None, 0, 4, 0, 3), (0, 4, 10));
+    }
+
+    #[test]
+    fn test_try_with_calendar_errors() {
+        let rows = records(&[&["3", "2"], &["1", "2"]]);
+        let job_master =
+            JobMaster::try_from_records(header("1", "2"), &rows).unwrap();
+        let columns = || Some(StringRecord::from(CALENDAR_COLUMNS.to_vec()));
+
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_calendar(
+                    Some(StringRecord::from(vec![
+                        "actor_id", "start", "end", "reason"
+                    ])),
+                    &[],
+                    Interruption::NonResumable,
+                )
+                .unwrap_err(),
+            JobMasterError::InvalidHeader {
+                column: 4,
+                value: "reason".to_string()
+            }
+        );
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_calendar(
+                    columns(),
+                    &records(&[&["2", "0", "5"]]),
+                    Interruption::NonResumable,
+                )
+                .unwrap_err(),
+            JobMasterError::ActorIdOutOfRange {
+                row: 2,
+                column: 1,
+                actor_id: 2,
+                machine_series_size: 2
+            }
+        );
+        assert_eq!(
+            job_master
+                .clone()
+                .try_with_calendar(
+                    columns(),
+                    &records(&[&["0", "0", "5"], &["1", "4", "4"]]),
+                    Interruption::NonResumable,
+                )
+                .unwrap_err(),
+            JobMasterError::InvalidDowntime {
+                row: 3,
+                start: 4,
+                end: 4
+            }
+        );
+        assert_eq!(
+            job_master
+                .try_with_calendar(
+                    columns(),
+                    &records(&[&["0", "0", "5000000000"]]),
+                    Interruption::NonResumable,
+                )
+                .unwrap_err(),
+            JobMasterError::InvalidNumber {
+                row: 2,
+                column: 3,
+                value: "5000000000".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_new_flow_shop() {
+        let job_master =
+            JobMaster::try_new_flow_shop(2, 3, vec![vec![1, 2, 3]; 2]).unwrap();
+        assert_eq!(job_master.actor_sequences, vec![vec![0, 1, 2]; 2]);
+        assert!(job_master.is_flow_shop());
+
+        let job_master = JobMaster::try_new(
+            2,
+            2,
+            vec![vec![1, 2]; 2],
+            vec![vec![0, 1], vec![1, 0]],
+        )
+        .unwrap();
+        assert!(!job_master.is_flow_shop());
+    }
+
+    #[test]
+    fn test_to_open_shop() {
+        let job_master = small_job_master();
+        let open_shop: JobMaster = job_master.to_open_shop().unwrap();
+        assert!(open_shop.open_shop);
+        assert!(!open_shop.is_flow_shop());
+        // exec_times は actor_id で添字付けする
+        assert_eq!(open_shop.exec_times, vec![vec![3, 2], vec![4, 2]]);
+        assert_eq!(open_shop.actor_sequences, vec![vec![0, 1]; 2]);
+
+        let open_shop: JobMaster =
+            JobMaster::try_new_open_shop(2, 2, vec![vec![3, 2], vec![4, 2]])
+                .unwrap();
+        assert!(open_shop.open_shop);
+        assert_eq!(
+            open_shop.to_open_shop().unwrap().exec_times,
+            open_shop.exec_times
+        );
+    }
+
+    #[test]
+    fn test_try_new_flexible() {
+        let alternative =
+            |actor_id: u16, time: Time| Alternative { actor_id, time };
+        // 同じ actor を2回訪れてもよい
+        let job_master = JobMaster::try_new_flexible(
+            1,
+            2,
+            vec![vec![
+                vec![alternative(0, 5), alternative(1, 3)],
+                vec![alternative(1, 2)],
+                vec![alternative(0, 4), alternative(1, 4)],
+            ]],
+        )
+        .unwrap();
+        assert_eq!(job_master.operation_count(0), 3);
+        assert_eq!(job_master.exec_times, vec![vec![3, 2, 4]]);
+        assert_eq!(job_master.actor_sequences, vec![vec![1, 1, 0]]);
+        assert_eq!(job_master.alternative_count(0, 0), 2);
+        assert_eq!(job_master.alternative(0, 0, 2), alternative(0, 5));
+
+        assert_eq!(
+            JobMaster::try_new_flexible(1, 1, vec![vec![vec![]]]).unwrap_err(),
+            JobMasterError::NoAlternative { row: 1, column: 1 }
+        );
+        assert_eq!(
+            JobMaster::try_new_flexible(
+                1,
+                1,
+                vec![vec![
+                    vec![alternative(0, Time::MAX), alternative(0, 1)];
+                    2
+                ]]
+            )
+            .unwrap_err(),
+            JobMasterError::TimeOverflow {
+                horizon: 2 * Time::MAX as u64,
+                max: Time::MAX as u64
+            }
+        );
+    }
 }
@@ -0,0 +1,245 @@
+use std::error::Error;
+
+use crate::optimization::decoder::open_shop;
+use crate::optimization::decoder::schedule::{Schedule, ScheduledOperation};
+use crate::optimization::decoder::score::{
+    assignment_from_operations, split_flexible, Evaluation, ScoreCalculator,
+};
+use crate::optimization::decoder::validator::{validate, Violation};
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::runner::la40::build_calculator;
+use crate::optimization::types;
+
+/// 解が job_master に対して妥当か (各 job がちょうど operation 数だけ現れるか) 確認する
+/// FJSP の場合は後半 (actor の選択) が operation 数だけあるかも確認する
+/// flow_shop の場合は各 job がちょうど1回現れるか確認する
+/// オープンショップの場合は operation 番号の順列か確認する
+pub(crate) fn check_chromosome(
+    job_master: &JobMaster,
+    chromosome: &types::Chromosome,
+    flow_shop: bool,
+) -> Result<(), String> {
+    if job_master.open_shop {
+        if !is_operation_permutation(job_master, chromosome) {
+            return Err(format!(
+                "the chromosome must be a permutation of the operation ids 0..{}",
+                job_master.total_operations()
+            ));
+        }
+        return Ok(());
+    }
+    let sequence: &[u16] = if job_master.is_flexible() {
+        let (sequence, assignment) = split_flexible(job_master, chromosome);
+        if assignment.len() != job_master.total_operations() {
+            return Err(format!(
+                "the chromosome has {} genes, but expected {} (operation order and actor assignment)",
+                chromosome.len(),
+                2 * job_master.total_operations()
+            ));
+        }
+        sequence
+    } else {
+        chromosome
+    };
+    let mut counts: Vec<usize> = vec![0; job_master.job_size];
+    for job in sequence {
+        match counts.get_mut(*job as usize) {
+            Some(count) => *count += 1,
+            None => {
+                return Err(format!(
+                    "job id {} is out of range for {} jobs",
+                    job, job_master.job_size
+                ))
+            }
+        }
+    }
+    for (job, count) in counts.iter().enumerate() {
+        if flow_shop && *count != 1 {
+            return Err(format!(
+                "job {} appears {} times, but expected once (permutation of jobs)",
+                job, count
+            ));
+        }
+        if !flow_shop && *count != job_master.operation_count(job) {
+            return Err(format!(
+                "job {} appears {} times, but expected {} (operations of the job)",
+                job,
+                count,
+                job_master.operation_count(job)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 解が operation 番号 (0 .. operation 数) の順列か
+fn is_operation_permutation(
+    job_master: &JobMaster,
+    chromosome: &types::Chromosome,
+) -> bool {
+    let mut seen: Vec<bool> = vec![false; job_master.total_operations()];
+    chromosome.len() == seen.len()
+        && chromosome
+            .iter()
+            .all(|gene| match seen.get_mut(*gene as usize) {
+                Some(seen) if !*seen => {
+                    *seen = true;
+                    true
+                }
+                _ => false,
+            })
+}
+
+/// 与えられた解を復号し、makespan と目的関数値を出力して評価結果を返す
+///
+/// operations (解ファイルに記録されていた割り当て) がある場合は、
+/// その割り当てが制約を満たすかも確認し、違反があればエラーを返す
+/// (制約を満たす場合は、復号し直さずにその割り当てを評価する)
+pub fn evaluate(
+    job_master: JobMaster,
+    chromosome: &types::Chromosome,
+    operations: Option<Vec<ScheduledOperation>>,
+    objective_name: &str,
+    decoder_name: &str,
+) -> Result<Evaluation, Box<dyn Error>> {
+    // CSV から復元した FJSP の解は operation 順のみのため、
+    // actor の選択を割り当てから補う
+    let mut chromosome: types::Chromosome = chromosome.clone();
+    if let Some(operations) = &operations {
+        if job_master.is_flexible()
+            && chromosome.len() == job_master.total_operations()
+        {
+            chromosome
+                .extend(assignment_from_operations(&job_master, operations));
+        }
+        // CSV から復元した解は job 番号の列のため、operation 番号の列に直す
+        if job_master.open_shop
+            && !is_operation_permutation(&job_master, &chromosome)
+        {
+            chromosome =
+                open_shop::chromosome_from_operations(&job_master, operations);
+        }
+    }
+
+    let recorded: Option<Schedule> = operations.map(|operations| {
+        // NOTE: 範囲外の actor_id も違反として報告できるよう、
+        // 記録されている最大の actor_id まで枠を用意する
+        let machine_series_size: usize = operations
+            .iter()
+            .map(|op| op.actor_id as usize + 1)
+            .max()
+            .unwrap_or(0)
+            .max(job_master.machine_series_size);
+        Schedule::new(operations, machine_series_size)
+    });
+    let violations: Vec<Violation> = match &recorded {
+        Some(schedule) => validate(&job_master, schedule),
+        None => Vec::new(),
+    };
+    for violation in violations.iter() {
+        println!("[VIOLATION] {}", violation);
+    }
+    if !violations.is_empty() {
+        return Err(format!(
+            "the imported schedule is infeasible ({} violations)",
+            violations.len()
+        )
+        .into());
+    }
+
+    // フローショップで job 数と同じ長さの解は、順列フローショップの解として扱う
+    let flow_shop: bool = job_master.is_flow_shop()
+        && 1 < job_master.machine_series_size
+        && chromosome.len() == job_master.job_size;
+    check_chromosome(&job_master, &chromosome, flow_shop)?;
+    let calculator: ScoreCalculator =
+        build_calculator(job_master, objective_name, decoder_name, flow_shop)?;
+    // 割り当てが記録されている場合は、デコードし直さずにその割り当てを評価する
+    let evaluation: Evaluation = match &recorded {
+        Some(schedule) => {
+            println!("schedule: recorded");
+            calculator.evaluate_schedule(schedule)
+        }
+        None => {
+            println!("schedule: decoded");
+            calculator.evaluate(&chromosome)
+        }
+    };
+    println!("decoder: {}", calculator.decoder().name());
+    println!("objective: {}", calculator.objective.name());
+    println!("makespan: {}", evaluation.makespan);
+    println!("objective_value: {}", evaluation.objective_value);
+    println!("score: {}", evaluation.score);
+    Ok(evaluation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::test_fixtures::small_job_master;
+
+    #[test]
+    fn test_check_chromosome() {
+        let job_master = small_job_master();
+        assert!(check_chromosome(&job_master, &vec![0, 1, 1, 0], false).is_ok());
+        assert!(check_chromosome(&job_master, &vec![0, 1, 1], false).is_err());
+        assert!(
+            check_chromosome(&job_master, &vec![0, 0, 0, 1], false).is_err()
+        );
+        assert!(
+            check_chromosome(&job_master, &vec![0, 1, 2, 0], false).is_err()
+        );
+        assert!(check_chromosome(&job_master, &vec![1, 0], true).is_ok());
+        assert!(check_chromosome(&job_master, &vec![1, 1], true).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_rejects_infeasible_operations() {
+        let chromosome: types::Chromosome = vec![0, 1, 1, 0];
+        let calculator: ScoreCalculator =
+            ScoreCalculator::new(small_job_master());
+        let operations: Vec<ScheduledOperation> =
+            calculator.decode_schedule(&chromosome).operations;
+        assert!(evaluate(
+            small_job_master(),
+            &chromosome,
+            Some(operations.clone()),
+            "makespan",
+            "semi-active"
+        )
+        .is_ok());
+
+        // 記録された割り当てはデコードし直さずに評価する
+        // (最後に終わる operation を遅らせた、実行可能だが詰めていない割り当て)
+        let mut delayed: Vec<ScheduledOperation> = operations.clone();
+        let last: &mut ScheduledOperation =
+            delayed.iter_mut().max_by_key(|op| op.end).unwrap();
+        last.start += 3;
+        last.end += 3;
+        let evaluation: Evaluation = evaluate(
+            small_job_master(),
+            &chromosome,
+            Some(delayed),
+            "makespan",
+            "semi-active",
+        )
+        .unwrap();
+        assert_eq!(
+            evaluation.makespan,
+            calculator.calc_makespan(&chromosome) + 3
+        );
+
+        // 同じ actor 上で重なるように開始時刻をずらす (範囲外の actor も報告する)
+        let mut broken: Vec<ScheduledOperation> = operations;
+        broken[0].start += 1;
+        broken[1].actor_id = 5;
+        assert!(evaluate(
+            small_job_master(),
+            &chromosome,
+            Some(broken),
+            "makespan",
+            "semi-active"
+        )
+        .is_err());
+    }
+}
@@ -1,45 +1,1077 @@
+use std::error::Error;
+
 use rand::seq::SliceRandom;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
 
-use crate::optimization::algorithm::simulated_annealing;
-use crate::optimization::decoder::score::ScoreCalculator;
-use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::algorithm::branch_and_bound::{
+    self, BranchAndBoundConfig,
+};
+use crate::optimization::algorithm::genetic::{self, GeneticConfig};
+use crate::optimization::algorithm::neh;
+use crate::optimization::algorithm::neighborhood::{
+    parse_neighborhood, FlexibleNeighborhood, Neighborhood,
+    DEFAULT_ASSIGNMENT_RATE,
+};
+use crate::optimization::algorithm::parallel::{self, ParallelConfig};
+use crate::optimization::algorithm::simulated_annealing::{
+    self, AnnealingConfig,
+};
+use crate::optimization::algorithm::tabu_search::{self, TabuConfig};
+use crate::optimization::decoder::flow_shop::PermutationDecoder;
+use crate::optimization::decoder::objective::{
+    objective_from_name, Objective, OBJECTIVE_NAMES,
+};
+use crate::optimization::decoder::open_shop::OpenShopDecoder;
+use crate::optimization::decoder::schedule::Schedule;
+use crate::optimization::decoder::score::{
+    decoder_from_name, Decoder, Evaluation, FlexibleDecoder, ScoreCalculator,
+    DECODER_NAMES,
+};
+use crate::optimization::preprocess::runner::{JobMaster, MAX_ID_COUNT};
 use crate::optimization::types;
 
-fn fix_random_seed(seed_num: u8) -> ChaCha8Rng {
-    // 任意の固定シード（例：配列で 32bit 値を4つ）
-    let seed: [u8; 32] = [seed_num; 32]; // 全部 42 にしてみる
-
+fn fix_random_seed(seed: u64) -> ChaCha8Rng {
     // RNG をシード付きで初期化
-    ChaCha8Rng::from_seed(seed)
+    ChaCha8Rng::seed_from_u64(seed)
 }
 
 /// 解を1つランダムに初期化
-fn initialize_chromosome(num_job: u16, num_actor: u16) -> types::Chromosome {
+fn initialize_chromosome(
+    num_job: usize,
+    num_actor: usize,
+    rng: &mut ChaCha8Rng,
+) -> types::Chromosome {
     // 綺麗にソートされた状態の解を生成
-    let length_gene: u16 = num_job * num_actor;
+    // NOTE: 遺伝子数は u16 に収まらないことがあるため usize で数える
+    let length_gene: usize = num_job * num_actor;
     let mut chromosome: types::Chromosome =
-        (0..length_gene).map(|num| num % num_job).collect();
+        (0..length_gene).map(|num| (num % num_job) as u16).collect();
 
     // 乱数生成器を使って解をシャッフル
-    chromosome.shuffle(&mut fix_random_seed(42));
+    chromosome.shuffle(rng);
     println!("[INFO] Initial chromosome: {:?}", chromosome);
 
     chromosome
 }
 
-pub fn run(job_master: JobMaster) {
-    let num_job: u16 = job_master.job_size as u16;
-    let num_actor: u16 = job_master.machine_series_size as u16;
-    let mut chromosome: types::Chromosome =
-        initialize_chromosome(num_job, num_actor);
+/// FJSP の解を1つランダムに初期化
+/// (operation 順をシャッフルし、actor の選択肢をランダムに選ぶ)
+fn initialize_flexible_chromosome(
+    job_master: &JobMaster,
+    rng: &mut ChaCha8Rng,
+) -> types::Chromosome {
+    let mut chromosome: types::Chromosome = (0..job_master.job_size)
+        .flat_map(|job| {
+            std::iter::repeat_n(job as u16, job_master.operation_count(job))
+        })
+        .collect();
+    chromosome.shuffle(rng);
+    for job in 0..job_master.job_size {
+        for operation in 0..job_master.operation_count(job) {
+            let count: usize = job_master.alternative_count(job, operation);
+            chromosome.push(rng.random_range(0..count) as u16);
+        }
+    }
+    println!("[INFO] Initial chromosome: {:?}", chromosome);
+
+    chromosome
+}
+
+/// オープンショップの解を1つランダムに初期化
+/// (operation 番号の順列をシャッフルする)
+fn initialize_open_shop_chromosome(
+    job_master: &JobMaster,
+    rng: &mut ChaCha8Rng,
+) -> types::Chromosome {
+    let mut chromosome: types::Chromosome = (0..job_master.total_operations())
+        .map(|gene| gene as u16)
+        .collect();
+    chromosome.shuffle(rng);
+    println!("[INFO] Initial chromosome: {:?}", chromosome);
+
+    chromosome
+}
+
+/// 選択できる探索アルゴリズムの名前
+pub const ALGORITHM_NAMES: [&str; 4] = ["sa", "ga", "ts", "bb"];
+
+/// 選択できる問題の種類の名前
+/// flow-shop は全 job を同じ順に処理する順列フローショップとして解く
+/// open-shop は job 内の operation の順序を無視したオープンショップとして解く
+/// (オープンショップのインスタンスは job-shop を指定してもオープンショップとして解く)
+pub const PROBLEM_NAMES: [&str; 3] = ["job-shop", "flow-shop", "open-shop"];
+
+/// 最適化の実行に関する設定
+#[derive(Debug, Clone, Serialize)]
+pub struct SolveOptions {
+    /// 問題の種類 (PROBLEM_NAMES のいずれか)
+    pub problem: String,
+    /// 探索アルゴリズム (ALGORITHM_NAMES のいずれか)
+    pub algorithm: String,
+    /// 目的関数 (OBJECTIVE_NAMES のいずれか)
+    pub objective: String,
+    /// 復号器 (DECODER_NAMES のいずれか)
+    pub decoder: String,
+    /// 近傍の指定 (例: "swap", "swap:0.5,insertion:0.5")
+    /// ga の場合は突然変異に使う
+    pub neighborhood: String,
+    pub annealing: AnnealingConfig,
+    pub genetic: GeneticConfig,
+    pub tabu: TabuConfig,
+    pub branch_and_bound: BranchAndBoundConfig,
+    /// sa を複数スレッドで実行する場合の設定
+    pub parallel: ParallelConfig,
+    /// 初期解の生成から探索まで、すべての乱数の元になる seed
+    pub seed: u64,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            problem: "job-shop".to_string(),
+            algorithm: "sa".to_string(),
+            objective: "makespan".to_string(),
+            decoder: "semi-active".to_string(),
+            neighborhood: "swap".to_string(),
+            annealing: AnnealingConfig::default(),
+            genetic: GeneticConfig::default(),
+            tabu: TabuConfig::default(),
+            branch_and_bound: BranchAndBoundConfig::default(),
+            parallel: ParallelConfig::default(),
+            seed: 42,
+        }
+    }
+}
+
+/// 探索で得られた最良解
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub chromosome: types::Chromosome,
+    pub evaluation: Evaluation,
+    /// chromosome をデコードしたスケジュール
+    pub schedule: Schedule,
+    /// 解いた問題の種類 (PROBLEM_NAMES のいずれか)
+    pub problem: &'static str,
+    /// 解いたインスタンス (--problem open-shop で変換した場合は変換後のもの)
+    pub job_master: JobMaster,
+}
+
+/// インスタンス本来の問題の種類
+/// (オープンショップのインスタンスは open-shop、それ以外は job-shop)
+///
+/// 既知の最良値 (BEST_KNOWN_MAKESPANS など) はこの問題に対する値のため、
+/// Solution::problem が異なる場合は比較に使えない
+pub fn native_problem(job_master: &JobMaster) -> &'static str {
+    if job_master.open_shop {
+        "open-shop"
+    } else {
+        "job-shop"
+    }
+}
+
+/// 名前から目的関数を取得し、job_master で評価できるか確認する
+/// flow_shop の場合は job の順列を PermutationDecoder で、
+/// オープンショップの場合は operation 番号の順列を OpenShopDecoder で復号する
+pub(crate) fn build_calculator(
+    job_master: JobMaster,
+    objective_name: &str,
+    decoder_name: &str,
+    flow_shop: bool,
+) -> Result<ScoreCalculator, Box<dyn Error>> {
+    let objective: Box<dyn Objective> = objective_from_name(objective_name)
+        .ok_or_else(|| {
+            format!(
+                "unknown objective {:?} (expected one of {:?})",
+                objective_name, OBJECTIVE_NAMES
+            )
+        })?;
+    objective.check(&job_master)?;
+    let decoder: Box<dyn Decoder> = decoder_from_name(decoder_name)
+        .ok_or_else(|| {
+            format!(
+                "unknown decoder {:?} (expected one of {:?})",
+                decoder_name, DECODER_NAMES
+            )
+        })?;
+
+    // FJSP は semi-active schedule を FlexibleDecoder で求める
+    let decoder: Box<dyn Decoder> = if job_master.is_flexible() {
+        if !matches!(decoder.name(), "semi-active" | "flexible") {
+            return Err(format!(
+                "decoder {:?} is not supported for flexible (FJSP) instances (use flexible)",
+                decoder_name
+            )
+            .into());
+        }
+        Box::new(FlexibleDecoder)
+    } else if job_master.open_shop {
+        // operation 番号の順列から作るスケジュールは semi-active schedule になる
+        if decoder.name() != "semi-active" {
+            return Err(format!(
+                "decoder {:?} is not supported for the open-shop problem (use semi-active)",
+                decoder_name
+            )
+            .into());
+        }
+        Box::new(OpenShopDecoder)
+    } else if flow_shop {
+        // 順列から作るスケジュールは semi-active schedule になる
+        if decoder.name() != "semi-active" {
+            return Err(format!(
+                "decoder {:?} is not supported for the flow-shop problem (use semi-active)",
+                decoder_name
+            )
+            .into());
+        }
+        Box::new(PermutationDecoder)
+    } else {
+        decoder
+    };
+
+    Ok(ScoreCalculator::with_decoder(job_master, decoder)
+        .with_objective(objective))
+}
+
+/// 近傍の指定に N5/N7 (クリティカルブロック近傍) が含まれるか
+/// (これらは actor が固定された job-shop の選言グラフを前提にしている)
+fn uses_critical_block(spec: &str) -> bool {
+    spec.split(',').any(|entry| {
+        let name: &str = entry.split(':').next().unwrap_or("");
+        matches!(name.trim().to_lowercase().as_str(), "n5" | "n7")
+    })
+}
+
+/// 選言グラフ (クリティカルパス) で表せない制約の名前 (なければ None)
+/// (bb・ts と N5/N7 近傍は選言グラフのクリティカルパスで探索するため扱えない)
+fn graph_unsupported_constraint(
+    job_master: &JobMaster,
+) -> Option<&'static str> {
+    if job_master.setup_times.is_some() {
+        return Some("setup times");
+    }
+    if job_master.calendars.is_some() {
+        return Some("machine calendars");
+    }
+    None
+}
+
+/// 選言グラフで探索するアルゴリズム (bb, ts) や近傍 (N5/N7) が、
+/// job_master の制約とデコーダに対応しているか確認する
+fn check_graph_support(
+    job_master: &JobMaster,
+    decoder: &dyn Decoder,
+    algorithm: &str,
+    neighborhood: &str,
+) -> Result<(), String> {
+    // ts と N5/N7 は semi-active スケジュールのクリティカルブロックで移動を選ぶため、
+    // 別のスケジュールを返すデコーダで評価すると、改善しうる移動にならない
+    // (bb は最適な makespan を求めるため、どのデコーダで評価しても最適値になる)
+    if !decoder.flat_compatible() && algorithm == "ts" {
+        return Err(format!(
+            "ts requires the semi-active decoder, but got {:?}",
+            decoder.name()
+        ));
+    }
+    if !decoder.flat_compatible() && uses_critical_block(neighborhood) {
+        return Err(format!(
+            "neighborhood {:?} requires the semi-active decoder, but got {:?}",
+            neighborhood,
+            decoder.name()
+        ));
+    }
+    let Some(constraint) = graph_unsupported_constraint(job_master) else {
+        return Ok(());
+    };
+    if matches!(algorithm, "bb" | "ts") {
+        return Err(format!("{} does not support {}", algorithm, constraint));
+    }
+    if uses_critical_block(neighborhood) {
+        return Err(format!(
+            "neighborhood {:?} does not support {}",
+            neighborhood, constraint
+        ));
+    }
+    Ok(())
+}
+
+/// 近傍の指定を解釈する
+/// FJSP の場合は、指定した近傍で operation 順を変え、
+/// actor の選択も変える FlexibleNeighborhood にする
+fn flexible_neighborhood(
+    spec: &str,
+    job_master: &JobMaster,
+) -> Result<Box<dyn Neighborhood>, String> {
+    let neighborhood: Box<dyn Neighborhood> =
+        parse_neighborhood(spec, job_master)?;
+    if !job_master.is_flexible() {
+        return Ok(neighborhood);
+    }
+    if uses_critical_block(spec) {
+        return Err(format!(
+            "neighborhood {:?} is not supported for flexible (FJSP) instances",
+            spec
+        ));
+    }
+    Ok(Box::new(FlexibleNeighborhood::new(
+        job_master,
+        neighborhood,
+        DEFAULT_ASSIGNMENT_RATE,
+    )))
+}
+
+/// options で指定したアルゴリズム・目的関数で解を最適化し、最良解を返す
+pub fn run(
+    job_master: JobMaster,
+    options: &SolveOptions,
+) -> Result<Solution, Box<dyn Error>> {
+    let (flow_shop, open_shop): (bool, bool) = match options.problem.as_str() {
+        "job-shop" => (false, job_master.open_shop),
+        "flow-shop" => (true, false),
+        "open-shop" => (false, true),
+        name => {
+            return Err(format!(
+                "unknown problem {:?} (expected one of {:?})",
+                name, PROBLEM_NAMES
+            )
+            .into())
+        }
+    };
+    if flow_shop && !job_master.is_flow_shop() {
+        return Err("the instance is not a flow shop (every job must visit the actors in the same order)".into());
+    }
+    let job_master: JobMaster = if open_shop {
+        let job_master: JobMaster = job_master.to_open_shop().ok_or(
+            "flexible (FJSP) instances cannot be solved as an open shop",
+        )?;
+        // 遺伝子は operation 番号のため、u16 で表せる数に限る
+        if MAX_ID_COUNT < job_master.total_operations() {
+            return Err(format!(
+                "the open-shop problem supports at most {} operations, but got {}",
+                MAX_ID_COUNT,
+                job_master.total_operations()
+            )
+            .into());
+        }
+        job_master
+    } else {
+        job_master
+    };
+    if flow_shop || open_shop {
+        let problem: &str = if flow_shop { "flow-shop" } else { "open-shop" };
+        if options.algorithm != "sa" {
+            return Err(format!(
+                "the {} problem is only supported by sa, but got {:?}",
+                problem, options.algorithm
+            )
+            .into());
+        }
+        if uses_critical_block(&options.neighborhood) {
+            return Err(format!(
+                "neighborhood {:?} is not supported for the {} problem",
+                options.neighborhood, problem
+            )
+            .into());
+        }
+    }
+    let neighborhood: Box<dyn Neighborhood> =
+        flexible_neighborhood(&options.neighborhood, &job_master)?;
+
+    let num_job: usize = job_master.job_size;
+    let num_actor: usize = job_master.machine_series_size;
+    let mut rng: ChaCha8Rng = fix_random_seed(options.seed);
+    println!("[INFO] seed: {:?}", options.seed);
+    let chromosome: types::Chromosome = if flow_shop {
+        // 順列フローショップは NEH 法の解から探索を始める
+        let sequence: types::Chromosome = neh::run(&job_master);
+        println!("[INFO] Initial chromosome (NEH): {:?}", sequence);
+        sequence
+    } else if open_shop {
+        initialize_open_shop_chromosome(&job_master, &mut rng)
+    } else if job_master.is_flexible() {
+        initialize_flexible_chromosome(&job_master, &mut rng)
+    } else {
+        initialize_chromosome(num_job, num_actor, &mut rng)
+    };
 
-    let calculator: ScoreCalculator = ScoreCalculator::new(job_master);
-    let (best_solution, best_makespan, best_score, iterated_num) =
-        simulated_annealing::run(&mut chromosome, calculator);
+    if job_master.is_flexible() && options.algorithm != "sa" {
+        return Err(format!(
+            "flexible (FJSP) instances are only supported by sa, but got {:?}",
+            options.algorithm
+        )
+        .into());
+    }
+    if options.algorithm != "sa" && 1 < options.parallel.threads {
+        return Err(format!(
+            "parallel execution is only supported for sa, but got {:?}",
+            options.algorithm
+        )
+        .into());
+    }
+    let calculator: ScoreCalculator = build_calculator(
+        job_master.clone(),
+        &options.objective,
+        &options.decoder,
+        flow_shop,
+    )?;
+    if options.algorithm == "bb" && !calculator.objective.is_makespan() {
+        return Err(format!(
+            "bb only supports the makespan objective, but got {:?}",
+            options.objective
+        )
+        .into());
+    }
+    check_graph_support(
+        &job_master,
+        calculator.decoder(),
+        &options.algorithm,
+        &options.neighborhood,
+    )?;
+    let (best_solution, best_makespan, best_score, iterated_num) = match options
+        .algorithm
+        .as_str()
+    {
+        "sa" if 1 < options.parallel.threads => parallel::run(
+            &chromosome,
+            &calculator,
+            neighborhood.as_ref(),
+            &options.annealing,
+            &options.parallel,
+            options.seed,
+        ),
+        "sa" => simulated_annealing::run(
+            &chromosome,
+            calculator,
+            neighborhood.as_ref(),
+            &options.annealing,
+            &mut rng,
+        ),
+        "ga" => genetic::run(
+            &chromosome,
+            calculator,
+            neighborhood.as_ref(),
+            &options.genetic,
+            &mut rng,
+        ),
+        "ts" => {
+            tabu_search::run(&chromosome, calculator, &options.tabu, &mut rng)
+        }
+        "bb" => branch_and_bound::run(
+            &chromosome,
+            calculator,
+            &options.branch_and_bound,
+        ),
+        name => {
+            return Err(format!(
+                "unknown algorithm {:?} (expected one of {:?})",
+                name, ALGORITHM_NAMES
+            )
+            .into())
+        }
+    };
     println!(
         "[INFO] best_score: {:?}, best_makespan: {:?}, iterated_num: {:?}, best_solution: {:?}",
         best_score, best_makespan, iterated_num, best_solution
     );
+
+    // 探索で calculator を使い切るため、評価用に作り直す
+    let calculator: ScoreCalculator = build_calculator(
+        job_master.clone(),
+        &options.objective,
+        &options.decoder,
+        flow_shop,
+    )?;
+    Ok(Solution {
+        evaluation: calculator.evaluate(&best_solution),
+        schedule: calculator.decode_schedule(&best_solution),
+        chromosome: best_solution,
+        problem: if flow_shop {
+            "flow-shop"
+        } else {
+            native_problem(&job_master)
+        },
+        job_master,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::algorithm::neighborhood::NEIGHBORHOOD_NAMES;
+    use crate::optimization::decoder::schedule::ScheduledOperation;
+    use crate::optimization::decoder::score::SemiActiveDecoder;
+    use crate::optimization::decoder::validator::validate;
+    use crate::optimization::preprocess::calendar::{
+        Calendars, Downtime, Interruption,
+    };
+    use crate::optimization::preprocess::instance_format::parse_or_library;
+    use crate::optimization::preprocess::lower_bound::LowerBounds;
+    use crate::optimization::runner::evaluation::{check_chromosome, evaluate};
+    use crate::optimization::test_fixtures::{
+        flexible_job_master, flow_shop_job_master, small_job_master, FT06,
+    };
+    use crate::optimization::types::Time;
+
+    #[test]
+    fn test_build_calculator_rejects_unknown_names() {
+        assert!(build_calculator(
+            small_job_master(),
+            "makespan",
+            "active",
+            false
+        )
+        .is_ok());
+        assert!(build_calculator(
+            small_job_master(),
+            "lateness",
+            "active",
+            false
+        )
+        .is_err());
+        assert!(build_calculator(
+            small_job_master(),
+            "makespan",
+            "greedy",
+            false
+        )
+        .is_err());
+        // 納期がないと納期系の目的関数は使えない
+        assert!(build_calculator(
+            small_job_master(),
+            "total-weighted-tardiness",
+            "active",
+            false
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_branch_and_bound_rejects_other_objectives() {
+        let options: SolveOptions = SolveOptions {
+            algorithm: "bb".to_string(),
+            objective: "total-flow-time".to_string(),
+            ..SolveOptions::default()
+        };
+        assert!(run(small_job_master(), &options).is_err());
+    }
+
+    #[test]
+    fn test_check_graph_support() {
+        let mut with_setup: JobMaster = small_job_master();
+        with_setup.setup_times = Some(vec![vec![vec![1; 2]; 2]; 2]);
+        let mut with_calendars: JobMaster = small_job_master();
+        with_calendars.calendars = Some(Calendars::new(
+            vec![vec![Downtime { start: 2, end: 4 }], vec![]],
+            Interruption::Resumable,
+        ));
+        assert_eq!(graph_unsupported_constraint(&small_job_master()), None);
+        assert_eq!(
+            graph_unsupported_constraint(&with_setup),
+            Some("setup times")
+        );
+        assert_eq!(
+            graph_unsupported_constraint(&with_calendars),
+            Some("machine calendars")
+        );
+
+        for (job_master, constraint) in [
+            (&with_setup, "setup times"),
+            (&with_calendars, "machine calendars"),
+        ] {
+            for algorithm in ALGORITHM_NAMES {
+                for neighborhood in NEIGHBORHOOD_NAMES {
+                    assert!(check_graph_support(
+                        &small_job_master(),
+                        &SemiActiveDecoder,
+                        algorithm,
+                        neighborhood
+                    )
+                    .is_ok());
+
+                    let result: Result<(), String> = check_graph_support(
+                        job_master,
+                        &SemiActiveDecoder,
+                        algorithm,
+                        neighborhood,
+                    );
+                    if matches!(algorithm, "bb" | "ts") {
+                        assert_eq!(
+                            result,
+                            Err(format!(
+                                "{} does not support {}",
+                                algorithm, constraint
+                            ))
+                        );
+                    } else if matches!(neighborhood, "n5" | "n7") {
+                        assert_eq!(
+                            result,
+                            Err(format!(
+                                "neighborhood {:?} does not support {}",
+                                neighborhood, constraint
+                            ))
+                        );
+                    } else {
+                        assert_eq!(
+                            result,
+                            Ok(()),
+                            "{} {}",
+                            algorithm,
+                            neighborhood
+                        );
+                    }
+                }
+            }
+            // 混合近傍の一部でも N5/N7 を含めば使えない
+            assert!(check_graph_support(
+                job_master,
+                &SemiActiveDecoder,
+                "sa",
+                "swap:1,n7:1"
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_check_graph_support_rejects_other_decoders() {
+        for decoder_name in DECODER_NAMES {
+            let decoder: Box<dyn Decoder> =
+                decoder_from_name(decoder_name).unwrap();
+            for algorithm in ALGORITHM_NAMES {
+                for neighborhood in NEIGHBORHOOD_NAMES {
+                    let result: Result<(), String> = check_graph_support(
+                        &small_job_master(),
+                        decoder.as_ref(),
+                        algorithm,
+                        neighborhood,
+                    );
+                    if decoder_name == "semi-active" {
+                        assert_eq!(result, Ok(()));
+                    } else if algorithm == "ts" {
+                        assert_eq!(
+                            result,
+                            Err(format!(
+                                "ts requires the semi-active decoder, but got {:?}",
+                                decoder_name
+                            ))
+                        );
+                    } else if matches!(neighborhood, "n5" | "n7") {
+                        assert_eq!(
+                            result,
+                            Err(format!(
+                                "neighborhood {:?} requires the semi-active decoder, but got {:?}",
+                                neighborhood, decoder_name
+                            ))
+                        );
+                    } else {
+                        assert_eq!(
+                            result,
+                            Ok(()),
+                            "{} {} {}",
+                            decoder_name,
+                            algorithm,
+                            neighborhood
+                        );
+                    }
+                }
+            }
+        }
+
+        // run からも、選んだデコーダで確認する
+        let options: SolveOptions = SolveOptions {
+            neighborhood: "n5".to_string(),
+            decoder: "active".to_string(),
+            ..SolveOptions::default()
+        };
+        assert!(run(small_job_master(), &options).is_err());
+        let options: SolveOptions = SolveOptions {
+            algorithm: "ts".to_string(),
+            decoder: "non-delay".to_string(),
+            ..SolveOptions::default()
+        };
+        assert!(run(small_job_master(), &options).is_err());
+    }
+
+    #[test]
+    fn test_run_with_setup_times() {
+        let mut job_master: JobMaster = parse_or_library(FT06).unwrap();
+        let job_size: usize = job_master.job_size;
+        job_master.setup_times = Some(
+            (0..job_master.machine_series_size)
+                .map(|actor| {
+                    (0..job_size)
+                        .map(|from| {
+                            (0..job_size)
+                                .map(|to| ((actor + from + 2 * to) % 4) as Time)
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect(),
+        );
+        let options: SolveOptions = SolveOptions {
+            annealing: AnnealingConfig {
+                steps: 2000,
+                ..AnnealingConfig::default()
+            },
+            ..SolveOptions::default()
+        };
+        let solution: Solution = run(job_master.clone(), &options).unwrap();
+        assert_eq!(validate(&job_master, &solution.schedule), vec![]);
+        assert!(solution.schedule.operations.iter().any(|op| 0 < op.setup));
+
+        // 分枝限定法は段取り時間に対応しない
+        let options: SolveOptions = SolveOptions {
+            algorithm: "bb".to_string(),
+            ..SolveOptions::default()
+        };
+        assert!(run(job_master.clone(), &options).is_err());
+
+        // タブー探索・N5/N7 近傍は段取りを含まない選言グラフで探索するため使えない
+        for options in [
+            SolveOptions {
+                algorithm: "ts".to_string(),
+                ..SolveOptions::default()
+            },
+            SolveOptions {
+                neighborhood: "swap:1,n5:1".to_string(),
+                ..SolveOptions::default()
+            },
+            SolveOptions {
+                algorithm: "ga".to_string(),
+                neighborhood: "n7".to_string(),
+                ..SolveOptions::default()
+            },
+        ] {
+            assert!(run(job_master.clone(), &options).is_err());
+        }
+    }
+
+    #[test]
+    fn test_run_with_calendars() {
+        let mut job_master: JobMaster = parse_or_library(FT06).unwrap();
+        // 各 actor が 20 ごとに 5 だけ停止する
+        let downtimes: Vec<Vec<Downtime>> = (0..job_master.machine_series_size)
+            .map(|actor| {
+                (0..5)
+                    .map(|cycle| {
+                        let start: Time = (20 * cycle + 3 * actor) as Time;
+                        Downtime {
+                            start,
+                            end: start + 5,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        for (interruption, decoder) in [
+            (Interruption::NonResumable, "semi-active"),
+            (Interruption::Resumable, "active"),
+        ] {
+            job_master.calendars =
+                Some(Calendars::new(downtimes.clone(), interruption));
+            let options: SolveOptions = SolveOptions {
+                decoder: decoder.to_string(),
+                annealing: AnnealingConfig {
+                    steps: 2000,
+                    ..AnnealingConfig::default()
+                },
+                ..SolveOptions::default()
+            };
+            let solution: Solution = run(job_master.clone(), &options).unwrap();
+            assert_eq!(validate(&job_master, &solution.schedule), vec![]);
+        }
+
+        // 分枝限定法・タブー探索・N5/N7 近傍は停止時間帯を含まない
+        // 選言グラフで探索するため使えない
+        for options in [
+            SolveOptions {
+                algorithm: "bb".to_string(),
+                ..SolveOptions::default()
+            },
+            SolveOptions {
+                algorithm: "ts".to_string(),
+                ..SolveOptions::default()
+            },
+            SolveOptions {
+                neighborhood: "n5".to_string(),
+                ..SolveOptions::default()
+            },
+            SolveOptions {
+                neighborhood: "swap:1,n7:1".to_string(),
+                ..SolveOptions::default()
+            },
+        ] {
+            assert!(run(job_master.clone(), &options).is_err());
+        }
+    }
+
+    #[test]
+    fn test_simulated_annealing_reaches_proven_optimum() {
+        let job_master: JobMaster = parse_or_library(FT06).unwrap();
+        let options: SolveOptions = SolveOptions {
+            algorithm: "bb".to_string(),
+            ..SolveOptions::default()
+        };
+        let optimum: Solution = run(job_master.clone(), &options).unwrap();
+        assert_eq!(optimum.evaluation.makespan, 55);
+
+        // 分枝限定法で証明した最適値に sa が N5 近傍で到達すること
+        // (seed を変えても 5,000 step 以内に到達する)
+        let options: SolveOptions = SolveOptions {
+            neighborhood: "n5".to_string(),
+            annealing: AnnealingConfig {
+                steps: 5_000,
+                ..AnnealingConfig::default()
+            },
+            ..SolveOptions::default()
+        };
+        let solution: Solution = run(job_master, &options).unwrap();
+        assert_eq!(solution.evaluation.makespan, optimum.evaluation.makespan);
+    }
+
+    #[test]
+    fn test_run_flexible_instance() {
+        let options: SolveOptions = SolveOptions {
+            annealing: AnnealingConfig {
+                steps: 5_000,
+                ..AnnealingConfig::default()
+            },
+            ..SolveOptions::default()
+        };
+        let solution: Solution = run(flexible_job_master(), &options).unwrap();
+        // job 0 の最初の operation を actor 1 に回すと 10 になる
+        assert_eq!(solution.evaluation.makespan, 10);
+        assert_eq!(solution.chromosome.len(), 10);
+        assert!(check_chromosome(
+            &flexible_job_master(),
+            &solution.chromosome,
+            false
+        )
+        .is_ok());
+        assert_eq!(
+            validate(&flexible_job_master(), &solution.schedule),
+            vec![]
+        );
+
+        // CSV から復元した解 (operation 順のみ) も割り当てどおりに評価する
+        let operations: Vec<ScheduledOperation> =
+            solution.schedule.operations.clone();
+        let evaluation: Evaluation = evaluate(
+            flexible_job_master(),
+            &solution.chromosome[..5].to_vec(),
+            Some(operations),
+            "makespan",
+            "semi-active",
+        )
+        .unwrap();
+        assert_eq!(evaluation.makespan, 10);
+
+        // 並列の焼きなましでも actor の選択を壊さない
+        let options: SolveOptions = SolveOptions {
+            annealing: AnnealingConfig {
+                steps: 2_000,
+                ..AnnealingConfig::default()
+            },
+            parallel: ParallelConfig {
+                threads: 2,
+                ..ParallelConfig::default()
+            },
+            ..SolveOptions::default()
+        };
+        let solution: Solution = run(flexible_job_master(), &options).unwrap();
+        assert!(check_chromosome(
+            &flexible_job_master(),
+            &solution.chromosome,
+            false
+        )
+        .is_ok());
+        assert_eq!(
+            validate(&flexible_job_master(), &solution.schedule),
+            vec![]
+        );
+
+        for options in [
+            SolveOptions {
+                algorithm: "ts".to_string(),
+                ..SolveOptions::default()
+            },
+            SolveOptions {
+                neighborhood: "swap:1,n5:1".to_string(),
+                ..SolveOptions::default()
+            },
+            SolveOptions {
+                decoder: "active".to_string(),
+                ..SolveOptions::default()
+            },
+        ] {
+            assert!(run(flexible_job_master(), &options).is_err());
+        }
+    }
+
+    #[test]
+    fn test_run_flow_shop() {
+        let job_master: JobMaster = flow_shop_job_master();
+        let options: SolveOptions = SolveOptions {
+            problem: "flow-shop".to_string(),
+            neighborhood: "insertion".to_string(),
+            annealing: AnnealingConfig {
+                steps: 1_000,
+                ..AnnealingConfig::default()
+            },
+            ..SolveOptions::default()
+        };
+        let solution: Solution = run(job_master.clone(), &options).unwrap();
+        // 総当たりの最適値は 43
+        assert_eq!(solution.evaluation.makespan, 43);
+        assert_eq!(solution.chromosome.len(), 4);
+        assert_eq!(validate(&job_master, &solution.schedule), vec![]);
+        // 順列に限定して解いたため、元の問題 (job-shop) とは区別する
+        assert_eq!(solution.problem, "flow-shop");
+        assert_eq!(native_problem(&job_master), "job-shop");
+
+        // job 数と同じ長さの解は順列として評価する
+        let evaluation: Evaluation = evaluate(
+            job_master.clone(),
+            &solution.chromosome,
+            None,
+            "makespan",
+            "semi-active",
+        )
+        .unwrap();
+        assert_eq!(evaluation.makespan, 43);
+
+        for options in [
+            SolveOptions {
+                algorithm: "ga".to_string(),
+                ..options.clone()
+            },
+            SolveOptions {
+                neighborhood: "n5".to_string(),
+                ..options.clone()
+            },
+            SolveOptions {
+                decoder: "active".to_string(),
+                ..options.clone()
+            },
+            SolveOptions {
+                problem: "hybrid-flow-shop".to_string(),
+                ..options.clone()
+            },
+        ] {
+            assert!(run(job_master.clone(), &options).is_err());
+        }
+        // actor の順が job ごとに異なるインスタンスは解けない
+        assert!(run(small_job_master(), &options).is_err());
+    }
+
+    #[test]
+    fn test_run_open_shop() {
+        let job_master: JobMaster =
+            JobMaster::try_new_open_shop(2, 2, vec![vec![3, 2], vec![4, 2]])
+                .unwrap();
+        let options: SolveOptions = SolveOptions {
+            annealing: AnnealingConfig {
+                steps: 2_000,
+                ..AnnealingConfig::default()
+            },
+            ..SolveOptions::default()
+        };
+        // オープンショップのインスタンスは job-shop のままでもオープンショップとして解く
+        let solution: Solution = run(job_master.clone(), &options).unwrap();
+        // actor 0 の負荷 7 が下界で、到達できる
+        assert_eq!(solution.evaluation.makespan, 7);
+        assert_eq!(solution.chromosome.len(), 4);
+        assert_eq!(solution.problem, native_problem(&job_master));
+        assert_eq!(validate(&job_master, &solution.schedule), vec![]);
+
+        // CSV から復元した解 (job 番号の列) も割り当てどおりに評価する
+        let operations: Vec<ScheduledOperation> =
+            solution.schedule.operations.clone();
+        let sequence: types::Chromosome =
+            operations.iter().map(|op| op.job).collect();
+        let evaluation: Evaluation = evaluate(
+            job_master.clone(),
+            &sequence,
+            Some(operations),
+            "makespan",
+            "semi-active",
+        )
+        .unwrap();
+        assert_eq!(evaluation.makespan, 7);
+
+        // 順序のあるインスタンスは順序を無視して解く
+        let open_shop: SolveOptions = SolveOptions {
+            problem: "open-shop".to_string(),
+            ..options.clone()
+        };
+        let solution: Solution = run(small_job_master(), &open_shop).unwrap();
+        assert_eq!(solution.evaluation.makespan, 7);
+
+        for options in [
+            SolveOptions {
+                algorithm: "ts".to_string(),
+                ..options.clone()
+            },
+            SolveOptions {
+                neighborhood: "n7".to_string(),
+                ..options.clone()
+            },
+            SolveOptions {
+                decoder: "non-delay".to_string(),
+                ..options.clone()
+            },
+            SolveOptions {
+                problem: "flow-shop".to_string(),
+                ..options.clone()
+            },
+        ] {
+            assert!(run(job_master.clone(), &options).is_err());
+        }
+        assert!(run(flexible_job_master(), &open_shop).is_err());
+    }
+
+    #[test]
+    fn test_run_open_shop_lower_bound() {
+        let job_master: JobMaster = parse_or_library(FT06).unwrap();
+        let options: SolveOptions = SolveOptions {
+            problem: "open-shop".to_string(),
+            annealing: AnnealingConfig {
+                steps: 2_000,
+                ..AnnealingConfig::default()
+            },
+            ..SolveOptions::default()
+        };
+        let solution: Solution = run(job_master.clone(), &options).unwrap();
+        assert_eq!(solution.problem, "open-shop");
+        assert_ne!(solution.problem, native_problem(&job_master));
+        assert!(solution.job_master.open_shop);
+
+        // オープンショップの下界は job と actor の負荷の最大値を超えない
+        let max_job_load: Time = job_master
+            .exec_times
+            .iter()
+            .map(|times| times.iter().sum())
+            .max()
+            .unwrap();
+        let mut machine_loads: Vec<Time> =
+            vec![0; job_master.machine_series_size];
+        for (times, actors) in job_master
+            .exec_times
+            .iter()
+            .zip(job_master.actor_sequences.iter())
+        {
+            for (time, actor) in times.iter().zip(actors.iter()) {
+                machine_loads[*actor as usize] += time;
+            }
+        }
+        let load_bound: Time =
+            max_job_load.max(*machine_loads.iter().max().unwrap());
+        let bounds: LowerBounds = LowerBounds::new(&solution.job_master);
+        assert!(bounds.best() <= load_bound);
+        assert!(bounds.best() <= solution.evaluation.makespan);
+        // 元の job-shop の下界は、オープンショップの解には当てはまらない
+        assert!(load_bound < LowerBounds::new(&job_master).best());
+    }
 }
@@ -1 +1,6 @@
+/// 与えられた解 (解ファイル・CSV から読み込んだ解) の評価
+pub mod evaluation;
+/// 指定されたアルゴリズムでの求解
 pub mod la40;
+/// 下界・既知の最良値との差やインスタンスの統計量の出力
+pub mod report;
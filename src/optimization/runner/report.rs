@@ -0,0 +1,95 @@
+use crate::optimization::preprocess::lower_bound::{self, LowerBounds};
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::Time;
+
+/// makespan の下界と、下界・既知の最良値 (best_known) に対する差を出力する
+pub fn report_gap(
+    job_master: &JobMaster,
+    makespan: Time,
+    best_known: Option<Time>,
+) {
+    let bounds: LowerBounds = LowerBounds::new(job_master);
+    println!(
+        "[INFO] lower_bound: {} (job_length: {}, machine_load: {}, one_machine: {}), gap_to_lower_bound: {:.2}%",
+        bounds.best(),
+        bounds.job_length,
+        bounds.machine_load,
+        bounds.one_machine,
+        lower_bound::gap(makespan, bounds.best())
+    );
+    if let Some(best_known) = best_known {
+        println!(
+            "[INFO] best_known: {}, gap_to_best_known: {:.2}%",
+            best_known,
+            lower_bound::gap(makespan, best_known)
+        );
+    }
+}
+
+/// インスタンスの規模と簡単な統計量を出力する
+pub fn info(job_master: &JobMaster, best_known: Option<Time>) {
+    let total_time: u64 = job_master
+        .exec_times
+        .iter()
+        .flatten()
+        .map(|time| *time as u64)
+        .sum();
+    let max_job_time: u64 = job_master
+        .exec_times
+        .iter()
+        .map(|times| times.iter().map(|time| *time as u64).sum())
+        .max()
+        .unwrap_or(0);
+    let mut machine_loads: Vec<u64> = vec![0; job_master.machine_series_size];
+    for (times, actors) in job_master
+        .exec_times
+        .iter()
+        .zip(job_master.actor_sequences.iter())
+    {
+        for (time, actor) in times.iter().zip(actors.iter()) {
+            machine_loads[*actor as usize] += *time as u64;
+        }
+    }
+    let max_machine_load: u64 =
+        machine_loads.iter().copied().max().unwrap_or(0);
+
+    println!("jobs: {}", job_master.job_size);
+    println!("machines: {}", job_master.machine_series_size);
+    println!("operations: {}", job_master.total_operations());
+    println!("flexible: {}", job_master.is_flexible());
+    println!("open_shop: {}", job_master.open_shop);
+    println!("total_processing_time: {}", total_time);
+    println!("max_job_processing_time: {}", max_job_time);
+    // FJSP では actor が決まっていないため、負荷は求めない
+    // (実行時間は各 operation の最短の選択肢で数える)
+    if !job_master.is_flexible() {
+        println!("machine_loads: {:?}", machine_loads);
+        println!(
+            "trivial_lower_bound: {}",
+            max_job_time.max(max_machine_load)
+        );
+    }
+    let bounds: LowerBounds = LowerBounds::new(job_master);
+    println!("lower_bound_job_length: {}", bounds.job_length);
+    println!("lower_bound_machine_load: {}", bounds.machine_load);
+    println!("lower_bound_one_machine: {}", bounds.one_machine);
+    println!("lower_bound: {}", bounds.best());
+    match best_known {
+        Some(best_known) => println!("best_known: {}", best_known),
+        None => println!("best_known: unknown"),
+    }
+    println!("release_dates: {}", job_master.release_dates.is_some());
+    println!("due_dates: {}", job_master.due_dates.is_some());
+    println!("weights: {}", job_master.weights.is_some());
+    println!("setup_times: {}", job_master.setup_times.is_some());
+    match &job_master.calendars {
+        Some(calendars) => {
+            let downtimes: Vec<usize> = (0..calendars.actor_count())
+                .map(|actor_id| calendars.downtimes(actor_id).len())
+                .collect();
+            println!("downtimes: {:?}", downtimes);
+            println!("interruption: {}", calendars.interruption.name());
+        }
+        None => println!("downtimes: none"),
+    }
+}
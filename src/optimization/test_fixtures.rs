@@ -0,0 +1,140 @@
+use crate::optimization::preprocess::runner::{Alternative, JobMaster};
+use crate::optimization::types::Time;
+
+/// Fisher and Thompson の 6x6 インスタンス (OR-Library 形式、最適値 55)
+pub(crate) const FT06: &str = "\
+6 6
+2 1 0 3 1 6 3 7 5 3 4 6
+1 8 2 5 4 10 5 10 0 10 3 4
+2 5 3 4 5 8 0 9 1 1 4 7
+1 5 0 5 2 5 3 3 4 8 5 9
+2 9 1 3 4 5 5 4 0 3 3 1
+1 3 3 3 5 9 0 10 4 4 2 1
+";
+
+/// 3 job x 2 actor のインスタンス (最適値 12)
+///
+/// - job 0: actor 0 (3) → actor 1 (2)
+/// - job 1: actor 1 (2) → actor 0 (5)
+/// - job 2: actor 0 (4) → actor 1 (1)
+pub(crate) fn sample_job_master() -> JobMaster {
+    JobMaster::try_new(
+        3,
+        2,
+        vec![vec![3, 2], vec![2, 5], vec![4, 1]],
+        vec![vec![0, 1], vec![1, 0], vec![0, 1]],
+    )
+    .unwrap()
+}
+
+/// 2 job x 2 actor のインスタンス
+///
+/// - job 0: actor 0 (3) → actor 1 (2)
+/// - job 1: actor 1 (2) → actor 0 (4)
+pub(crate) fn small_job_master() -> JobMaster {
+    JobMaster::try_new(
+        2,
+        2,
+        vec![vec![3, 2], vec![2, 4]],
+        vec![vec![0, 1], vec![1, 0]],
+    )
+    .unwrap()
+}
+
+/// 3 job x 3 actor のインスタンス
+pub(crate) fn square_job_master() -> JobMaster {
+    JobMaster::try_new(
+        3,
+        3,
+        vec![vec![3, 2, 2], vec![2, 5, 4], vec![4, 1, 3]],
+        vec![vec![0, 1, 2], vec![1, 0, 2], vec![2, 1, 0]],
+    )
+    .unwrap()
+}
+
+/// 4 job x 3 actor のインスタンス
+pub(crate) fn medium_job_master() -> JobMaster {
+    JobMaster::try_new(
+        4,
+        3,
+        vec![vec![3, 2, 4], vec![2, 5, 3], vec![4, 1, 2], vec![3, 3, 1]],
+        vec![vec![0, 1, 2], vec![1, 0, 2], vec![2, 1, 0], vec![0, 2, 1]],
+    )
+    .unwrap()
+}
+
+/// 4 job x 3 actor の順列フローショップ (総当たりの最適値 43)
+pub(crate) fn flow_shop_job_master() -> JobMaster {
+    JobMaster::try_new_flow_shop(
+        4,
+        3,
+        vec![vec![5, 9, 8], vec![9, 3, 10], vec![9, 4, 5], vec![4, 8, 8]],
+    )
+    .unwrap()
+}
+
+/// 2 job x 2 actor の FJSP
+///
+/// - job 0: (actor 0 (3) | actor 1 (5)) → actor 1 (2)
+/// - job 1: actor 0 (4) → (actor 0 (2) | actor 1 (1)) → actor 1 (3)
+pub(crate) fn flexible_job_master() -> JobMaster {
+    let alternative =
+        |actor_id: u16, time: Time| Alternative { actor_id, time };
+    JobMaster::try_new_flexible(
+        2,
+        2,
+        vec![
+            vec![
+                vec![alternative(0, 3), alternative(1, 5)],
+                vec![alternative(1, 2)],
+            ],
+            vec![
+                vec![alternative(0, 4)],
+                vec![alternative(0, 2), alternative(1, 1)],
+                vec![alternative(1, 3)],
+            ],
+        ],
+    )
+    .unwrap()
+}
+
+/// 2 job x 2 actor のオープンショップ (exec_times[job][actor_id])
+pub(crate) fn open_shop_job_master() -> JobMaster {
+    JobMaster::try_new_open_shop(2, 2, vec![vec![3, 2], vec![2, 4]]).unwrap()
+}
+
+/// Taillard (1993) の ta001 (20 job x 5 machine、machine ごとの実行時間)
+const TA001: [[Time; 20]; 5] = [
+    [
+        54, 83, 15, 71, 77, 36, 53, 38, 27, 87, 76, 91, 14, 29, 12, 77, 32, 87,
+        68, 94,
+    ],
+    [
+        79, 3, 11, 99, 56, 70, 99, 60, 5, 56, 3, 61, 73, 75, 47, 14, 21, 86, 5,
+        77,
+    ],
+    [
+        16, 89, 49, 15, 89, 45, 60, 23, 57, 64, 7, 1, 63, 41, 63, 47, 26, 75,
+        77, 40,
+    ],
+    [
+        66, 58, 31, 68, 78, 91, 13, 59, 49, 85, 85, 9, 39, 41, 56, 40, 54, 77,
+        51, 31,
+    ],
+    [
+        58, 56, 20, 85, 53, 35, 53, 41, 69, 13, 86, 72, 8, 49, 47, 87, 58, 18,
+        68, 28,
+    ],
+];
+
+/// ta001 の順列フローショップ (最適値 1278)
+pub(crate) fn ta001_job_master() -> JobMaster {
+    JobMaster::try_new_flow_shop(
+        20,
+        5,
+        (0..20)
+            .map(|job| TA001.iter().map(|times| times[job]).collect())
+            .collect(),
+    )
+    .unwrap()
+}
@@ -1 +1,7 @@
+/// 遺伝子 (job 番号) の列
+/// job 番号は u16 で表すため、job 数は 65536 以下 (JobMaster の検証で確認する)
 pub type Chromosome = Vec<u16>;
+
+/// 実行時間・開始時刻・終了時刻を表す型
+/// 全 operation の実行時間の合計が収まること (JobMaster の検証で確認する)
+pub type Time = u32;
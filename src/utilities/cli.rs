@@ -0,0 +1,784 @@
+use clap::{App, AppSettings, Arg, ArgMatches};
+use std::ffi::OsString;
+
+use crate::optimization::algorithm::branch_and_bound::BranchAndBoundConfig;
+use crate::optimization::algorithm::genetic::{
+    Crossover, GeneticConfig, Selection, CROSSOVER_NAMES,
+};
+use crate::optimization::algorithm::neighborhood::CriticalBlockMoves;
+use crate::optimization::algorithm::parallel::{ParallelConfig, ParallelMode};
+use crate::optimization::algorithm::simulated_annealing::{
+    AnnealingConfig, CoolingSchedule,
+};
+use crate::optimization::algorithm::tabu_search::{parse_tenure, TabuConfig};
+use crate::optimization::decoder::objective::OBJECTIVE_NAMES;
+use crate::optimization::decoder::score::DECODER_NAMES;
+use crate::optimization::preprocess::calendar::INTERRUPTION_NAMES;
+use crate::optimization::preprocess::instance_format::FORMAT_NAMES;
+use crate::optimization::runner::la40::{
+    SolveOptions, ALGORITHM_NAMES, PROBLEM_NAMES,
+};
+use crate::utilities::file_readers::DELIMITER_NAMES;
+
+/// インスタンスファイルの指定
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceArgs {
+    pub path: OsString,
+    /// 標準形式名 (auto, orlib, taillard) または区切り文字名 (tab, comma, ...)
+    pub format: String,
+    /// job 属性ファイル (release_date, due_date, weight の CSV) のパス
+    pub job_attributes: Option<OsString>,
+    /// 段取り時間ファイル (actor_id, from_job, to_job, setup_time の CSV) のパス
+    pub setup_times: Option<OsString>,
+    /// 稼働カレンダー (actor_id, start, end の停止時間帯の CSV) のパス
+    pub calendar: Option<OsString>,
+    /// 停止時間帯にかかる operation の扱い (non-resumable, resumable)
+    pub interruption: String,
+    /// 既知の最良値の表 ("インスタンス名,makespan" の CSV) のパス
+    pub best_known: Option<OsString>,
+}
+
+/// 最良解の出力先
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputArgs {
+    /// 最良解の書き出し先 (拡張子で JSON / CSV / テキストを切り替える)
+    pub solutions: Vec<OsString>,
+    /// ガントチャート (SVG) の書き出し先
+    pub svg: Option<OsString>,
+    /// ASCII のガントチャートを表示するか
+    pub gantt: bool,
+}
+
+/// サブコマンドごとの引数
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// 解を最適化する
+    Solve {
+        instance: InstanceArgs,
+        options: Box<SolveOptions>,
+        output: OutputArgs,
+    },
+    /// 解ファイルを評価する
+    Evaluate {
+        instance: InstanceArgs,
+        solution: OsString,
+        /// 指定しない場合は解ファイル (JSON) に記録された目的関数を使う
+        objective: Option<String>,
+        /// 指定しない場合は解ファイル (JSON) に記録された復号器を使う
+        decoder: Option<String>,
+    },
+    /// インスタンスの情報を表示する
+    Info { instance: InstanceArgs },
+}
+
+fn instance_args() -> Vec<Arg<'static>> {
+    vec![
+        Arg::new("instance")
+            .help("Path to the instance file")
+            .required(true)
+            .allow_invalid_utf8(true),
+        Arg::new("format")
+            .long("format")
+            .short('f')
+            .help("Instance format or CSV delimiter")
+            .takes_value(true)
+            .possible_values(FORMAT_NAMES.iter().chain(DELIMITER_NAMES.iter()))
+            .ignore_case(true)
+            .default_value("auto"),
+        Arg::new("job-attributes")
+            .long("job-attributes")
+            .help("CSV with release_date, due_date and weight columns")
+            .takes_value(true)
+            .allow_invalid_utf8(true),
+        Arg::new("setup-times")
+            .long("setup-times")
+            .help("CSV with actor_id, from_job, to_job and setup_time columns")
+            .takes_value(true)
+            .allow_invalid_utf8(true),
+        Arg::new("calendar")
+            .long("calendar")
+            .help("CSV of machine downtimes with actor_id, start and end columns")
+            .takes_value(true)
+            .allow_invalid_utf8(true),
+        Arg::new("interruption")
+            .long("interruption")
+            .help("Whether an operation may be interrupted by a downtime and resumed after it")
+            .takes_value(true)
+            .possible_values(INTERRUPTION_NAMES)
+            .default_value("non-resumable"),
+        Arg::new("best-known")
+            .long("best-known")
+            .help("CSV of instance names and best known makespans")
+            .takes_value(true)
+            .allow_invalid_utf8(true),
+    ]
+}
+
+fn objective_arg() -> Arg<'static> {
+    Arg::new("objective")
+        .long("objective")
+        .help("Objective function to minimize")
+        .takes_value(true)
+        .possible_values(OBJECTIVE_NAMES)
+}
+
+fn decoder_arg() -> Arg<'static> {
+    Arg::new("decoder")
+        .long("decoder")
+        .help("Decoder from chromosome to schedule")
+        .takes_value(true)
+        .possible_values(DECODER_NAMES)
+}
+
+/// sandbox コマンドの定義
+pub fn build_app() -> App<'static> {
+    App::new("sandbox")
+        .about("Job shop scheduling solver")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            App::new("solve")
+                .about("Optimize a schedule for the instance")
+                .args(instance_args())
+                .arg(
+                    Arg::new("problem")
+                        .long("problem")
+                        .help("Problem type (flow-shop: permutation flow shop, open-shop: ignore the operation order)")
+                        .takes_value(true)
+                        .possible_values(PROBLEM_NAMES)
+                        .default_value("job-shop"),
+                )
+                .arg(
+                    Arg::new("algo")
+                        .long("algo")
+                        .help("Search algorithm")
+                        .takes_value(true)
+                        .possible_values(ALGORITHM_NAMES)
+                        .default_value("sa"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Write the best solution (.json, .csv or text)")
+                        .takes_value(true)
+                        .multiple_occurrences(true)
+                        .allow_invalid_utf8(true),
+                )
+                .arg(
+                    Arg::new("svg")
+                        .long("svg")
+                        .help("Write a Gantt chart of the best solution as SVG")
+                        .takes_value(true)
+                        .allow_invalid_utf8(true),
+                )
+                .arg(
+                    Arg::new("gantt")
+                        .long("gantt")
+                        .help("Print an ASCII Gantt chart of the best solution"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .help("Seed of every random decision")
+                        .takes_value(true)
+                        .default_value("42"),
+                )
+                .arg(
+                    Arg::new("time-limit")
+                        .long("time-limit")
+                        .help("Wall-clock limit in seconds")
+                        .takes_value(true)
+                        .validator(parse_time_limit),
+                )
+                .arg(
+                    Arg::new("steps")
+                        .long("steps")
+                        .help("Maximum number of steps")
+                        .takes_value(true),
+                )
+                .arg(decoder_arg().default_value("semi-active"))
+                .arg(objective_arg().default_value("makespan"))
+                .arg(
+                    Arg::new("neighborhood")
+                        .long("neighborhood")
+                        .help("Neighborhood (mutation for ga), e.g. swap:0.5,n5:0.5 [default: swap, insertion for flow-shop]")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("cooling")
+                        .long("cooling")
+                        .help("Cooling schedule, e.g. geometric:0.99")
+                        .takes_value(true)
+                        .validator(CoolingSchedule::parse)
+                        .default_value("geometric"),
+                )
+                .arg(
+                    Arg::new("initial-temperature")
+                        .long("initial-temperature")
+                        .help("Initial temperature (estimated if omitted)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("steps-per-temperature")
+                        .long("steps-per-temperature")
+                        .help("Steps at each temperature level")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("threads")
+                        .long("threads")
+                        .help("Number of parallel annealing chains (sa)")
+                        .takes_value(true)
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::new("island")
+                        .long("island")
+                        .help("Exchange best solutions between chains (sa)"),
+                )
+                .arg(
+                    Arg::new("migration-interval")
+                        .long("migration-interval")
+                        .help("Steps between exchanges in island mode (sa)")
+                        .takes_value(true)
+                        .requires("island"),
+                )
+                .arg(
+                    Arg::new("population")
+                        .long("population")
+                        .help("Population size (ga)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("generations")
+                        .long("generations")
+                        .help("Maximum number of generations (ga)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("selection")
+                        .long("selection")
+                        .help("Selection, tournament[:size] or roulette (ga)")
+                        .takes_value(true)
+                        .validator(Selection::parse)
+                        .default_value("tournament"),
+                )
+                .arg(
+                    Arg::new("crossover")
+                        .long("crossover")
+                        .help("Crossover (ga)")
+                        .takes_value(true)
+                        .possible_values(CROSSOVER_NAMES)
+                        .default_value("pox"),
+                )
+                .arg(
+                    Arg::new("crossover-rate")
+                        .long("crossover-rate")
+                        .help("Probability of crossover (ga)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("mutation-rate")
+                        .long("mutation-rate")
+                        .help("Probability of mutation (ga)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("elite")
+                        .long("elite")
+                        .help("Number of elite solutions (ga, ts)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("tabu-moves")
+                        .long("tabu-moves")
+                        .help("Critical block moves (ts)")
+                        .takes_value(true)
+                        .possible_values(["n5", "n7"])
+                        .default_value("n7"),
+                )
+                .arg(
+                    Arg::new("tenure")
+                        .long("tenure")
+                        .help("Tabu tenure, e.g. 10 or 8:12 (ts)")
+                        .takes_value(true)
+                        .validator(parse_tenure),
+                )
+                .arg(
+                    Arg::new("iterations")
+                        .long("iterations")
+                        .help("Maximum number of iterations (ts)")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::new("restart-after")
+                        .long("restart-after")
+                        .help("Restart from an elite after stagnation (ts)")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            App::new("evaluate")
+                .about("Evaluate a solution (.json, .csv or job ids)")
+                .args(instance_args())
+                .arg(
+                    Arg::new("solution")
+                        .help("Path to the solution file")
+                        .required(true)
+                        .allow_invalid_utf8(true),
+                )
+                .arg(decoder_arg().help(
+                    "Decoder from chromosome to schedule [default: as recorded in the .json solution, otherwise semi-active]",
+                ))
+                .arg(objective_arg().help(
+                    "Objective function to minimize [default: as recorded in the .json solution, otherwise makespan]",
+                )),
+        )
+        .subcommand(
+            App::new("info")
+                .about("Show the size and statistics of the instance")
+                .args(instance_args()),
+        )
+}
+
+fn parse_instance_args(matches: &ArgMatches) -> InstanceArgs {
+    InstanceArgs {
+        path: matches.value_of_os("instance").unwrap().to_os_string(),
+        format: matches.value_of("format").unwrap().to_lowercase(),
+        job_attributes: matches
+            .value_of_os("job-attributes")
+            .map(|path| path.to_os_string()),
+        setup_times: matches
+            .value_of_os("setup-times")
+            .map(|path| path.to_os_string()),
+        calendar: matches
+            .value_of_os("calendar")
+            .map(|path| path.to_os_string()),
+        interruption: matches.value_of("interruption").unwrap().to_string(),
+        best_known: matches
+            .value_of_os("best-known")
+            .map(|path| path.to_os_string()),
+    }
+}
+
+/// 制限時間 (秒) を解釈する (正の有限値のみ)
+fn parse_time_limit(value: &str) -> Result<f32, String> {
+    value
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && 0.0 < *seconds)
+        .ok_or_else(|| {
+            format!("invalid time limit {:?} (expected seconds > 0)", value)
+        })
+}
+
+/// 値が指定されていれば型変換し、指定されていなければ None を返す
+fn optional_value<T>(
+    matches: &ArgMatches,
+    name: &str,
+) -> Result<Option<T>, clap::Error>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
+{
+    match matches.value_of(name) {
+        Some(_) => matches.value_of_t::<T>(name).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn parse_solve_options(
+    matches: &ArgMatches,
+) -> Result<SolveOptions, clap::Error> {
+    // validator で検証済み
+    let time_limit: Option<f32> = matches
+        .value_of("time-limit")
+        .map(|value| parse_time_limit(value).unwrap());
+    let defaults: AnnealingConfig = AnnealingConfig::default();
+    let annealing: AnnealingConfig = AnnealingConfig {
+        initial_temperature: optional_value::<f64>(
+            matches,
+            "initial-temperature",
+        )?,
+        // validator で検証済み
+        cooling: CoolingSchedule::parse(matches.value_of("cooling").unwrap())
+            .unwrap(),
+        steps_per_temperature: optional_value::<u32>(
+            matches,
+            "steps-per-temperature",
+        )?
+        .unwrap_or(defaults.steps_per_temperature),
+        steps: optional_value::<u32>(matches, "steps")?
+            .unwrap_or(defaults.steps),
+        time_limit: time_limit.unwrap_or(defaults.time_limit),
+        ..defaults
+    };
+    let defaults: GeneticConfig = GeneticConfig::default();
+    let genetic: GeneticConfig = GeneticConfig {
+        population_size: optional_value::<usize>(matches, "population")?
+            .unwrap_or(defaults.population_size),
+        generations: optional_value::<u32>(matches, "generations")?
+            .unwrap_or(defaults.generations),
+        // validator / possible_values で検証済み
+        selection: Selection::parse(matches.value_of("selection").unwrap())
+            .unwrap(),
+        crossover: Crossover::from_name(matches.value_of("crossover").unwrap())
+            .unwrap(),
+        crossover_rate: optional_value::<f64>(matches, "crossover-rate")?
+            .unwrap_or(defaults.crossover_rate),
+        mutation_rate: optional_value::<f64>(matches, "mutation-rate")?
+            .unwrap_or(defaults.mutation_rate),
+        elite_size: optional_value::<usize>(matches, "elite")?
+            .unwrap_or(defaults.elite_size),
+        time_limit: time_limit.unwrap_or(defaults.time_limit),
+    };
+    let defaults: TabuConfig = TabuConfig::default();
+    let tabu: TabuConfig = TabuConfig {
+        moves: match matches.value_of("tabu-moves") {
+            Some("n5") => CriticalBlockMoves::N5,
+            _ => CriticalBlockMoves::N7,
+        },
+        // validator で検証済み
+        tenure: matches
+            .value_of("tenure")
+            .map(|tenure| parse_tenure(tenure).unwrap()),
+        iterations: optional_value::<u32>(matches, "iterations")?
+            .unwrap_or(defaults.iterations),
+        restart_after: optional_value::<u32>(matches, "restart-after")?
+            .unwrap_or(defaults.restart_after),
+        elite_size: optional_value::<usize>(matches, "elite")?
+            .unwrap_or(defaults.elite_size),
+        time_limit: time_limit.unwrap_or(defaults.time_limit),
+    };
+    let defaults: BranchAndBoundConfig = BranchAndBoundConfig::default();
+    let branch_and_bound: BranchAndBoundConfig = BranchAndBoundConfig {
+        time_limit: time_limit.unwrap_or(defaults.time_limit),
+    };
+
+    let parallel: ParallelConfig = ParallelConfig {
+        threads: matches.value_of_t::<usize>("threads")?,
+        mode: if matches.is_present("island") {
+            match optional_value::<u32>(matches, "migration-interval")? {
+                Some(migration_interval) => {
+                    ParallelMode::Island { migration_interval }
+                }
+                None => ParallelMode::island(),
+            }
+        } else {
+            ParallelMode::MultiStart
+        },
+    };
+
+    let problem: &str = matches.value_of("problem").unwrap();
+    // 順列フローショップでは挿入近傍を既定にする
+    let neighborhood: &str =
+        matches.value_of("neighborhood").unwrap_or(match problem {
+            "flow-shop" => "insertion",
+            _ => "swap",
+        });
+
+    Ok(SolveOptions {
+        problem: problem.to_string(),
+        algorithm: matches.value_of("algo").unwrap().to_string(),
+        objective: matches.value_of("objective").unwrap().to_string(),
+        decoder: matches.value_of("decoder").unwrap().to_string(),
+        neighborhood: neighborhood.to_string(),
+        annealing,
+        genetic,
+        tabu,
+        branch_and_bound,
+        parallel,
+        seed: matches.value_of_t::<u64>("seed")?,
+    })
+}
+
+/// コマンドライン引数 (先頭はプログラム名) を解析します。
+/// 不正な引数や --help の場合は clap::Error を返します。
+pub fn try_parse_from<I, T>(args: I) -> Result<Command, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches: ArgMatches = build_app().try_get_matches_from(args)?;
+    match matches.subcommand() {
+        Some(("solve", sub_matches)) => Ok(Command::Solve {
+            instance: parse_instance_args(sub_matches),
+            options: Box::new(parse_solve_options(sub_matches)?),
+            output: OutputArgs {
+                solutions: sub_matches
+                    .values_of_os("output")
+                    .map(|values| values.map(OsString::from).collect())
+                    .unwrap_or_default(),
+                svg: sub_matches.value_of_os("svg").map(OsString::from),
+                gantt: sub_matches.is_present("gantt"),
+            },
+        }),
+        Some(("evaluate", sub_matches)) => Ok(Command::Evaluate {
+            instance: parse_instance_args(sub_matches),
+            solution: sub_matches
+                .value_of_os("solution")
+                .unwrap()
+                .to_os_string(),
+            objective: sub_matches.value_of("objective").map(str::to_string),
+            decoder: sub_matches.value_of("decoder").map(str::to_string),
+        }),
+        Some(("info", sub_matches)) => Ok(Command::Info {
+            instance: parse_instance_args(sub_matches),
+        }),
+        // SubcommandRequiredElseHelp により到達しない
+        _ => unreachable!("subcommand is required"),
+    }
+}
+
+/// プロセスのコマンドライン引数を解析します。
+/// 不正な引数の場合は使い方を表示して終了します (終了コード 2)。
+pub fn parse() -> Command {
+    try_parse_from(std::env::args_os()).unwrap_or_else(|err| err.exit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ErrorKind;
+
+    #[test]
+    fn test_parse_solve() {
+        let command = try_parse_from([
+            "sandbox",
+            "solve",
+            "ft06.txt",
+            "--seed",
+            "7",
+            "--steps",
+            "1000",
+            "--time-limit",
+            "1.5",
+            "--decoder",
+            "active",
+            "--neighborhood",
+            "n5",
+            "--cooling",
+            "lundy-mees:0.01",
+            "--algo",
+            "ga",
+            "--crossover",
+            "gox",
+            "--population",
+            "30",
+            "--threads",
+            "4",
+            "--island",
+            "--migration-interval",
+            "500",
+            "--output",
+            "best.json",
+            "-o",
+            "best.csv",
+            "--svg",
+            "best.svg",
+        ])
+        .unwrap();
+        match command {
+            Command::Solve {
+                instance,
+                options,
+                output,
+            } => {
+                assert_eq!(
+                    output,
+                    OutputArgs {
+                        solutions: vec![
+                            OsString::from("best.json"),
+                            OsString::from("best.csv")
+                        ],
+                        svg: Some(OsString::from("best.svg")),
+                        gantt: false,
+                    }
+                );
+                assert_eq!(instance.path, OsString::from("ft06.txt"));
+                assert_eq!(instance.format, "auto");
+                assert_eq!(instance.job_attributes, None);
+                assert_eq!(instance.setup_times, None);
+                assert_eq!(instance.calendar, None);
+                assert_eq!(instance.interruption, "non-resumable");
+                assert_eq!(options.problem, "job-shop");
+                assert_eq!(options.algorithm, "ga");
+                assert_eq!(options.seed, 7);
+                assert_eq!(options.decoder, "active");
+                assert_eq!(options.objective, "makespan");
+                assert_eq!(options.neighborhood, "n5");
+                assert_eq!(options.annealing.steps, 1000);
+                assert_eq!(options.annealing.time_limit, 1.5);
+                assert_eq!(
+                    options.annealing.cooling,
+                    CoolingSchedule::LundyMees { beta: 0.01 }
+                );
+                assert_eq!(options.genetic.crossover, Crossover::Gox);
+                assert_eq!(options.genetic.population_size, 30);
+                assert_eq!(options.genetic.time_limit, 1.5);
+                assert_eq!(options.tabu.moves, CriticalBlockMoves::N7);
+                assert_eq!(options.tabu.tenure, None);
+                assert_eq!(options.tabu.time_limit, 1.5);
+                assert_eq!(options.branch_and_bound.time_limit, 1.5);
+                assert_eq!(
+                    options.parallel,
+                    ParallelConfig {
+                        threads: 4,
+                        mode: ParallelMode::Island {
+                            migration_interval: 500
+                        },
+                    }
+                );
+            }
+            _ => panic!("expected solve"),
+        }
+    }
+
+    #[test]
+    fn test_parse_flow_shop_neighborhood() {
+        let neighborhood = |args: &[&str]| match try_parse_from(args).unwrap() {
+            Command::Solve { options, .. } => options.neighborhood,
+            _ => panic!("expected solve"),
+        };
+        assert_eq!(neighborhood(&["sandbox", "solve", "a.txt"]), "swap");
+        assert_eq!(
+            neighborhood(&[
+                "sandbox",
+                "solve",
+                "a.txt",
+                "--problem",
+                "flow-shop"
+            ]),
+            "insertion"
+        );
+        assert_eq!(
+            neighborhood(&[
+                "sandbox",
+                "solve",
+                "a.txt",
+                "--problem",
+                "flow-shop",
+                "--neighborhood",
+                "swap"
+            ]),
+            "swap"
+        );
+    }
+
+    #[test]
+    fn test_parse_evaluate_and_info() {
+        let command = try_parse_from([
+            "sandbox",
+            "evaluate",
+            "jobs.csv",
+            "solution.txt",
+            "--format",
+            "tab",
+            "--objective",
+            "tardy-jobs",
+            "--job-attributes",
+            "attributes.csv",
+            "--setup-times",
+            "setups.csv",
+            "--calendar",
+            "downtimes.csv",
+            "--interruption",
+            "resumable",
+        ])
+        .unwrap();
+        match command {
+            Command::Evaluate {
+                instance,
+                solution,
+                objective,
+                decoder,
+            } => {
+                assert_eq!(instance.format, "tab");
+                assert_eq!(
+                    instance.job_attributes,
+                    Some(OsString::from("attributes.csv"))
+                );
+                assert_eq!(
+                    instance.setup_times,
+                    Some(OsString::from("setups.csv"))
+                );
+                assert_eq!(
+                    instance.calendar,
+                    Some(OsString::from("downtimes.csv"))
+                );
+                assert_eq!(instance.interruption, "resumable");
+                assert_eq!(solution, OsString::from("solution.txt"));
+                assert_eq!(objective, Some("tardy-jobs".to_string()));
+                // 指定しない場合は解ファイルの記録に任せる
+                assert_eq!(decoder, None);
+            }
+            _ => panic!("expected evaluate"),
+        }
+
+        let command =
+            try_parse_from(["sandbox", "info", "ft06.txt", "-f", "ORLIB"])
+                .unwrap();
+        match command {
+            Command::Info { instance } => assert_eq!(instance.format, "orlib"),
+            _ => panic!("expected info"),
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        let kind = |args: &[&str]| try_parse_from(args).unwrap_err().kind;
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--format", "colon"]),
+            ErrorKind::InvalidValue
+        );
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--seed", "-1"]),
+            ErrorKind::UnknownArgument
+        );
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--seed", "abc"]),
+            ErrorKind::ValueValidation
+        );
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--cooling", "fast"]),
+            ErrorKind::ValueValidation
+        );
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--algo", "aco"]),
+            ErrorKind::InvalidValue
+        );
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--selection", "rank"]),
+            ErrorKind::ValueValidation
+        );
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--tenure", "9:3"]),
+            ErrorKind::ValueValidation
+        );
+        for time_limit in ["0", "NaN", "inf", "abc"] {
+            assert_eq!(
+                kind(&[
+                    "sandbox",
+                    "solve",
+                    "a.txt",
+                    "--time-limit",
+                    time_limit
+                ]),
+                ErrorKind::ValueValidation
+            );
+        }
+        assert_eq!(
+            kind(&["sandbox", "solve", "a.txt", "--migration-interval", "9"]),
+            ErrorKind::MissingRequiredArgument
+        );
+        assert_eq!(
+            kind(&["sandbox", "solve"]),
+            ErrorKind::MissingRequiredArgument
+        );
+        assert_eq!(
+            kind(&["sandbox"]),
+            ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+        );
+    }
+}
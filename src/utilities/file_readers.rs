@@ -1,25 +1,24 @@
 use csv::StringRecord;
-use std::env;
 use std::error::Error;
 use std::ffi::OsString;
 use std::fs::File;
-use std::process;
-
-/// コマンドライン引数の最初の値を取得します。
-/// 引数がない場合はエラーを返します。
-fn get_first_arg() -> Result<OsString, Box<dyn Error>> {
-    // コマンドライン引数を取得
-    match env::args_os().nth(1) {
-        None => Err(From::from("expected 1 argument, but got none ...")),
-        Some(file_path) => Ok(file_path),
-    }
-}
 
-fn get_second_arg() -> Result<OsString, Box<dyn Error>> {
-    // コマンドライン引数を取得
-    match env::args_os().nth(2) {
-        None => Err(From::from("expected 2nd argument, but got none ...")),
-        Some(delimiter) => Ok(delimiter),
+use crate::optimization::types;
+
+/// 区切り文字として指定できる名前
+pub const DELIMITER_NAMES: [&str; 5] =
+    ["tab", "comma", "semicolon", "pipe", "space"];
+
+/// 区切り文字名 (tab, comma, ...) を区切り文字に変換します。
+/// 未知の名前の場合は None を返します。
+pub fn delimiter_from_name(name: &str) -> Option<u8> {
+    match name.to_lowercase().as_str() {
+        "tab" => Some(b'\t'),
+        "comma" => Some(b','),
+        "semicolon" => Some(b';'),
+        "pipe" => Some(b'|'),
+        "space" => Some(b' '),
+        _ => None,
     }
 }
 
@@ -68,100 +67,83 @@ fn parse_delimited_file(
 /// 指定されたパスのファイルを開きます。
 /// ファイルが開けない場合はエラーを返します。
 fn fopen(path: &OsString) -> Result<File, Box<dyn Error>> {
-    let file: File = match File::open(path) {
-        Ok(file) => file,
-        Err(err) => {
-            eprintln!("Error opening file {:?}: {}", path, err);
-            process::exit(1);
-        }
-    };
-    Ok(file)
+    File::open(path)
+        .map_err(|err| format!("cannot open {:?}: {}", path, err).into())
 }
 
-/// コマンドライン引数を解析するための構造体
-struct ArgParser {
+/// 指定されたファイルを区切り文字で分割して読み込み、
+/// ヘッダー行とそれ以降の行を返します。
+///
+/// # Examples
+///
+/// $ cargo run solve data/input/la40_tailored.txt --format tab
+/// $ cargo run solve data/input/la40_tailored.txt --format comma
+/// $ cargo run solve data/input/la40_tailored.txt --format semicolon
+///
+pub fn read_csv(
     file_path: OsString,
     delimiter: u8,
     delimiter_str: String,
+) -> Result<(Option<StringRecord>, Vec<StringRecord>), Box<dyn Error>> {
+    // ファイルを開く
+    let file: File = fopen(&file_path)?;
+    let (header, row_list): (Option<StringRecord>, Vec<StringRecord>) =
+        parse_delimited_file(file, delimiter)
+            .map_err(|err| format!("cannot parse {:?}: {}", file_path, err))?;
+    println!(
+        "Successfully {:?} is parsed with {:?}",
+        file_path, delimiter_str
+    );
+    Ok((header, row_list))
 }
-impl ArgParser {
-    /// 新しいArgParserを作成します。
-    /// 引数はコマンドライン引数から取得します。
-    fn new() -> Self {
-        let file_path = get_first_arg().unwrap();
-        let delimiter_str = get_second_arg()
-            .unwrap_or_else(|_| OsString::from("comma"))
-            .into_string()
-            .unwrap_or_else(|_| "comma".to_string());
-
-        let delimiter = match delimiter_str.to_lowercase().as_str() {
-            "tab" => b'\t',
-            "comma" => b',',
-            "semicolon" => b';',
-            "pipe" => b'|',
-            "space" => b' ',
-            _ => b',',
-        };
-
-        let delimiter_str = match delimiter {
-            b'\t' => "tab".to_string(),
-            b',' => "comma".to_string(),
-            b';' => "semicolon".to_string(),
-            b'|' => "pipe".to_string(),
-            b' ' => "space".to_string(),
-            _ => "comma".to_string(),
-        };
-
-        Self {
-            file_path,
-            delimiter,
-            delimiter_str,
-        }
-    }
-}
-
-pub fn parse_args() -> (OsString, u8, String) {
-    // コマンドライン引数を解析
-    let arg_parser = ArgParser::new();
-    let file_path = arg_parser.file_path;
-    let delimiter: u8 = arg_parser.delimiter;
-    let delimiter_str: String = arg_parser.delimiter_str;
 
-    (file_path, delimiter, delimiter_str)
+/// 指定されたパスのファイルを文字列として読み込みます。
+/// 読み込みに失敗した場合はエラーを返します。
+pub fn read_text(file_path: &OsString) -> Result<String, Box<dyn Error>> {
+    std::fs::read_to_string(file_path)
+        .map_err(|err| format!("cannot read {:?}: {}", file_path, err).into())
 }
 
-/// コマンドライン引数で指定されたファイルを読み込み、
-/// 各行を出力します。ファイルパスを文字列として返します。
+/// 解 (job 番号の列) をテキストから読み込みます。
+/// 区切りはカンマ・空白・改行のいずれでもよく、前後の角括弧は無視します。
 ///
 /// # Examples
 ///
-/// $ cargo run data/input/la40_tailored.txt tab
-/// $ cargo run data/input/la40_tailored.txt comma
-/// $ cargo run data/input/la40_tailored.txt semicolon
-///
-pub fn read_csv(
-    file_path: OsString,
-    delimiter: u8,
-    delimiter_str: String,
-) -> (Option<StringRecord>, Vec<StringRecord>) {
-    // ファイルを開く
-    let file: File = fopen(&file_path).unwrap();
-    let (header, row_list) = match parse_delimited_file(file, delimiter) {
-        Ok(result) => {
-            let (header, row_list): (Option<StringRecord>, Vec<StringRecord>) =
-                result;
-            println!(
-                "Successfully {:?} is parsed with {:?}",
-                file_path, delimiter_str
-            );
-            (header, row_list)
-        }
-        Err(err) => {
-            eprintln!("Error: {}", err);
-            process::exit(1);
-        }
-    };
-
-    // file_path.into_string().unwrap()
-    (header, row_list)
+/// "[0, 1, 1, 0]" や "0 1 1 0" を vec![0, 1, 1, 0] として読み込む
+pub fn parse_chromosome(text: &str) -> Result<types::Chromosome, String> {
+    text.split(|c: char| c == ',' || c == '[' || c == ']' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            token
+                .parse::<u16>()
+                .map_err(|_| format!("{:?} is not a valid job id", token))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delimiter_from_name() {
+        assert_eq!(delimiter_from_name("Tab"), Some(b'\t'));
+        assert_eq!(delimiter_from_name("pipe"), Some(b'|'));
+        assert_eq!(delimiter_from_name("colon"), None);
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        let path: OsString = OsString::from("no/such/instance.txt");
+        assert!(read_text(&path).is_err());
+        assert!(read_csv(path, b',', "comma".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_chromosome() {
+        assert_eq!(parse_chromosome("[0, 1, 1, 0]"), Ok(vec![0, 1, 1, 0]));
+        assert_eq!(parse_chromosome("2 0\n1\n"), Ok(vec![2, 0, 1]));
+        assert!(parse_chromosome("0, x, 1").is_err());
+        assert!(parse_chromosome("0, -1").is_err());
+    }
 }
@@ -0,0 +1,331 @@
+use std::fmt::Write;
+
+use crate::optimization::decoder::disjunctive_graph::{
+    DisjunctiveGraph, GraphSchedule, OperationId,
+};
+use crate::optimization::decoder::schedule::Schedule;
+use crate::optimization::preprocess::calendar::Downtime;
+use crate::optimization::preprocess::runner::JobMaster;
+use crate::optimization::types::Time;
+
+/// ASCII のガントチャートで job を表す文字 (job 数が多い場合は繰り返します)
+const GLYPHS: &[u8] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// SVG の各部の大きさ (px)
+const SVG_WIDTH: f64 = 1000.0;
+const SVG_LABEL_WIDTH: f64 = 60.0;
+const SVG_ROW_HEIGHT: f64 = 24.0;
+const SVG_MARGIN: f64 = 30.0;
+
+/// 端末の幅 (環境変数 COLUMNS、未設定の場合は 80)
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse::<usize>().ok())
+        .filter(|columns| 0 < *columns)
+        .unwrap_or(80)
+}
+
+/// job を表す文字
+fn glyph(job: u16) -> char {
+    GLYPHS[job as usize % GLYPHS.len()] as char
+}
+
+/// スケジュールを actor ごとに1行の ASCII ガントチャートにします。
+/// 時間軸は width 文字 (行の見出しを含む) に収まるように縮尺します。
+pub fn render_ascii(schedule: &Schedule, width: usize) -> String {
+    let label_width: usize =
+        format!("M{}", schedule.machine_sequences.len().saturating_sub(1))
+            .len();
+    // 見出しと両端の '|' の分を除いた幅
+    let columns: usize = width.saturating_sub(label_width + 3).max(1);
+    let makespan: u64 = (schedule.makespan as u64).max(1);
+    let column_of =
+        |time: Time| (time as u64 * columns as u64 / makespan) as usize;
+
+    let mut text: String = String::new();
+    for (actor_id, sequence) in schedule.machine_sequences.iter().enumerate() {
+        let mut row: Vec<char> = vec![' '; columns];
+        for index in sequence {
+            let operation = &schedule.operations[*index];
+            let start: usize = column_of(operation.start).min(columns - 1);
+            // 短い operation も1文字は表示する
+            let end: usize = column_of(operation.end).clamp(start + 1, columns);
+            row[start..end].fill(glyph(operation.job));
+        }
+        let row: String = row.into_iter().collect();
+        let label: String = format!("M{}", actor_id);
+        writeln!(text, "{:>label_width$} |{}|", label, row).unwrap();
+    }
+    writeln!(
+        text,
+        "{:>label_width$} 0{:>columns$}",
+        "",
+        schedule.makespan,
+        columns = columns + 1
+    )
+    .unwrap();
+    text
+}
+
+/// クリティカルパス上の operation (OperationId で添字付け)
+///
+/// schedule の actor ごとの処理順から選言グラフを作って求めるため、
+/// schedule は semi-active (左に詰められない) である必要があります。
+/// FJSP・オープンショップのインスタンスでは選言グラフを作れないため、
+/// 段取り時間・稼働カレンダーがあるインスタンスでは選言グラフが
+/// それらを表せないため、空を返します。
+pub fn critical_operations(
+    job_master: &JobMaster,
+    schedule: &Schedule,
+) -> Vec<bool> {
+    if job_master.is_flexible()
+        || job_master.open_shop
+        || job_master.setup_times.is_some()
+        || job_master.calendars.is_some()
+    {
+        return Vec::new();
+    }
+    let graph: DisjunctiveGraph =
+        DisjunctiveGraph::from_schedule(job_master, schedule);
+    let mut critical: Vec<bool> = vec![false; graph.size()];
+    let evaluated: Option<GraphSchedule> = graph.evaluate();
+    if let Some(evaluated) = evaluated {
+        for id in graph.critical_path(&evaluated) {
+            critical[id] = true;
+        }
+    }
+    critical
+}
+
+/// job ごとの色 (黄金角ずつ色相をずらす)
+fn job_color(job: u16) -> String {
+    let hue: f64 = (job as f64 * 137.508) % 360.0;
+    format!("hsl({:.0}, 65%, 60%)", hue)
+}
+
+/// 目盛りの間隔 (1, 2, 5 x 10^n のうち、目盛りが 10 個前後になるもの)
+fn tick_interval(makespan: Time) -> Time {
+    let target: f64 = (makespan as f64 / 10.0).max(1.0);
+    let base: f64 = 10f64.powf(target.log10().floor());
+    let step: f64 = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * base)
+        .find(|step| target <= *step)
+        .unwrap_or(10.0 * base);
+    step as Time
+}
+
+/// スケジュールを単体の SVG ファイルの内容にします。
+/// job ごとに色分けし、クリティカルパス上の operation を赤枠で強調します。
+/// 稼働カレンダーがある場合は、actor の停止時間帯を灰色で示します。
+pub fn render_svg(job_master: &JobMaster, schedule: &Schedule) -> String {
+    let critical: Vec<bool> = critical_operations(job_master, schedule);
+    let rows: usize = schedule.machine_sequences.len();
+    let chart_width: f64 = SVG_WIDTH - SVG_LABEL_WIDTH - 2.0 * SVG_MARGIN;
+    let chart_height: f64 = rows as f64 * SVG_ROW_HEIGHT;
+    let height: f64 = chart_height + 3.0 * SVG_MARGIN;
+    let left: f64 = SVG_MARGIN + SVG_LABEL_WIDTH;
+    let top: f64 = SVG_MARGIN;
+    let scale: f64 = chart_width / (schedule.makespan.max(1) as f64);
+
+    let mut svg: String = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="sans-serif" font-size="11">"#,
+        SVG_WIDTH, height
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="13">makespan: {}</text>"#,
+        SVG_MARGIN,
+        top - 10.0,
+        schedule.makespan
+    )
+    .unwrap();
+
+    // 時間軸の目盛り
+    let interval: Time = tick_interval(schedule.makespan);
+    let mut tick: Time = 0;
+    while tick <= schedule.makespan {
+        let x: f64 = left + tick as f64 * scale;
+        writeln!(
+            svg,
+            r##"<line x1="{x:.1}" y1="{top}" x2="{x:.1}" y2="{bottom}" stroke="#ddd"/>"##,
+            x = x,
+            top = top,
+            bottom = top + chart_height
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{}" text-anchor="middle">{}</text>"#,
+            x,
+            top + chart_height + 15.0,
+            tick
+        )
+        .unwrap();
+        tick += interval;
+    }
+
+    for (actor_id, sequence) in schedule.machine_sequences.iter().enumerate() {
+        let y: f64 = top + actor_id as f64 * SVG_ROW_HEIGHT;
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" dominant-baseline="middle">M{}</text>"#,
+            SVG_MARGIN,
+            y + SVG_ROW_HEIGHT / 2.0,
+            actor_id
+        )
+        .unwrap();
+        let downtimes: &[Downtime] = job_master
+            .calendars
+            .as_ref()
+            .filter(|calendars| actor_id < calendars.actor_count())
+            .map_or(&[], |calendars| calendars.downtimes(actor_id));
+        for downtime in downtimes
+            .iter()
+            .take_while(|downtime| downtime.start < schedule.makespan)
+        {
+            let end: Time = downtime.end.min(schedule.makespan);
+            writeln!(
+                svg,
+                r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#ccc"><title>downtime: {}-{}</title></rect>"##,
+                left + downtime.start as f64 * scale,
+                y,
+                (end - downtime.start) as f64 * scale,
+                SVG_ROW_HEIGHT,
+                downtime.start,
+                downtime.end
+            )
+            .unwrap();
+        }
+        for index in sequence {
+            let operation = &schedule.operations[*index];
+            let id: OperationId = operation.job as usize
+                * job_master.machine_series_size
+                + operation.operation;
+            let x: f64 = left + operation.start as f64 * scale;
+            let width: f64 = (operation.end - operation.start) as f64 * scale;
+            let stroke: &str = if critical.get(id).copied().unwrap_or(false) {
+                r##"stroke="#d00" stroke-width="2""##
+            } else {
+                r##"stroke="#333" stroke-width="0.5""##
+            };
+            writeln!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" {}><title>job {} operation {}: {}-{}</title></rect>"#,
+                x,
+                y + 2.0,
+                width,
+                SVG_ROW_HEIGHT - 4.0,
+                job_color(operation.job),
+                stroke,
+                operation.job,
+                operation.operation,
+                operation.start,
+                operation.end
+            )
+            .unwrap();
+            // 幅が足りる場合のみ job 番号を書く
+            if 14.0 <= width {
+                writeln!(
+                    svg,
+                    r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+                    x + width / 2.0,
+                    y + SVG_ROW_HEIGHT / 2.0,
+                    operation.job
+                )
+                .unwrap();
+            }
+        }
+    }
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::score::ScoreCalculator;
+    use crate::optimization::preprocess::calendar::{Calendars, Interruption};
+    use crate::optimization::test_fixtures::sample_job_master;
+
+    fn sample_schedule() -> Schedule {
+        ScoreCalculator::new(sample_job_master())
+            .decode_schedule(&[0, 1, 2, 0, 1, 2])
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        // makespan 12 を 12 文字に描画すると 1 文字 = 1 時刻になる
+        let schedule = sample_schedule();
+        assert_eq!(schedule.makespan, 12);
+        let text: String = render_ascii(&schedule, 12 + 5);
+        assert_eq!(
+            text,
+            "M0 |000222211111|\nM1 |11 00  2    |\n   0           12\n"
+        );
+    }
+
+    #[test]
+    fn test_render_ascii_scales_to_width() {
+        let schedule = sample_schedule();
+        for width in [10, 40, 120] {
+            for line in render_ascii(&schedule, width).lines() {
+                assert!(line.chars().count() <= width.max(6));
+            }
+        }
+    }
+
+    #[test]
+    fn test_critical_operations() {
+        let job_master = sample_job_master();
+        let critical = critical_operations(&job_master, &sample_schedule());
+        // job 0 (0-3) → job 2 (3-7) → job 1 の 2 番目 (7-12) が makespan を決める
+        assert_eq!(critical, vec![true, false, false, true, true, false]);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let job_master = sample_job_master();
+        let svg: String = render_svg(&job_master, &sample_schedule());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 6);
+        assert_eq!(svg.matches(r##"stroke="#d00""##).count(), 3);
+    }
+
+    #[test]
+    fn test_render_svg_with_calendars() {
+        let mut job_master = sample_job_master();
+        // makespan より後に始まる停止時間帯は描かない
+        job_master.calendars = Some(Calendars::new(
+            vec![
+                vec![Downtime { start: 8, end: 9 }],
+                vec![
+                    Downtime { start: 1, end: 3 },
+                    Downtime { start: 50, end: 60 },
+                ],
+            ],
+            Interruption::Resumable,
+        ));
+        let schedule: Schedule = ScoreCalculator::new(job_master.clone())
+            .decode_schedule(&[0, 1, 2, 0, 1, 2]);
+        let svg: String = render_svg(&job_master, &schedule);
+        assert_eq!(svg.matches("<title>downtime").count(), 2);
+        assert_eq!(svg.matches("<rect").count(), 8);
+        // 選言グラフで表せないため、クリティカルパスは強調しない
+        assert_eq!(svg.matches(r##"stroke="#d00""##).count(), 0);
+    }
+
+    #[test]
+    fn test_tick_interval() {
+        assert_eq!(tick_interval(12), 2);
+        assert_eq!(tick_interval(55), 10);
+        assert_eq!(tick_interval(1234), 200);
+        assert_eq!(tick_interval(0), 1);
+    }
+}
@@ -1 +1,4 @@
+pub mod cli;
 pub mod file_readers;
+pub mod gantt;
+pub mod solution_file;
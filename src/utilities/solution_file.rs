@@ -0,0 +1,341 @@
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::decoder::schedule::ScheduledOperation;
+use crate::optimization::runner::la40::{Solution, SolveOptions};
+use crate::optimization::types::{self, Time};
+use crate::utilities::file_readers;
+
+/// 解ファイルの形式 (拡張子から判定します)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolutionFormat {
+    /// 設定・染色体・スケジュールをまとめた JSON (.json)
+    Json,
+    /// operation ごとに1行のスケジュール (.csv)
+    Csv,
+    /// job 番号の列のみ (それ以外の拡張子)
+    Text,
+}
+
+impl SolutionFormat {
+    pub fn from_path(path: &OsStr) -> Self {
+        let extension: Option<String> = Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("json") => SolutionFormat::Json,
+            Some("csv") => SolutionFormat::Csv,
+            _ => SolutionFormat::Text,
+        }
+    }
+}
+
+/// 保存する解 (実行条件と、デコードしたスケジュールを含みます)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolutionFile {
+    /// インスタンスファイルのパス
+    pub instance: String,
+    pub seed: u64,
+    pub algorithm: String,
+    /// 探索に使った設定 (SolveOptions のうち、algorithm が使う項目のみ)
+    pub config: serde_json::Value,
+    pub makespan: Time,
+    pub objective: String,
+    pub objective_value: f64,
+    pub chromosome: types::Chromosome,
+    /// 各 operation の割り当て (開始時刻・終了時刻)
+    pub operations: Vec<ScheduledOperation>,
+}
+
+impl SolutionFile {
+    pub fn new(
+        instance: &str,
+        options: &SolveOptions,
+        solution: &Solution,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(SolutionFile {
+            instance: instance.to_string(),
+            seed: options.seed,
+            algorithm: options.algorithm.clone(),
+            config: used_config(options)?,
+            makespan: solution.evaluation.makespan,
+            objective: options.objective.clone(),
+            objective_value: solution.evaluation.objective_value,
+            chromosome: solution.chromosome.clone(),
+            operations: solution.schedule.operations.clone(),
+        })
+    }
+}
+
+/// SolveOptions のうち、options.algorithm が使う項目だけを取り出す
+fn used_config(
+    options: &SolveOptions,
+) -> Result<serde_json::Value, serde_json::Error> {
+    let mut config: serde_json::Map<String, serde_json::Value> =
+        serde_json::Map::new();
+    config.insert("problem".to_string(), options.problem.clone().into());
+    config.insert("algorithm".to_string(), options.algorithm.clone().into());
+    config.insert("objective".to_string(), options.objective.clone().into());
+    config.insert("decoder".to_string(), options.decoder.clone().into());
+    config.insert("seed".to_string(), options.seed.into());
+    match options.algorithm.as_str() {
+        "sa" => {
+            config.insert(
+                "neighborhood".to_string(),
+                options.neighborhood.clone().into(),
+            );
+            config.insert(
+                "annealing".to_string(),
+                serde_json::to_value(options.annealing)?,
+            );
+            config.insert(
+                "parallel".to_string(),
+                serde_json::to_value(options.parallel)?,
+            );
+        }
+        "ga" => {
+            config.insert(
+                "neighborhood".to_string(),
+                options.neighborhood.clone().into(),
+            );
+            config.insert(
+                "genetic".to_string(),
+                serde_json::to_value(options.genetic)?,
+            );
+        }
+        "ts" => {
+            config.insert(
+                "tabu".to_string(),
+                serde_json::to_value(options.tabu)?,
+            );
+        }
+        "bb" => {
+            config.insert(
+                "branch_and_bound".to_string(),
+                serde_json::to_value(options.branch_and_bound)?,
+            );
+        }
+        _ => {}
+    }
+    Ok(serde_json::Value::Object(config))
+}
+
+/// 解を拡張子に応じた形式 (JSON / CSV / テキスト) で書き出します。
+pub fn write_solution(
+    path: &OsStr,
+    solution_file: &SolutionFile,
+) -> Result<(), Box<dyn Error>> {
+    let file: File = File::create(path)
+        .map_err(|err| format!("cannot create {:?}: {}", path, err))?;
+    match SolutionFormat::from_path(path) {
+        SolutionFormat::Json => {
+            serde_json::to_writer_pretty(&file, solution_file)?;
+            writeln!(&file)?;
+        }
+        SolutionFormat::Csv => {
+            write_operations_csv(&file, &solution_file.operations)?
+        }
+        SolutionFormat::Text => {
+            writeln!(&file, "{:?}", solution_file.chromosome)?
+        }
+    }
+    Ok(())
+}
+
+/// 読み込んだ解
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSolution {
+    pub chromosome: types::Chromosome,
+    /// ファイルに記録されていた割り当て (テキスト形式の場合は None)
+    pub operations: Option<Vec<ScheduledOperation>>,
+    /// 探索時の目的関数 (JSON 形式の場合のみ)
+    pub objective: Option<String>,
+    /// 探索時の復号器 (JSON 形式の場合のみ)
+    pub decoder: Option<String>,
+}
+
+/// 解ファイルを読み込みます。
+/// CSV の場合は operation を開始時刻順に並べた job 番号の列を染色体とします。
+pub fn read_solution(path: &OsStr) -> Result<ImportedSolution, Box<dyn Error>> {
+    match SolutionFormat::from_path(path) {
+        SolutionFormat::Json => {
+            let text: String = fs::read_to_string(path)
+                .map_err(|err| format!("cannot read {:?}: {}", path, err))?;
+            let solution_file: SolutionFile = serde_json::from_str(&text)?;
+            let decoder: Option<String> = solution_file
+                .config
+                .get("decoder")
+                .and_then(|decoder| decoder.as_str())
+                .map(str::to_string);
+            Ok(ImportedSolution {
+                chromosome: solution_file.chromosome,
+                operations: Some(solution_file.operations),
+                objective: Some(solution_file.objective),
+                decoder,
+            })
+        }
+        SolutionFormat::Csv => {
+            let file: File = File::open(path)
+                .map_err(|err| format!("cannot open {:?}: {}", path, err))?;
+            let operations: Vec<ScheduledOperation> =
+                read_operations_csv(file)?;
+            Ok(ImportedSolution {
+                chromosome: chromosome_from_operations(&operations),
+                operations: Some(operations),
+                objective: None,
+                decoder: None,
+            })
+        }
+        SolutionFormat::Text => {
+            let text: String = fs::read_to_string(path)
+                .map_err(|err| format!("cannot read {:?}: {}", path, err))?;
+            Ok(ImportedSolution {
+                chromosome: file_readers::parse_chromosome(&text)?,
+                operations: None,
+                objective: None,
+                decoder: None,
+            })
+        }
+    }
+}
+
+/// operation ごとに1行 (job, operation, actor_id, start, end, setup) の CSV を書き出します。
+pub fn write_operations_csv<W: Write>(
+    writer: W,
+    operations: &[ScheduledOperation],
+) -> Result<(), Box<dyn Error>> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    for operation in operations {
+        wtr.serialize(operation)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// write_operations_csv で書き出した CSV を読み込みます。
+/// setup 列がない (段取り時間に対応する前の) CSV は setup を 0 とします。
+pub fn read_operations_csv<R: Read>(
+    reader: R,
+) -> Result<Vec<ScheduledOperation>, Box<dyn Error>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let mut operations: Vec<ScheduledOperation> = Vec::new();
+    for result in rdr.deserialize() {
+        operations.push(result?);
+    }
+    Ok(operations)
+}
+
+/// operation を開始時刻順に並べ、その job 番号の列を染色体とします。
+/// semi-active デコードすると、元のスケジュール (を左に詰めたもの) が得られます。
+pub fn chromosome_from_operations(
+    operations: &[ScheduledOperation],
+) -> types::Chromosome {
+    let mut sorted: Vec<&ScheduledOperation> = operations.iter().collect();
+    sorted.sort_by_key(|op| (op.start, op.end, op.job, op.operation));
+    sorted.iter().map(|op| op.job).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::decoder::schedule::Schedule;
+    use crate::optimization::decoder::score::ScoreCalculator;
+    use crate::optimization::preprocess::runner::JobMaster;
+    use crate::optimization::test_fixtures::sample_job_master;
+
+    fn sample_solution() -> Solution {
+        let job_master: JobMaster = sample_job_master();
+        let calculator = ScoreCalculator::new(job_master.clone());
+        let chromosome: types::Chromosome = vec![1, 0, 2, 0, 1, 2];
+        Solution {
+            evaluation: calculator.evaluate(&chromosome),
+            schedule: calculator.decode_schedule(&chromosome),
+            chromosome,
+            problem: "job-shop",
+            job_master,
+        }
+    }
+
+    #[test]
+    fn test_solution_format_from_path() {
+        for (path, format) in [
+            ("best.json", SolutionFormat::Json),
+            ("out/BEST.CSV", SolutionFormat::Csv),
+            ("best.txt", SolutionFormat::Text),
+            ("best", SolutionFormat::Text),
+        ] {
+            assert_eq!(SolutionFormat::from_path(OsStr::new(path)), format);
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let solution = sample_solution();
+        let options = SolveOptions::default();
+        let solution_file =
+            SolutionFile::new("ft06.txt", &options, &solution).unwrap();
+        assert_eq!(solution_file.makespan, solution.evaluation.makespan);
+        assert_eq!(solution_file.config["seed"], 42);
+        assert_eq!(solution_file.config["decoder"], "semi-active");
+        assert_eq!(solution_file.config["annealing"]["steps"], 2_000_000);
+        // 使わないアルゴリズムの設定は記録しない
+        assert!(solution_file.config.get("genetic").is_none());
+        assert!(solution_file.config.get("tabu").is_none());
+
+        let options = SolveOptions {
+            algorithm: "bb".to_string(),
+            ..SolveOptions::default()
+        };
+        let bb_file =
+            SolutionFile::new("ft06.txt", &options, &solution).unwrap();
+        assert!(bb_file.config.get("branch_and_bound").is_some());
+        assert!(bb_file.config.get("annealing").is_none());
+        assert!(bb_file.config.get("neighborhood").is_none());
+
+        let text: String = serde_json::to_string(&solution_file).unwrap();
+        let restored: SolutionFile = serde_json::from_str(&text).unwrap();
+        assert_eq!(restored, solution_file);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let solution = sample_solution();
+        let mut buffer: Vec<u8> = Vec::new();
+        write_operations_csv(&mut buffer, &solution.schedule.operations)
+            .unwrap();
+        let text: String = String::from_utf8(buffer.clone()).unwrap();
+        assert!(text.starts_with("job,operation,actor_id,start,end,setup\n"));
+
+        let operations = read_operations_csv(buffer.as_slice()).unwrap();
+        assert_eq!(operations, solution.schedule.operations);
+
+        // setup 列のない CSV も読み込める
+        let legacy: &str = "job,operation,actor_id,start,end\n1,0,1,0,2\n";
+        let operations = read_operations_csv(legacy.as_bytes()).unwrap();
+        assert_eq!(operations[0].setup, 0);
+        assert_eq!(operations[0].end, 2);
+    }
+
+    #[test]
+    fn test_chromosome_from_operations_reproduces_schedule() {
+        let solution = sample_solution();
+        let chromosome =
+            chromosome_from_operations(&solution.schedule.operations);
+        let calculator = ScoreCalculator::new(sample_job_master());
+        let schedule: Schedule = calculator.decode_schedule(&chromosome);
+        assert_eq!(schedule.makespan, solution.schedule.makespan);
+        for job in 0..3 {
+            assert_eq!(
+                schedule.job_operations(job),
+                solution.schedule.job_operations(job)
+            );
+        }
+    }
+}